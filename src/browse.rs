@@ -0,0 +1,288 @@
+//! Thumbnail strip listing the images in a folder. Drag a thumbnail onto the canvas to add it.
+
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{LoadState, RenderAssetUsages},
+    diagnostic::FrameCount,
+    prelude::*,
+    window::SystemCursorIcon,
+};
+
+use crate::{
+    canvas::{DropImageFrame, ImageFrame},
+    cursor::{CursorSource, Cursors},
+    despawn::SafeDespawn,
+    modal,
+    observe_component::Observe,
+    redraw::Redraw,
+};
+
+/// Extensions of the image formats enabled in bevy.
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "webp"];
+
+/// Maximum width and height of a thumbnail in pixels.
+const THUMBNAIL_SIZE: u32 = 96;
+
+/// Maximum number of full-size images loaded at once to make thumbnails.
+const MAX_LOADING: usize = 4;
+
+/// Horizontal scroll distance of the strip per mouse wheel step.
+const SCROLL_STEP: f32 = 50.0;
+
+pub struct BrowsePlugin;
+
+impl Plugin for BrowsePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (load_thumbnails, finish_thumbnails).chain())
+            .add_observer(on_drop_thumbnail);
+    }
+}
+
+/// Row at the bottom of the window listing the browsed images.
+#[derive(Component)]
+pub struct ThumbnailStrip;
+
+/// Scrollable row of [`Thumbnail`]s in the [`ThumbnailStrip`].
+#[derive(Component)]
+struct ThumbnailRow;
+
+#[derive(Component)]
+struct Thumbnail {
+    path: PathBuf,
+}
+
+/// Full-size image being loaded to make the thumbnail from. Dropped once the thumbnail is made.
+#[derive(Component)]
+struct LoadingThumbnail(Handle<Image>);
+
+/// Returns the image files directly in `dir`, sorted by name.
+pub fn image_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = std::fs::read_dir(dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_file() && is_image_file(path))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|image_extension| extension.eq_ignore_ascii_case(image_extension))
+        })
+}
+
+/// One-shot system to pick a folder and show its images in the thumbnail strip,
+/// replacing the current one.
+pub fn browse_folder(
+    mut commands: Commands,
+    strip: Query<Entity, With<ThumbnailStrip>>,
+    frame: Res<FrameCount>,
+) {
+    let dir = rfd::FileDialog::new().pick_folder();
+    commands.queue(modal::block_native_dialog(&frame));
+    let Some(dir) = dir else {
+        return;
+    };
+    let files = match image_files(&dir) {
+        Ok(files) => files,
+        Err(error) => {
+            warn!("Failed to read {}: {error}", dir.display());
+            return;
+        }
+    };
+    info!("Found {} images in {}", files.len(), dir.display());
+
+    for entity in &strip {
+        commands.safe_despawn(entity);
+    }
+
+    let strip = commands
+        .spawn((
+            Name::new("ThumbnailStrip"),
+            ThumbnailStrip,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Px(THUMBNAIL_SIZE as f32 + 20.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                column_gap: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            children![(
+                Text::new("Close"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                Observe::new(
+                    |mut trigger: Trigger<Pointer<Click>>,
+                     mut commands: Commands,
+                     strip: Single<Entity, With<ThumbnailStrip>>| {
+                        trigger.propagate(false);
+                        commands.safe_despawn(*strip);
+                    },
+                ),
+            )],
+        ))
+        .id();
+
+    let row = commands
+        .spawn((
+            ChildOf(strip),
+            ThumbnailRow,
+            Node {
+                flex_grow: 1.0,
+                height: Val::Percent(100.0),
+                column_gap: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                overflow: Overflow::scroll_x(),
+                ..default()
+            },
+            Observe::new(
+                |mut trigger: Trigger<Pointer<Scroll>>,
+                 mut row: Single<&mut ScrollPosition, With<ThumbnailRow>>| {
+                    trigger.propagate(false);
+                    row.offset_x -= trigger.y * SCROLL_STEP;
+                },
+            ),
+        ))
+        .id();
+
+    for path in files {
+        commands.spawn((
+            ChildOf(row),
+            Name::new(format!("Thumbnail {}", path.display())),
+            Thumbnail { path },
+            Node {
+                width: Val::Px(THUMBNAIL_SIZE as f32),
+                height: Val::Px(THUMBNAIL_SIZE as f32),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.1)),
+            Observe::new(
+                |_trigger: Trigger<Pointer<DragStart>>, mut cursors: ResMut<Cursors>| {
+                    cursors.set(CursorSource::Drag, SystemCursorIcon::Grabbing);
+                },
+            ),
+            Observe::new(
+                |_trigger: Trigger<Pointer<DragEnd>>, mut cursors: ResMut<Cursors>| {
+                    cursors.clear(CursorSource::Drag);
+                },
+            ),
+        ));
+    }
+}
+
+/// Starts loading the images of pending thumbnails, a few at a time.
+fn load_thumbnails(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    pending: Query<(Entity, &Thumbnail), (Without<LoadingThumbnail>, Without<ImageNode>)>,
+    loading: Query<(), With<LoadingThumbnail>>,
+    mut redraw: ResMut<Redraw>,
+) {
+    if pending.is_empty() && loading.is_empty() {
+        return;
+    }
+    // Keep updating until all thumbnails are made, as loading doesn't wake up `desktop_app()`
+    redraw.request_redraw_once();
+
+    let slots = MAX_LOADING.saturating_sub(loading.iter().count());
+    for (entity, thumbnail) in pending.iter().take(slots) {
+        commands
+            .entity(entity)
+            .insert(LoadingThumbnail(assets.load(thumbnail.path.clone())));
+    }
+}
+
+/// Replaces loaded images with downscaled thumbnails, so that full-size images are not kept.
+fn finish_thumbnails(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut loading: Query<(Entity, &LoadingThumbnail, &mut Node)>,
+) {
+    for (entity, loading, mut node) in &mut loading {
+        if matches!(
+            asset_server.get_load_state(&loading.0),
+            Some(LoadState::Failed(..)),
+        ) {
+            commands.safe_despawn(entity);
+            continue;
+        }
+        let Some(image) = images.get(&loading.0) else {
+            continue;
+        };
+
+        let Some(thumbnail) = downscale(image) else {
+            warn!(
+                "Unsupported image format: {:?}",
+                image.texture_descriptor.format
+            );
+            commands.safe_despawn(entity);
+            continue;
+        };
+        node.width = Val::Px(thumbnail.width() as f32);
+        node.height = Val::Px(thumbnail.height() as f32);
+
+        commands
+            .entity(entity)
+            .remove::<(LoadingThumbnail, BackgroundColor)>()
+            .insert(ImageNode::new(images.add(thumbnail)));
+    }
+}
+
+/// Returns `image` scaled down to fit in [`THUMBNAIL_SIZE`], keeping the aspect ratio.
+fn downscale(image: &Image) -> Option<Image> {
+    let is_srgb = image.texture_descriptor.format.is_srgb();
+    let dynamic = image.clone().try_into_dynamic().ok()?;
+    Some(Image::from_dynamic(
+        dynamic.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+        is_srgb,
+        RenderAssetUsages::RENDER_WORLD,
+    ))
+}
+
+/// Adds the image of a thumbnail dropped onto the canvas or a frame at the drop point.
+fn on_drop_thumbnail(
+    mut trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    thumbnails: Query<&Thumbnail>,
+    drop_targets: Query<(), Or<(With<Window>, With<ImageFrame>)>>,
+    mut redraw: ResMut<Redraw>,
+) {
+    let Ok(thumbnail) = thumbnails.get(trigger.dropped) else {
+        return;
+    };
+    if !drop_targets.contains(trigger.target()) {
+        return;
+    }
+
+    trigger.propagate(false);
+
+    commands.spawn(DropImageFrame(thumbnail.path.clone()));
+
+    redraw.request_redraw_once();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_image_file() {
+        assert!(is_image_file(Path::new("a/b.png")));
+        assert!(is_image_file(Path::new("b.JPG")));
+        assert!(!is_image_file(Path::new("b.txt")));
+        assert!(!is_image_file(Path::new("png")));
+    }
+}
@@ -0,0 +1,848 @@
+//! One-shot systems to rearrange [`ImageFrame`]s.
+//! Use `Commands::run_system_cached_with` to run them with the target entities. Each run is
+//! recorded as one undo entry.
+
+use bevy::prelude::*;
+
+use super::{
+    ImageFrame,
+    undo::{EditAction, UndoStack},
+};
+
+/// Settings for the arrange actions.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ArrangeSettings {
+    /// Gap between frames lined up by [`stack_horizontal`], [`stack_vertical`] and
+    /// [`arrange_grid`].
+    pub stack_gap: f32,
+    /// Size applied by [`set_size`].
+    pub frame_size: FrameSize,
+    /// Whether [`set_size`] keeps each frame's aspect ratio, fitting it into the given size.
+    pub keep_aspect_ratio: bool,
+    /// Number of columns of [`arrange_grid`], or 0 for a grid as close to square as possible.
+    pub grid_columns: usize,
+    /// Sizing of the cells of [`arrange_grid`].
+    pub grid_cells: GridCells,
+    /// Placement of frames in cells larger than them.
+    pub cell_align: CellAlign,
+}
+
+impl Default for ArrangeSettings {
+    fn default() -> Self {
+        Self {
+            stack_gap: 10.0,
+            frame_size: FrameSize::Fixed(Vec2::splat(64.0)),
+            keep_aspect_ratio: true,
+            grid_columns: 0,
+            grid_cells: GridCells::Natural,
+            cell_align: CellAlign::Center,
+        }
+    }
+}
+
+/// Size applied to frames by [`set_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum FrameSize {
+    /// Resize to the given width and height.
+    Fixed(Vec2),
+    /// Scale the current size by the factor.
+    Scale(f32),
+}
+
+/// Sizing of the cells of [`arrange_grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum GridCells {
+    /// Columns as wide and rows as tall as their largest frame. Frames keep their size.
+    Natural,
+    /// Cells of the given size. Each frame is resized to fit its cell, keeping the aspect ratio
+    /// of its image.
+    Uniform(Vec2),
+}
+
+/// Placement of a frame in a grid cell larger than it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum CellAlign {
+    #[default]
+    Center,
+    Top,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+type ArrangeFrames<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static ImageFrame,
+        &'static mut Sprite,
+        &'static mut Transform,
+    ),
+>;
+
+/// Placement, size and flip of a frame, as changed by the arrange actions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FramePose {
+    transform: Transform,
+    size: Option<Vec2>,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl FramePose {
+    fn of(sprite: &Sprite, transform: &Transform) -> Self {
+        Self {
+            transform: *transform,
+            size: sprite.custom_size,
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
+        }
+    }
+
+    fn apply(&self, world: &mut World, entity: Entity) {
+        let Ok(mut frame) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut transform) = frame.get_mut::<Transform>() {
+            *transform = self.transform;
+        }
+        if let Some(mut sprite) = frame.get_mut::<Sprite>() {
+            sprite.custom_size = self.size;
+            sprite.flip_x = self.flip_x;
+            sprite.flip_y = self.flip_y;
+        }
+    }
+}
+
+/// Frames changed by one arrange action, with their previous poses.
+struct ArrangeEdit {
+    name: &'static str,
+    frames: Vec<(Entity, FramePose, FramePose)>,
+}
+
+impl EditAction for ArrangeEdit {
+    fn describe(&self) -> String {
+        format!("{} {} frame(s)", self.name, self.frames.len())
+    }
+
+    fn thumbnail_frame(&self) -> Option<Entity> {
+        self.frames.first().map(|(entity, ..)| *entity)
+    }
+
+    fn undo(&self, world: &mut World) {
+        for (entity, before, _) in &self.frames {
+            before.apply(world, *entity);
+        }
+    }
+
+    fn redo(&self, world: &mut World) {
+        for (entity, _, after) in &self.frames {
+            after.apply(world, *entity);
+        }
+    }
+}
+
+/// Runs `arrange` on `frames`, then pushes the changes to `target` frames as one undo entry
+/// described by `name`.
+fn record(
+    name: &'static str,
+    target: &[Entity],
+    frames: &mut ArrangeFrames,
+    undo_stack: &mut UndoStack,
+    arrange: impl FnOnce(&mut ArrangeFrames),
+) {
+    let before = frames
+        .iter_many(target)
+        .map(|(entity, _, sprite, transform)| (entity, FramePose::of(sprite, transform)))
+        .collect::<Vec<_>>();
+
+    arrange(frames);
+
+    let changed = before
+        .into_iter()
+        .filter_map(|(entity, before)| {
+            let (_, _, sprite, transform) = frames.get(entity).ok()?;
+            let after = FramePose::of(sprite, transform);
+            (after != before).then_some((entity, before, after))
+        })
+        .collect::<Vec<_>>();
+    if !changed.is_empty() {
+        undo_stack.push(ArrangeEdit {
+            name,
+            frames: changed,
+        });
+    }
+}
+
+/// Pushes the move of `entity` from `before` to `after`, e.g. by a drag, as an undo entry.
+pub fn record_move(
+    undo_stack: &mut UndoStack,
+    entity: Entity,
+    sprite: &Sprite,
+    before: Transform,
+    after: Transform,
+) {
+    if before == after {
+        return;
+    }
+    let pose = FramePose::of(sprite, &before);
+    undo_stack.push(ArrangeEdit {
+        name: "move",
+        frames: vec![(
+            entity,
+            pose,
+            FramePose {
+                transform: after,
+                ..pose
+            },
+        )],
+    });
+}
+
+/// Lines up `target` frames in a single row (left to right), centered on their centroid.
+pub fn stack_horizontal(
+    In(target): In<Vec<Entity>>,
+    settings: Res<ArrangeSettings>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("stack", &target, &mut frames, &mut undo_stack, |frames| {
+        stack(&target, Axis::Horizontal, settings.stack_gap, frames);
+    });
+}
+
+/// Lines up `target` frames in a single column (top to bottom), centered on their centroid.
+pub fn stack_vertical(
+    In(target): In<Vec<Entity>>,
+    settings: Res<ArrangeSettings>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("stack", &target, &mut frames, &mut undo_stack, |frames| {
+        stack(&target, Axis::Vertical, settings.stack_gap, frames);
+    });
+}
+
+fn stack(target: &[Entity], axis: Axis, gap: f32, frames: &mut ArrangeFrames) {
+    let items = target
+        .iter()
+        .filter_map(|&entity| {
+            let (_, _, sprite, transform) = frames.get(entity).ok()?;
+            Some((
+                entity,
+                transform.translation.xy(),
+                frame_extent(sprite, transform),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    if items.len() < 2 {
+        return;
+    }
+
+    let positions = stack_positions(
+        &items
+            .iter()
+            .map(|&(_, center, extent)| (center, extent))
+            .collect::<Vec<_>>(),
+        axis,
+        gap,
+    );
+
+    for ((entity, ..), position) in items.into_iter().zip(positions) {
+        if let Ok((.., mut transform)) = frames.get_mut(entity) {
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Lays out `target` frames in a grid, centered on their centroid. Frames fill the grid row
+/// by row in the reading order of their current positions, so that it keeps their rough
+/// arrangement. See [`ArrangeSettings::grid_cells`].
+pub fn arrange_grid(
+    In(target): In<Vec<Entity>>,
+    settings: Res<ArrangeSettings>,
+    images: Res<Assets<Image>>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("arrange", &target, &mut frames, &mut undo_stack, |frames| {
+        grid(
+            &target,
+            &settings,
+            settings.grid_cells,
+            settings.cell_align,
+            &images,
+            frames,
+        );
+    });
+}
+
+/// Lays out `target` frames like [`arrange_grid`] in uniform cells placing frames by `align`,
+/// whichever [`ArrangeSettings::grid_cells`] is. Cells are of the uniform size of the settings,
+/// or squares fitting the largest frame.
+pub fn arrange_uniform_grid(
+    In((target, align)): In<(Vec<Entity>, CellAlign)>,
+    settings: Res<ArrangeSettings>,
+    images: Res<Assets<Image>>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    let cell = match settings.grid_cells {
+        GridCells::Uniform(cell) => cell,
+        GridCells::Natural => Vec2::splat(
+            frames
+                .iter_many(&target)
+                .map(|(_, _, sprite, transform)| frame_extent(sprite, transform).max_element())
+                .fold(0.0, f32::max),
+        ),
+    };
+    record("arrange", &target, &mut frames, &mut undo_stack, |frames| {
+        grid(
+            &target,
+            &settings,
+            GridCells::Uniform(cell),
+            align,
+            &images,
+            frames,
+        );
+    });
+}
+
+fn grid(
+    target: &[Entity],
+    settings: &ArrangeSettings,
+    cells: GridCells,
+    align: CellAlign,
+    images: &Assets<Image>,
+    frames: &mut ArrangeFrames,
+) {
+    let items = frames
+        .iter_many(target)
+        .map(|(entity, .., transform)| (entity, transform.translation.xy()))
+        .collect::<Vec<_>>();
+    if items.len() < 2 {
+        return;
+    }
+    let columns = match settings.grid_columns {
+        0 => (items.len() as f32).sqrt().ceil() as usize,
+        columns => columns,
+    };
+    let centers = items.iter().map(|&(_, center)| center).collect::<Vec<_>>();
+    let target = reading_order(&centers, columns)
+        .into_iter()
+        .map(|i| items[i].0)
+        .collect::<Vec<_>>();
+
+    let mut extents = vec![];
+    let mut iter = frames.iter_many_mut(&target);
+    while let Some((_, image_frame, mut sprite, transform)) = iter.fetch_next() {
+        if let GridCells::Uniform(cell) = cells {
+            // Fit the native aspect ratio, which a frame resized without keeping it has lost
+            let native = images
+                .get(&image_frame.0)
+                .map(Image::size_f32)
+                .or(sprite.custom_size)
+                .unwrap_or(Vec2::ZERO);
+            let rotation = transform.rotation.to_euler(EulerRot::XYZ).2;
+            sprite.custom_size = Some(fit_size(native, rotation, cell) / transform.scale.xy());
+        }
+        extents.push(frame_extent(&sprite, &transform));
+    }
+    let centroid = centers.iter().sum::<Vec2>() / centers.len() as f32;
+
+    let cell = match cells {
+        GridCells::Natural => None,
+        GridCells::Uniform(cell) => Some(cell),
+    };
+    let positions = grid_positions(&extents, columns, cell, align, settings.stack_gap);
+
+    let mut iter = frames.iter_many_mut(&target);
+    let mut positions = positions.into_iter();
+    while let Some((.., mut transform)) = iter.fetch_next()
+        && let Some(position) = positions.next()
+    {
+        transform.translation = (centroid + position).extend(transform.translation.z);
+    }
+    info!("Arranged {} frame(s) in {columns} column(s)", extents.len());
+}
+
+/// Mirrors `target` frames about the vertical axis through the center of their bounding box,
+/// flipping each sprite horizontally.
+pub fn mirror_horizontal(
+    In(target): In<Vec<Entity>>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("mirror", &target, &mut frames, &mut undo_stack, |frames| {
+        mirror(&target, Axis::Horizontal, frames);
+    });
+}
+
+/// Mirrors `target` frames about the horizontal axis through the center of their bounding box,
+/// flipping each sprite vertically.
+pub fn mirror_vertical(
+    In(target): In<Vec<Entity>>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("mirror", &target, &mut frames, &mut undo_stack, |frames| {
+        mirror(&target, Axis::Vertical, frames);
+    });
+}
+
+fn mirror(target: &[Entity], axis: Axis, frames: &mut ArrangeFrames) {
+    let Some(bounds) = target
+        .iter()
+        .filter_map(|&entity| {
+            let (_, _, sprite, transform) = frames.get(entity).ok()?;
+            Some(Rect::from_center_size(
+                transform.translation.xy(),
+                frame_extent(sprite, transform),
+            ))
+        })
+        .reduce(|a, b| a.union(b))
+    else {
+        return;
+    };
+    let center = bounds.center();
+
+    let mut frames = frames.iter_many_mut(target);
+    while let Some((_, _, mut sprite, mut transform)) = frames.fetch_next() {
+        match axis {
+            Axis::Horizontal => {
+                transform.translation.x = 2.0 * center.x - transform.translation.x;
+                sprite.flip_x = !sprite.flip_x;
+            }
+            Axis::Vertical => {
+                transform.translation.y = 2.0 * center.y - transform.translation.y;
+                sprite.flip_y = !sprite.flip_y;
+            }
+        }
+        // A reflected frame rotates the other way
+        transform.rotation = transform.rotation.inverse();
+    }
+}
+
+/// Applies [`ArrangeSettings::frame_size`] to all `target` frames at once.
+pub fn set_size(
+    In(target): In<Vec<Entity>>,
+    settings: Res<ArrangeSettings>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("resize", &target, &mut frames, &mut undo_stack, |frames| {
+        let mut frames = frames.iter_many_mut(&target);
+        while let Some((_, _, mut sprite, _)) = frames.fetch_next() {
+            let Some(size) = sprite.custom_size.as_mut() else {
+                continue;
+            };
+            *size = resized(*size, settings.frame_size, settings.keep_aspect_ratio);
+        }
+    });
+}
+
+/// Returns `degrees` wrapped into `[0, 360)`.
+pub fn normalize_degrees(degrees: f32) -> f32 {
+    let degrees = degrees.rem_euclid(360.0);
+    // Tiny negative angles round up to 360
+    if degrees >= 360.0 { 0.0 } else { degrees }
+}
+
+/// Rotates `target` frames to `degrees` counterclockwise, which may be negative or beyond a
+/// full turn.
+pub fn set_rotation(
+    In((target, degrees)): In<(Vec<Entity>, f32)>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    let degrees = normalize_degrees(degrees);
+    record("rotate", &target, &mut frames, &mut undo_stack, |frames| {
+        let mut frames = frames.iter_many_mut(&target);
+        while let Some((.., mut transform)) = frames.fetch_next() {
+            transform.rotation = Quat::from_rotation_z(degrees.to_radians());
+        }
+    });
+    info!("Rotated {} frame(s) to {degrees} deg", target.len());
+}
+
+/// Resizes `target` frames to `scale` times the native size of their images, so that frames
+/// imported from mixed-resolution images share the same pixel ratio.
+pub fn actual_size(
+    In((target, scale)): In<(Vec<Entity>, f32)>,
+    images: Res<Assets<Image>>,
+    mut frames: ArrangeFrames,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    record("resize", &target, &mut frames, &mut undo_stack, |frames| {
+        let mut frames = frames.iter_many_mut(&target);
+        while let Some((_, image_frame, mut sprite, _)) = frames.fetch_next() {
+            let Some(image) = images.get(&image_frame.0) else {
+                continue;
+            };
+            let size = image.size_f32() * scale;
+            sprite.custom_size = Some(size);
+            info!(
+                "Resized frame to {}x{} ({}%)",
+                size.x,
+                size.y,
+                scale * 100.0
+            );
+        }
+    });
+}
+
+fn resized(size: Vec2, frame_size: FrameSize, keep_aspect_ratio: bool) -> Vec2 {
+    match frame_size {
+        FrameSize::Fixed(fixed) if keep_aspect_ratio && size.cmpgt(Vec2::ZERO).all() => {
+            size * (fixed / size).min_element()
+        }
+        FrameSize::Fixed(fixed) => fixed,
+        FrameSize::Scale(factor) => size * factor,
+    }
+}
+
+/// Size of the axis-aligned bounding box of a (possibly rotated) frame.
+fn frame_extent(sprite: &Sprite, transform: &Transform) -> Vec2 {
+    let size = sprite.custom_size.unwrap_or(Vec2::ZERO) * transform.scale.xy();
+    let z_angle = transform.rotation.to_euler(EulerRot::XYZ).2;
+    rotated_extent(size, z_angle)
+}
+
+fn rotated_extent(size: Vec2, angle: f32) -> Vec2 {
+    let rotation = Mat2::from_angle(angle);
+    rotation.x_axis.abs() * size.x + rotation.y_axis.abs() * size.y
+}
+
+/// Returns `size` scaled so that it fits into `cell` when rotated by `angle`.
+fn fit_size(size: Vec2, angle: f32, cell: Vec2) -> Vec2 {
+    let extent = rotated_extent(size, angle);
+    if extent.cmple(Vec2::ZERO).any() {
+        return size;
+    }
+    size * (cell / extent).min_element()
+}
+
+/// Computes centers for frames of `extents` laid out row by row in `columns` columns, relative
+/// to the center of the grid. Cells are of size `cell`, or as large as the largest frame in
+/// their column and row if `None`.
+fn grid_positions(
+    extents: &[Vec2],
+    columns: usize,
+    cell: Option<Vec2>,
+    align: CellAlign,
+    gap: f32,
+) -> Vec<Vec2> {
+    let columns = columns.clamp(1, extents.len().max(1));
+    let rows = extents.len().div_ceil(columns);
+
+    let mut widths = vec![cell.map_or(0.0, |cell| cell.x); columns];
+    let mut heights = vec![cell.map_or(0.0, |cell| cell.y); rows];
+    if cell.is_none() {
+        for (i, extent) in extents.iter().enumerate() {
+            widths[i % columns] = widths[i % columns].max(extent.x);
+            heights[i / columns] = heights[i / columns].max(extent.y);
+        }
+    }
+
+    // Offsets of the left and top edges of each column and row
+    let edges = |sizes: &[f32]| {
+        let total = sizes.iter().sum::<f32>() + gap * (sizes.len() - 1) as f32;
+        let mut edge = -total / 2.0;
+        sizes
+            .iter()
+            .map(|size| {
+                let start = edge;
+                edge += size + gap;
+                start
+            })
+            .collect::<Vec<_>>()
+    };
+    let lefts = edges(&widths);
+    let tops = edges(&heights);
+
+    extents
+        .iter()
+        .enumerate()
+        .map(|(i, extent)| {
+            let (column, row) = (i % columns, i / columns);
+            let x = lefts[column] + widths[column] / 2.0;
+            let y = match align {
+                CellAlign::Center => tops[row] + heights[row] / 2.0,
+                CellAlign::Top => tops[row] + extent.y / 2.0,
+            };
+            // Rows go down
+            Vec2::new(x, -y)
+        })
+        .collect()
+}
+
+/// Returns indices of `centers` in reading order for a grid of `columns` columns: the topmost
+/// frames form the first row, ordered left to right, and so on.
+fn reading_order(centers: &[Vec2], columns: usize) -> Vec<usize> {
+    let mut order = (0..centers.len()).collect::<Vec<_>>();
+    // Top to bottom
+    order.sort_by(|&a, &b| centers[b].y.total_cmp(&centers[a].y));
+    for row in order.chunks_mut(columns.max(1)) {
+        row.sort_by(|&a, &b| centers[a].x.total_cmp(&centers[b].x));
+    }
+    order
+}
+
+/// Computes new centers for `items` (center, extent) lined up along `axis`.
+/// Items keep their current order along the axis, and the line is centered on their centroid.
+/// The returned positions are in the same order as `items`.
+fn stack_positions(items: &[(Vec2, Vec2)], axis: Axis, gap: f32) -> Vec<Vec2> {
+    let centroid = items.iter().map(|(center, _)| *center).sum::<Vec2>() / items.len() as f32;
+
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    match axis {
+        Axis::Horizontal => order.sort_by(|&a, &b| items[a].0.x.total_cmp(&items[b].0.x)),
+        // Top to bottom
+        Axis::Vertical => order.sort_by(|&a, &b| items[b].0.y.total_cmp(&items[a].0.y)),
+    }
+
+    let along = |v: Vec2| match axis {
+        Axis::Horizontal => v.x,
+        Axis::Vertical => v.y,
+    };
+
+    let total = items.iter().map(|(_, extent)| along(*extent)).sum::<f32>()
+        + gap * (items.len() - 1) as f32;
+
+    let mut positions = vec![centroid; items.len()];
+    let mut cursor = -total / 2.0;
+    for i in order {
+        let extent = along(items[i].1);
+        let offset = cursor + extent / 2.0;
+        match axis {
+            Axis::Horizontal => positions[i].x += offset,
+            Axis::Vertical => positions[i].y -= offset,
+        }
+        cursor += extent + gap;
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::canvas::undo;
+
+    use super::*;
+
+    #[test]
+    fn test_stack_positions() {
+        let items = [
+            (Vec2::new(100.0, 0.0), Vec2::new(20.0, 10.0)),
+            (Vec2::new(0.0, 30.0), Vec2::new(10.0, 10.0)),
+        ];
+
+        let positions = stack_positions(&items, Axis::Horizontal, 10.0);
+        // Total width is 10 + 10 + 20 = 40, centered on the centroid (50, 15)
+        assert_eq!(positions[1], Vec2::new(35.0, 15.0));
+        assert_eq!(positions[0], Vec2::new(60.0, 15.0));
+
+        let positions = stack_positions(&items, Axis::Vertical, 10.0);
+        // The second item is above the first, so it stays on top
+        assert_eq!(positions[1], Vec2::new(50.0, 25.0));
+        assert_eq!(positions[0], Vec2::new(50.0, 5.0));
+    }
+
+    #[test]
+    fn test_grid_positions() {
+        let extents = [
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 30.0),
+            Vec2::new(10.0, 10.0),
+        ];
+
+        // Columns of 20 and 10 and rows of 30 and 10 with a gap of 10
+        let positions = grid_positions(&extents, 2, None, CellAlign::Center, 10.0);
+        assert_eq!(
+            positions,
+            vec![
+                Vec2::new(-10.0, 10.0),
+                Vec2::new(15.0, 10.0),
+                Vec2::new(-10.0, -20.0),
+            ]
+        );
+
+        // Uniform cells of 40x40, with the first frame at the top of its cell
+        let positions = grid_positions(&extents, 2, Some(Vec2::splat(40.0)), CellAlign::Top, 0.0);
+        assert_eq!(positions[0], Vec2::new(-20.0, 35.0));
+        assert_eq!(positions[1], Vec2::new(20.0, 25.0));
+        assert_eq!(positions[2], Vec2::new(-20.0, -5.0));
+    }
+
+    #[test]
+    fn test_reading_order() {
+        // A rough 2x2 grid given out of order
+        let centers = [
+            Vec2::new(105.0, -2.0),
+            Vec2::new(0.0, -100.0),
+            Vec2::new(100.0, -95.0),
+            Vec2::new(-3.0, 0.0),
+        ];
+        assert_eq!(reading_order(&centers, 2), vec![3, 0, 1, 2]);
+        assert_eq!(reading_order(&centers, 4), vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_fit_size() {
+        let cell = Vec2::splat(40.0);
+        // Letterboxed, keeping the aspect ratio
+        assert_eq!(
+            fit_size(Vec2::new(80.0, 20.0), 0.0, cell),
+            Vec2::new(40.0, 10.0)
+        );
+        assert_eq!(
+            fit_size(Vec2::new(5.0, 10.0), 0.0, cell),
+            Vec2::new(20.0, 40.0)
+        );
+        // Rotated by 90 degrees, the height fills the width of the cell
+        assert!(
+            fit_size(Vec2::new(10.0, 20.0), FRAC_PI_2, Vec2::new(10.0, 40.0))
+                .abs_diff_eq(Vec2::new(5.0, 10.0), 1e-4)
+        );
+    }
+
+    #[test]
+    fn test_set_rotation() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+        let frame = world
+            .spawn((
+                ImageFrame(Handle::default()),
+                Sprite::default(),
+                Transform::default(),
+            ))
+            .id();
+
+        world
+            .run_system_cached_with(set_rotation, (vec![frame], 370.0))
+            .unwrap();
+        let angle = world
+            .get::<Transform>(frame)
+            .unwrap()
+            .rotation
+            .to_euler(EulerRot::XYZ)
+            .2;
+        assert!((normalize_degrees(angle.to_degrees()) - 10.0).abs() < 1e-3);
+
+        assert_eq!(normalize_degrees(370.0), 10.0);
+        assert_eq!(normalize_degrees(-30.0), 330.0);
+        assert_eq!(normalize_degrees(-1e-9), 0.0);
+    }
+
+    #[test]
+    fn test_resized() {
+        let size = Vec2::new(40.0, 20.0);
+        let fixed = FrameSize::Fixed(Vec2::splat(10.0));
+
+        assert_eq!(resized(size, fixed, false), Vec2::new(10.0, 10.0));
+        assert_eq!(resized(size, fixed, true), Vec2::new(10.0, 5.0));
+        assert_eq!(
+            resized(size, FrameSize::Scale(0.5), true),
+            Vec2::new(20.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn test_mirror_horizontal() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+
+        let sprite = Sprite {
+            custom_size: Some(Vec2::splat(10.0)),
+            ..default()
+        };
+        let a = world
+            .spawn((
+                ImageFrame(Handle::default()),
+                sprite.clone(),
+                Transform::from_xyz(-10.0, 0.0, 0.0),
+            ))
+            .id();
+        let b = world
+            .spawn((
+                ImageFrame(Handle::default()),
+                Sprite {
+                    flip_x: true,
+                    ..sprite
+                },
+                Transform::from_xyz(30.0, 5.0, 0.0),
+            ))
+            .id();
+
+        world
+            .run_system_cached_with(mirror_horizontal, vec![a, b])
+            .unwrap();
+
+        let a = world.entity(a);
+        assert_eq!(
+            a.get::<Transform>().unwrap().translation,
+            Vec3::new(30.0, 0.0, 0.0)
+        );
+        assert!(a.get::<Sprite>().unwrap().flip_x);
+        assert!(!a.get::<Sprite>().unwrap().flip_y);
+
+        let b = world.entity(b);
+        assert_eq!(
+            b.get::<Transform>().unwrap().translation,
+            Vec3::new(-10.0, 5.0, 0.0)
+        );
+        assert!(!b.get::<Sprite>().unwrap().flip_x);
+    }
+
+    #[test]
+    fn test_arrange_undo() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+        world.init_resource::<ArrangeSettings>();
+
+        let sprite = Sprite {
+            custom_size: Some(Vec2::splat(10.0)),
+            ..default()
+        };
+        let frames = [Vec3::new(-50.0, 20.0, 0.1), Vec3::new(40.0, 0.0, 0.2)].map(|translation| {
+            world
+                .spawn((
+                    ImageFrame(Handle::default()),
+                    sprite.clone(),
+                    Transform::from_translation(translation),
+                ))
+                .id()
+        });
+        let translations =
+            |world: &World| frames.map(|frame| world.get::<Transform>(frame).unwrap().translation);
+        let before = translations(&world);
+
+        world
+            .run_system_cached_with(stack_horizontal, frames.to_vec())
+            .unwrap();
+        world
+            .run_system_cached_with(mirror_vertical, frames.to_vec())
+            .unwrap();
+        let after = translations(&world);
+        assert_ne!(after, before);
+        // One entry per batch
+        assert_eq!(world.resource::<UndoStack>().position(), 2);
+
+        undo::undo(&mut world);
+        undo::undo(&mut world);
+        assert_eq!(translations(&world), before);
+        assert!(!world.get::<Sprite>(frames[0]).unwrap().flip_y);
+
+        undo::redo(&mut world);
+        undo::redo(&mut world);
+        assert_eq!(translations(&world), after);
+    }
+}
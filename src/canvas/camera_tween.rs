@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::redraw::Redraw;
+
+const TWEEN_DURATION: Duration = Duration::from_millis(250);
+
+pub struct CameraTweenPlugin;
+
+impl Plugin for CameraTweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, tween_camera);
+    }
+}
+
+/// Animates a camera's [`Transform`] towards `end`. Removed when finished.
+#[derive(Component)]
+pub struct CameraTween {
+    start: Transform,
+    end: Transform,
+    timer: Timer,
+}
+
+impl CameraTween {
+    pub fn new(start: Transform, end: Transform) -> Self {
+        Self {
+            start,
+            end,
+            timer: Timer::new(TWEEN_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+/// Returns the camera transform that fits `rect` (in world space) into the camera's viewport.
+/// `current` is the current camera transform, whose z and rotation are kept.
+pub fn fit_rect(camera: &Camera, current: &Transform, rect: Rect) -> Option<Transform> {
+    let viewport_size = camera.logical_viewport_size()?;
+    let scale = (rect.size() / viewport_size).max_element();
+    if !scale.is_normal() {
+        return None;
+    }
+
+    Some(Transform {
+        translation: rect.center().extend(current.translation.z),
+        scale: Vec3::new(scale, scale, current.scale.z),
+        ..*current
+    })
+}
+
+fn tween_camera(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(Entity, &mut Transform, &mut CameraTween)>,
+    mut redraw: ResMut<Redraw>,
+) {
+    for (entity, mut transform, mut tween) in &mut cameras {
+        tween.timer.tick(time.delta());
+
+        let t = tween.timer.fraction();
+        // smoothstep
+        let t = t * t * (3.0 - 2.0 * t);
+
+        transform.translation = tween.start.translation.lerp(tween.end.translation, t);
+        // Interpolate zoom geometrically so that the zoom speed looks constant
+        transform.scale = tween.start.scale * (tween.end.scale / tween.start.scale).powf(t);
+
+        if tween.timer.finished() {
+            *transform = tween.end;
+            commands.entity(entity).remove::<CameraTween>();
+        } else {
+            redraw.request_redraw_once();
+        }
+    }
+}
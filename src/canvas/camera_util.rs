@@ -86,6 +86,18 @@ impl<'w, 's> CameraTranslator<'w, 's> {
         })
     }
 
+    /// Maps a viewport position to the [`MainCamera`]'s world.
+    pub fn viewport_to_main(&self, viewport: Vec2) -> Result<Vec2> {
+        let main_camera_transform = self
+            .transform_helper
+            .compute_global_transform(self.main_camera.1)?;
+
+        Ok(self
+            .main_camera
+            .0
+            .viewport_to_world_2d(&main_camera_transform, viewport)?)
+    }
+
     pub fn map_rect_to_main(&self, rect: &Rect) -> Result<Rect> {
         let control_camera_transform = self
             .transform_helper
@@ -102,6 +114,23 @@ impl<'w, 's> CameraTranslator<'w, 's> {
             max: affine.transform_point3(rect.max.extend(0.0)).truncate(),
         })
     }
+
+    pub fn map_rect_to_control(&self, rect: &Rect) -> Result<Rect> {
+        let control_camera_transform = self
+            .transform_helper
+            .compute_global_transform(self.control_camera.1)?;
+
+        let main_camera_transform = self
+            .transform_helper
+            .compute_global_transform(self.main_camera.1)?;
+
+        let affine = control_camera_transform.affine() * main_camera_transform.affine().inverse();
+
+        Ok(Rect {
+            min: affine.transform_point3(rect.min.extend(0.0)).truncate(),
+            max: affine.transform_point3(rect.max.extend(0.0)).truncate(),
+        })
+    }
 }
 
 // Since the information on which camera the picking backend used is not included in pointer events,
@@ -125,3 +154,144 @@ impl<'w, 's, C: Component> RenderTargetHelper<'w, 's, C> {
         Ok(id)
     }
 }
+
+/// Keeps the [`ControlCamera`] viewport the same as the [`MainCamera`] one, which
+/// [`CameraTranslator`] relies on. Divergence (e.g. after a window resize race) is warned about
+/// and fixed by copying the [`MainCamera`] viewport.
+pub fn sync_control_viewport(
+    main_camera: Single<&Camera, (With<MainCamera>, Without<ControlCamera>)>,
+    mut control_camera: Single<&mut Camera, With<ControlCamera>>,
+) {
+    let viewport_rect = |camera: &Camera| {
+        camera
+            .viewport
+            .as_ref()
+            .map(|viewport| (viewport.physical_position, viewport.physical_size))
+    };
+
+    let main_size = main_camera.logical_viewport_size();
+    let control_size = control_camera.logical_viewport_size();
+    if main_size == control_size && viewport_rect(&main_camera) == viewport_rect(&control_camera) {
+        return;
+    }
+
+    warn!(
+        "Viewports of MainCamera ({main_size:?}) and ControlCamera ({control_size:?}) diverged, \
+         re-syncing"
+    );
+    // Target info is recomputed for the changed camera
+    control_camera.viewport = main_camera.viewport.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::camera::{ManualTextureViews, Viewport, camera_system},
+        window::{WindowCreated, WindowResized, WindowScaleFactorChanged},
+    };
+
+    use super::*;
+
+    /// Updates the camera viewports as the render app would and re-syncs them.
+    fn update_cameras(world: &mut World) {
+        world.run_system_cached(camera_system).unwrap();
+        world.run_system_cached(sync_control_viewport).unwrap();
+        // Recompute the target info of the re-synced camera
+        world.run_system_cached(camera_system).unwrap();
+    }
+
+    /// Whether a handle drawn around `rect` by the [`ControlCamera`] is at the corners of
+    /// `rect` as shown by the [`MainCamera`].
+    fn handle_aligned(world: &mut World, rect: Rect) -> bool {
+        world
+            .run_system_once(move |translator: CameraTranslator| {
+                let handle = translator.map_rect_to_control(&rect).unwrap();
+                [(rect.min, handle.min), (rect.max, handle.max)]
+                    .into_iter()
+                    .all(|(corner, handle_corner)| {
+                        let transform = GlobalTransform::from_translation(corner.extend(0.0));
+                        let control = translator.to_control(&transform).unwrap();
+                        control.translation.xy().abs_diff_eq(handle_corner, 1e-3)
+                    })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_handles_align_after_resize() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<ManualTextureViews>();
+        let window = world.spawn((Window::default(), PrimaryWindow)).id();
+        // The main viewport leaves room for a panel on the left
+        world.spawn((
+            Camera2d,
+            Camera {
+                viewport: Some(Viewport {
+                    physical_position: UVec2::new(100, 0),
+                    physical_size: UVec2::new(1180, 720),
+                    ..default()
+                }),
+                ..default()
+            },
+            MainCamera,
+        ));
+        world.spawn((
+            Camera2d,
+            Camera {
+                order: 1,
+                ..default()
+            },
+            ControlCamera,
+        ));
+        let rect = Rect::new(-30.0, -20.0, 50.0, 40.0);
+
+        world.run_system_cached(camera_system).unwrap();
+        assert!(!handle_aligned(&mut world, rect));
+        update_cameras(&mut world);
+        assert!(handle_aligned(&mut world, rect));
+
+        // The main viewport is clamped to the smaller window
+        world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set(640.0, 480.0);
+        world.send_event(WindowResized {
+            window,
+            width: 640.0,
+            height: 480.0,
+        });
+        update_cameras(&mut world);
+        assert!(handle_aligned(&mut world, rect));
+    }
+
+    #[test]
+    fn test_sync_control_viewport() {
+        let mut world = World::new();
+        let main_camera = world.spawn((Camera::default(), MainCamera)).id();
+        let control_camera = world.spawn((Camera::default(), ControlCamera)).id();
+
+        // The main viewport changed, e.g. by a resize
+        world.get_mut::<Camera>(main_camera).unwrap().viewport = Some(Viewport {
+            physical_position: UVec2::new(10, 20),
+            physical_size: UVec2::new(640, 480),
+            ..default()
+        });
+        world.run_system_cached(sync_control_viewport).unwrap();
+
+        let viewport = world
+            .get::<Camera>(control_camera)
+            .unwrap()
+            .viewport
+            .clone()
+            .unwrap();
+        assert_eq!(viewport.physical_position, UVec2::new(10, 20));
+        assert_eq!(viewport.physical_size, UVec2::new(640, 480));
+    }
+}
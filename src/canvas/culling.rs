@@ -0,0 +1,104 @@
+//! Culling of frames outside the [`MainCamera`] view, so that per-frame border drawing and
+//! handle updates can skip them. Picking backends don't look at [`Culled`], so culled frames
+//! stay pickable, but the handles of a culled frame are made unpickable since they aren't
+//! moved.
+
+use bevy::prelude::*;
+
+use crate::redraw::Redraw;
+
+use super::{ImageFrame, MainCamera, handle::ControlledSprite};
+
+pub struct CullingPlugin;
+
+impl Plugin for CullingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            cull_frames.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Marks a frame entirely outside the [`MainCamera`] view.
+#[derive(Component)]
+pub struct Culled;
+
+pub fn cull_frames(
+    mut commands: Commands,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    frames: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Sprite,
+            Has<Culled>,
+            Has<ControlledSprite>,
+        ),
+        With<ImageFrame>,
+    >,
+    mut redraw: ResMut<Redraw>,
+) {
+    let Some(view) = view_rect(camera.0, camera.1) else {
+        return;
+    };
+
+    for (entity, transform, sprite, culled, controlled) in &frames {
+        let bounds = frame_bounds(transform, sprite.custom_size.unwrap_or(Vec2::ZERO));
+        let visible = !view.intersect(bounds).is_empty();
+
+        if visible && culled {
+            commands.entity(entity).remove::<Culled>();
+            if controlled {
+                // Handle updates were skipped while culled, so update once more
+                redraw.request_redraw_once();
+            }
+        } else if !visible && !culled {
+            commands.entity(entity).insert(Culled);
+        }
+    }
+}
+
+/// The region of the world visible through `camera`.
+fn view_rect(camera: &Camera, transform: &GlobalTransform) -> Option<Rect> {
+    let viewport_size = camera.logical_viewport_size()?;
+    Some(Rect::from_corners(
+        camera.viewport_to_world_2d(transform, Vec2::ZERO).ok()?,
+        camera.viewport_to_world_2d(transform, viewport_size).ok()?,
+    ))
+}
+
+/// Axis-aligned bounding box of a frame of `size` in world space.
+pub(super) fn frame_bounds(transform: &GlobalTransform, size: Vec2) -> Rect {
+    let half = size / 2.0;
+    [
+        Vec2::new(-half.x, -half.y),
+        Vec2::new(half.x, -half.y),
+        Vec2::new(-half.x, half.y),
+        Vec2::new(half.x, half.y),
+    ]
+    .into_iter()
+    .map(|corner| transform.transform_point(corner.extend(0.0)).xy())
+    .fold(Rect::EMPTY, |bounds, corner| bounds.union_point(corner))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn test_frame_bounds() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(100.0, 50.0, 0.0)
+                .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+                .with_scale(Vec3::splat(2.0)),
+        );
+
+        let bounds = frame_bounds(&transform, Vec2::new(20.0, 10.0));
+        // Rotated by 90 degrees, then scaled by 2
+        assert!(bounds.min.abs_diff_eq(Vec2::new(90.0, 30.0), 1e-3));
+        assert!(bounds.max.abs_diff_eq(Vec2::new(110.0, 70.0), 1e-3));
+    }
+}
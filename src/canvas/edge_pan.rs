@@ -0,0 +1,156 @@
+//! Pans the [`MainCamera`] while a frame is dragged near the window edge, so that frames can
+//! be moved beyond the current view.
+
+use bevy::{
+    picking::pointer::PointerId, platform::collections::HashMap, prelude::*, window::PrimaryWindow,
+};
+
+use crate::redraw::Redraw;
+
+use super::{
+    ImageFrame, MainCamera, camera_tween::CameraTween, grid_snap::Unsnapped, pan::PanBinding,
+};
+
+/// Width of the band along the window edge where panning starts, in logical pixels.
+const EDGE_MARGIN: f32 = 40.0;
+/// Pan speed at the very edge, in logical pixels per second.
+const MAX_PAN_SPEED: f32 = 800.0;
+
+pub struct EdgePanPlugin;
+
+impl Plugin for EdgePanPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EdgePan>()
+            .add_observer(on_drag_frame)
+            .add_observer(on_drag_frame_end)
+            .add_systems(Update, pan_at_window_edge);
+    }
+}
+
+/// A frame being dragged and the last pointer position in the viewport.
+struct FrameDrag {
+    frame: Entity,
+    pointer: Vec2,
+}
+
+/// Frames being dragged, per pointer.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct EdgePan(HashMap<PointerId, FrameDrag>);
+
+fn on_drag_frame(
+    trigger: Trigger<Pointer<Drag>>,
+    mut edge_pan: ResMut<EdgePan>,
+    frames: Query<(), With<ImageFrame>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pan_binding: Res<PanBinding>,
+) {
+    if trigger.button != PointerButton::Primary
+        || !frames.contains(trigger.target())
+        || pan_binding.is_pan(trigger.button, &keyboard_input)
+    {
+        return;
+    }
+
+    edge_pan.insert(
+        trigger.pointer_id,
+        FrameDrag {
+            frame: trigger.target(),
+            pointer: trigger.pointer_location.position,
+        },
+    );
+}
+
+fn on_drag_frame_end(trigger: Trigger<Pointer<DragEnd>>, mut edge_pan: ResMut<EdgePan>) {
+    edge_pan.remove(&trigger.pointer_id);
+}
+
+fn pan_at_window_edge(
+    mut commands: Commands,
+    edge_pan: Res<EdgePan>,
+    time: Res<Time>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(Entity, &mut Transform), With<MainCamera>>,
+    mut frames: Query<
+        (&mut Transform, Option<&mut Unsnapped>),
+        (With<ImageFrame>, Without<MainCamera>),
+    >,
+    mut redraw: ResMut<Redraw>,
+) {
+    // Pointers near different edges pan toward all of them
+    let direction = edge_pan
+        .values()
+        .filter(|drag| frames.contains(drag.frame))
+        .map(|drag| edge_pan_direction(drag.pointer, window.size()))
+        .sum::<Vec2>()
+        .clamp(Vec2::NEG_ONE, Vec2::ONE);
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let (camera_id, mut camera_transform) = camera.into_inner();
+    commands.entity(camera_id).remove::<CameraTween>();
+
+    // Viewport y points down
+    let delta = Vec2::new(direction.x, -direction.y)
+        * MAX_PAN_SPEED
+        * time.delta_secs()
+        * camera_transform.scale.xy();
+    camera_transform.translation += delta.extend(0.0);
+    // Keep the frames under the pointers
+    for drag in edge_pan.values() {
+        let Ok((mut frame_transform, unsnapped)) = frames.get_mut(drag.frame) else {
+            continue;
+        };
+        frame_transform.translation += delta.extend(0.0);
+        if let Some(mut unsnapped) = unsnapped {
+            unsnapped.0 += delta;
+        }
+    }
+
+    // Keep panning while the pointer stays still
+    redraw.request_redraw_once();
+}
+
+/// Returns the pan direction for the pointer at `pointer` in a viewport of `size`.
+/// Each component ranges from -1 to 1 and grows as the pointer approaches the edge.
+fn edge_pan_direction(pointer: Vec2, size: Vec2) -> Vec2 {
+    let axis = |position: f32, size: f32| {
+        if position < EDGE_MARGIN {
+            -(EDGE_MARGIN - position) / EDGE_MARGIN
+        } else if position > size - EDGE_MARGIN {
+            (position - (size - EDGE_MARGIN)) / EDGE_MARGIN
+        } else {
+            0.0
+        }
+    };
+
+    Vec2::new(axis(pointer.x, size.x), axis(pointer.y, size.y)).clamp(Vec2::NEG_ONE, Vec2::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_pan_direction() {
+        let size = Vec2::new(800.0, 600.0);
+
+        assert_eq!(
+            edge_pan_direction(Vec2::new(400.0, 300.0), size),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            edge_pan_direction(Vec2::new(20.0, 300.0), size),
+            Vec2::new(-0.5, 0.0)
+        );
+        assert_eq!(
+            edge_pan_direction(Vec2::new(800.0, 590.0), size),
+            Vec2::new(1.0, 0.75)
+        );
+        // Clamped when the pointer leaves the window
+        assert_eq!(
+            edge_pan_direction(Vec2::new(-100.0, 300.0), size),
+            Vec2::new(-1.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! Exporting frames as individual PNG files, as they appear on the canvas.
+//!
+//! Each frame is rasterized at one pixel per world unit with its tint, flip and rotation, then
+//! trimmed to its opaque pixels. Files are named after the [`FrameName`] of the frame, or the
+//! file name of its image, with a suffix where the folder already has a file of that name so
+//! that nothing is overwritten. Frames pinned to the screen are not exported, since their size
+//! on the canvas follows the zoom.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::bevyhow;
+
+use super::{ImageFrame, naming::FrameName, pin::ScreenPinned};
+
+/// Largest width or height in pixels of a rasterized frame, before trimming.
+const MAX_EXTENT: u32 = 8192;
+
+/// Renders `image` as a frame of `size` in world units rotated by `rotation` radians, with the
+/// tint and flip of `sprite`. The result is trimmed to the pixels with any opacity, and is
+/// `None` if there are none. Fails if the frame is larger than [`MAX_EXTENT`] on either axis.
+fn rasterize(image: &Image, sprite: &Sprite, size: Vec2, rotation: f32) -> Result<Option<Image>> {
+    let image_size = image.size();
+    if size.min_element() <= 0.0 || image_size.min_element() == 0 {
+        return Ok(None);
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+    let extent = Vec2::new(
+        size.x * cos.abs() + size.y * sin.abs(),
+        size.x * sin.abs() + size.y * cos.abs(),
+    )
+    // Keep the size exact for right angles despite rounding errors
+    .map(|length| (length - 1e-4).ceil())
+    .as_uvec2();
+    if extent.max_element() > MAX_EXTENT {
+        return Err(bevyhow!(
+            "{}x{} pixels is larger than the maximum of {MAX_EXTENT}",
+            extent.x,
+            extent.y
+        ));
+    }
+    let tint = sprite.color.to_linear().to_vec4();
+
+    let mut pixels = vec![[0u8; 4]; (extent.x * extent.y) as usize];
+    for y in 0..extent.y {
+        for x in 0..extent.x {
+            // Offset of the pixel center from the frame center, y up
+            let offset = Vec2::new(
+                x as f32 + 0.5 - extent.x as f32 / 2.0,
+                extent.y as f32 / 2.0 - (y as f32 + 0.5),
+            );
+            let local = Vec2::from_angle(-rotation).rotate(offset);
+            let mut uv = Vec2::new(local.x / size.x + 0.5, 0.5 - local.y / size.y);
+            if !(0.0..1.0).contains(&uv.x) || !(0.0..1.0).contains(&uv.y) {
+                continue;
+            }
+            if sprite.flip_x {
+                uv.x = 1.0 - uv.x;
+            }
+            if sprite.flip_y {
+                uv.y = 1.0 - uv.y;
+            }
+
+            let texel = (uv * image_size.as_vec2())
+                .as_uvec2()
+                .min(image_size - UVec2::ONE);
+            let color = image.get_color_at(texel.x, texel.y)?.to_linear().to_vec4() * tint;
+            pixels[(y * extent.x + x) as usize] =
+                Srgba::from(LinearRgba::from_vec4(color)).to_u8_array();
+        }
+    }
+
+    let Some(bounds) = opaque_bounds(&pixels, extent.x) else {
+        return Ok(None);
+    };
+    let trimmed_size = bounds.size() + UVec2::ONE;
+    let mut data = Vec::with_capacity((trimmed_size.x * trimmed_size.y * 4) as usize);
+    for y in bounds.min.y..=bounds.max.y {
+        let row = (y * extent.x) as usize;
+        for pixel in &pixels[row + bounds.min.x as usize..=row + bounds.max.x as usize] {
+            data.extend_from_slice(pixel);
+        }
+    }
+
+    Ok(Some(Image::new(
+        Extent3d {
+            width: trimmed_size.x,
+            height: trimmed_size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    )))
+}
+
+/// Inclusive bounds of the pixels with any opacity in rows of `width` RGBA pixels.
+fn opaque_bounds(pixels: &[[u8; 4]], width: u32) -> Option<URect> {
+    let mut bounds: Option<URect> = None;
+    for (i, pixel) in pixels.iter().enumerate() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        let position = UVec2::new(i as u32 % width, i as u32 / width);
+        bounds = Some(match bounds {
+            Some(bounds) => URect::from_corners(bounds.min.min(position), bounds.max.max(position)),
+            None => URect::from_corners(position, position),
+        });
+    }
+    bounds
+}
+
+/// Lowercase names of the files in `dir`, to keep exported files from overwriting them.
+fn existing_file_names(dir: &Path) -> HashSet<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashSet::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+        .collect()
+}
+
+/// Returns a file name for the frame named `name` that is not in `used`, and adds it there.
+fn unique_file_name(name: &str, used: &mut HashSet<String>) -> String {
+    let name = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>();
+    let name = if name.trim().is_empty() {
+        "frame".to_string()
+    } else {
+        name
+    };
+
+    let mut file_name = format!("{name}.png");
+    let mut index = 2;
+    while !used.insert(file_name.to_lowercase()) {
+        file_name = format!("{name}_{index}.png");
+        index += 1;
+    }
+    file_name
+}
+
+fn export_frame(
+    path: &Path,
+    images: &Assets<Image>,
+    image_frame: &ImageFrame,
+    sprite: &Sprite,
+    transform: &Transform,
+) -> Result<bool> {
+    let image = images
+        .get(&image_frame.0)
+        .ok_or_else(|| bevyhow!("Image is not loaded"))?;
+    let size = sprite.custom_size.unwrap_or(image.size_f32()) * transform.scale.xy();
+    let rotation = transform.rotation.to_euler(EulerRot::ZYX).0;
+
+    let Some(raster) = rasterize(image, sprite, size, rotation)? else {
+        return Ok(false);
+    };
+    raster.try_into_dynamic()?.save(path)?;
+    Ok(true)
+}
+
+/// Result of the last [`export_frames`], shown until dismissed.
+#[derive(Resource, Debug, Default)]
+pub struct ExportSummary {
+    pub dir: PathBuf,
+    /// Number of files written.
+    pub written: usize,
+    /// Number of frames without opaque pixels.
+    pub skipped: usize,
+    /// Number of frames that failed to export.
+    pub failed: usize,
+}
+
+impl ExportSummary {
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "Exported {} frame(s) to {}",
+            self.written,
+            self.dir.display()
+        );
+        if self.skipped > 0 {
+            message += &format!(", skipped {} without opaque pixels", self.skipped);
+        }
+        if self.failed > 0 {
+            message += &format!(", {} failed", self.failed);
+        }
+        message
+    }
+}
+
+/// One-shot system to write the `target` frames as PNG files into a folder, skipping frames
+/// without opaque pixels and frames pinned to the screen. Inserts the [`ExportSummary`]. Use
+/// `Commands::run_system_cached_with` to run it.
+pub fn export_frames(
+    In((target, dir)): In<(Vec<Entity>, PathBuf)>,
+    mut commands: Commands,
+    frames: Query<(&ImageFrame, &Sprite, &Transform, Option<&FrameName>), Without<ScreenPinned>>,
+    images: Res<Assets<Image>>,
+) {
+    let mut used = existing_file_names(&dir);
+    let mut summary = ExportSummary::default();
+    for (image_frame, sprite, transform, name) in frames.iter_many(&target) {
+        let name = match name {
+            Some(name) => name.0.clone(),
+            None => image_frame
+                .0
+                .path()
+                .and_then(|path| path.path().file_stem())
+                .map_or_else(|| "frame".to_string(), |stem| stem.to_string_lossy().into()),
+        };
+        let path = dir.join(unique_file_name(&name, &mut used));
+        match export_frame(&path, &images, image_frame, sprite, transform) {
+            Ok(true) => summary.written += 1,
+            Ok(false) => {
+                info!("Skipped {name}, which has no opaque pixels");
+                summary.skipped += 1;
+            }
+            Err(error) => {
+                warn!("Failed to export {}: {error}", path.display());
+                summary.failed += 1;
+            }
+        }
+    }
+    summary.dir = dir;
+    info!("{}", summary.message());
+    commands.insert_resource(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn pixels(image: &Image) -> Vec<[u8; 4]> {
+        image
+            .data
+            .as_ref()
+            .unwrap()
+            .chunks_exact(4)
+            .map(|pixel| pixel.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rasterize() {
+        const RED: [u8; 4] = [255, 0, 0, 255];
+        const BLUE: [u8; 4] = [0, 0, 255, 255];
+        const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+        // Red and blue pixels, and a transparent column trimmed away
+        let image = Image::new(
+            Extent3d {
+                width: 3,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            [RED, BLUE, CLEAR].concat(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+
+        let raster = rasterize(&image, &Sprite::default(), Vec2::new(6.0, 2.0), 0.0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(raster.size(), UVec2::new(4, 2));
+        assert_eq!(pixels(&raster)[..4], [RED, RED, BLUE, BLUE]);
+
+        // Flipped so that red is on the right, then rotated counterclockwise to the top
+        let sprite = Sprite {
+            flip_x: true,
+            ..default()
+        };
+        let raster = rasterize(&image, &sprite, Vec2::new(3.0, 1.0), FRAC_PI_2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(raster.size(), UVec2::new(1, 2));
+        assert_eq!(pixels(&raster), [RED, BLUE]);
+
+        // Fully transparent
+        let sprite = Sprite {
+            color: Color::NONE,
+            ..default()
+        };
+        assert!(
+            rasterize(&image, &sprite, Vec2::new(3.0, 1.0), 0.0)
+                .unwrap()
+                .is_none()
+        );
+
+        // Too large to rasterize
+        let size = Vec2::new(MAX_EXTENT as f32 + 1.0, 1.0);
+        assert!(rasterize(&image, &Sprite::default(), size, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_unique_file_name() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_file_name("tile", &mut used), "tile.png");
+        assert_eq!(unique_file_name("Tile", &mut used), "Tile_2.png");
+        assert_eq!(unique_file_name("a/b", &mut used), "a_b.png");
+        assert_eq!(unique_file_name("", &mut used), "frame.png");
+    }
+
+    #[test]
+    fn test_export_keeps_existing_files() {
+        let dir = std::env::temp_dir().join(format!("neta_export_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("tile.png");
+        std::fs::write(&existing, b"source").unwrap();
+
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        let image = Image::new_fill(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        let image = world.resource_mut::<Assets<Image>>().add(image);
+        let frame = world
+            .spawn((
+                ImageFrame(image),
+                Sprite::default(),
+                Transform::default(),
+                FrameName("Tile".to_string()),
+            ))
+            .id();
+
+        world
+            .run_system_cached_with(export_frames, (vec![frame], dir.clone()))
+            .unwrap();
+
+        let summary = world.resource::<ExportSummary>();
+        assert_eq!(
+            (summary.written, summary.skipped, summary.failed),
+            (1, 0, 0)
+        );
+        assert_eq!(std::fs::read(&existing).unwrap(), b"source");
+        assert!(dir.join("Tile_2.png").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
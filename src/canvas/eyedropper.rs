@@ -0,0 +1,77 @@
+//! Eyedropper tool to copy the style of a frame to others.
+//!
+//! While [`Tool::Eyedropper`] is active, clicking a frame captures its tint, opacity and flip
+//! flags, and clicking further frames applies them.
+
+use bevy::{prelude::*, window::SystemCursorIcon};
+
+use crate::{
+    cursor::{CursorSource, Cursors},
+    key_bindings::{Action, action_just_pressed},
+};
+
+use super::{
+    ImageFrame, Tool,
+    style::{FrameStyle, StyleEdit},
+    undo::UndoStack,
+};
+
+pub struct EyedropperPlugin;
+
+impl Plugin for EyedropperPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CopiedStyle>().add_systems(
+            Update,
+            toggle_eyedropper.run_if(action_just_pressed(Action::Eyedropper)),
+        );
+    }
+}
+
+/// Style captured from the first frame clicked since the eyedropper was activated.
+#[derive(Resource, Default)]
+pub struct CopiedStyle(Option<FrameStyle>);
+
+fn toggle_eyedropper(
+    mut tool: ResMut<Tool>,
+    mut copied: ResMut<CopiedStyle>,
+    mut cursors: ResMut<Cursors>,
+) {
+    if *tool == Tool::Eyedropper {
+        *tool = Tool::Select;
+        cursors.clear(CursorSource::Tool);
+        info!("Eyedropper disabled");
+    } else {
+        *tool = Tool::Eyedropper;
+        copied.0 = None;
+        cursors.set(CursorSource::Tool, SystemCursorIcon::Crosshair);
+        info!("Eyedropper enabled, click a frame to copy its style");
+    }
+}
+
+/// One-shot system for a frame clicked with the eyedropper. Use
+/// `Commands::run_system_cached_with` to run it with the clicked [`ImageFrame`].
+pub fn eyedrop(
+    In(target): In<Entity>,
+    mut copied: ResMut<CopiedStyle>,
+    mut sprites: Query<&mut Sprite, With<ImageFrame>>,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    let Ok(mut sprite) = sprites.get_mut(target) else {
+        return;
+    };
+
+    let Some(style) = copied.0 else {
+        copied.0 = Some(FrameStyle::of(&sprite));
+        info!("Copied style, click frames to apply it");
+        return;
+    };
+
+    let before = FrameStyle::of(&sprite);
+    if before == style {
+        return;
+    }
+    style.apply(&mut sprite);
+    undo_stack.push(StyleEdit {
+        frames: vec![(target, before, style)],
+    });
+}
@@ -0,0 +1,201 @@
+//! Snapping of dragged frames to a grid, aligning a chosen anchor of the frame to grid lines,
+//! and of placed frames to whole pixels.
+
+use bevy::prelude::*;
+
+use crate::key_bindings::{Action, action_just_pressed};
+
+pub struct GridSnapPlugin;
+
+impl Plugin for GridSnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GridSnap>()
+            .register_type::<GridSnap>()
+            .init_resource::<PixelSnap>()
+            .register_type::<PixelSnap>()
+            .add_systems(
+                Update,
+                (
+                    toggle_grid_snap.run_if(action_just_pressed(Action::ToggleGridSnap)),
+                    toggle_pixel_snap.run_if(action_just_pressed(Action::TogglePixelSnap)),
+                ),
+            );
+    }
+}
+
+/// Grid snapping of dragged frames.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct GridSnap {
+    pub enabled: bool,
+    /// Distance between grid lines in world units.
+    pub spacing: f32,
+    /// Point of the frame placed on grid lines.
+    pub anchor: SnapAnchor,
+}
+
+impl Default for GridSnap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spacing: 16.0,
+            anchor: SnapAnchor::Center,
+        }
+    }
+}
+
+/// Rounding of frame positions to whole texels when a drag ends or a frame is added, so that
+/// the texel grids of frames at the same scale line up and sprites at 1:1 scale render without
+/// sub-pixel blurring. Applied on top of [`GridSnap`].
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource, Default)]
+pub struct PixelSnap {
+    pub enabled: bool,
+}
+
+impl PixelSnap {
+    /// Returns the translation of a frame of `size` and `rotation` centered at `translation`,
+    /// showing an image of `image_size` texels, moved so that its edges lie on multiples of the
+    /// size of a texel in world units. Exact for rotations by multiples of 90 degrees, where the
+    /// edges are axis aligned.
+    pub fn snap(translation: Vec2, size: Vec2, image_size: Vec2, rotation: Quat) -> Vec2 {
+        let rotate = |v: Vec2| (rotation * v.extend(0.0)).xy().abs();
+        let half_extent = rotate(size / 2.0);
+        let texel = rotate(size / image_size);
+        // Whole world units for empty frames or images
+        let texel = Vec2::select(
+            texel.cmpgt(Vec2::ZERO) & texel.is_finite_mask(),
+            texel,
+            Vec2::ONE,
+        );
+        ((translation - half_extent) / texel).round() * texel + half_extent
+    }
+}
+
+/// Translation of a frame being dragged with [`GridSnap`] before snapping.
+#[derive(Component)]
+pub struct Unsnapped(pub Vec2);
+
+/// Point of a frame aligned to the grid by [`GridSnap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum SnapAnchor {
+    #[default]
+    Center,
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl SnapAnchor {
+    /// Offset of the anchor from the center of an unrotated frame of `size`.
+    fn offset(&self, size: Vec2) -> Vec2 {
+        let direction = match self {
+            SnapAnchor::Center => Vec2::ZERO,
+            SnapAnchor::TopLeft => Vec2::new(-1.0, 1.0),
+            SnapAnchor::Top => Vec2::new(0.0, 1.0),
+            SnapAnchor::TopRight => Vec2::new(1.0, 1.0),
+            SnapAnchor::Left => Vec2::new(-1.0, 0.0),
+            SnapAnchor::Right => Vec2::new(1.0, 0.0),
+            SnapAnchor::BottomLeft => Vec2::new(-1.0, -1.0),
+            SnapAnchor::Bottom => Vec2::new(0.0, -1.0),
+            SnapAnchor::BottomRight => Vec2::new(1.0, -1.0),
+        };
+        direction * size.abs() / 2.0
+    }
+}
+
+impl GridSnap {
+    /// Returns the translation of a frame of `size` and `rotation` centered at `translation`,
+    /// moved so that its anchor lies on the nearest grid point.
+    pub fn snap(&self, translation: Vec2, size: Vec2, rotation: Quat) -> Vec2 {
+        if self.spacing <= 0.0 {
+            return translation;
+        }
+        let offset = (rotation * self.anchor.offset(size).extend(0.0)).xy();
+        ((translation + offset) / self.spacing).round() * self.spacing - offset
+    }
+}
+
+fn toggle_grid_snap(mut grid_snap: ResMut<GridSnap>) {
+    grid_snap.enabled = !grid_snap.enabled;
+    info!(
+        "Grid snap {}",
+        if grid_snap.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+}
+
+pub fn toggle_pixel_snap(mut pixel_snap: ResMut<PixelSnap>) {
+    pixel_snap.enabled = !pixel_snap.enabled;
+    info!(
+        "Pixel snap {}",
+        if pixel_snap.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn test_snap_center() {
+        let grid_snap = GridSnap::default();
+        let snapped = grid_snap.snap(Vec2::new(9.0, -23.0), Vec2::new(20.0, 10.0), Quat::IDENTITY);
+        assert_eq!(snapped, Vec2::new(16.0, -16.0));
+    }
+
+    #[test]
+    fn test_snap_corner() {
+        let grid_snap = GridSnap {
+            anchor: SnapAnchor::TopLeft,
+            ..default()
+        };
+
+        // Top-left corner at (-1, 6) snaps to (0, 0)
+        let snapped = grid_snap.snap(Vec2::new(9.0, 1.0), Vec2::new(20.0, 10.0), Quat::IDENTITY);
+        assert_eq!(snapped, Vec2::new(10.0, -5.0));
+
+        // Rotated by 90 degrees, the top-left corner is at the bottom-left
+        let snapped = grid_snap.snap(
+            Vec2::new(4.0, 11.0),
+            Vec2::new(20.0, 10.0),
+            Quat::from_rotation_z(FRAC_PI_2),
+        );
+        assert!(snapped.abs_diff_eq(Vec2::new(5.0, 10.0), 1e-4));
+    }
+
+    #[test]
+    fn test_pixel_snap() {
+        // At 1:1 scale, even sizes are centered on whole pixels, odd sizes between them
+        let size = Vec2::new(20.0, 15.0);
+        let snapped = PixelSnap::snap(Vec2::new(0.3, -4.6), size, size, Quat::IDENTITY);
+        assert_eq!(snapped, Vec2::new(0.0, -4.5));
+
+        // Rotated by 90 degrees, the odd size is along x
+        let snapped = PixelSnap::snap(
+            Vec2::new(0.3, -4.6),
+            size,
+            size,
+            Quat::from_rotation_z(FRAC_PI_2),
+        );
+        assert!(snapped.abs_diff_eq(Vec2::new(0.5, -5.0), 1e-4));
+
+        // Scaled by 4, the edges lie on multiples of 4 world units
+        let snapped = PixelSnap::snap(Vec2::new(3.0, 9.0), size * 4.0, size, Quat::IDENTITY);
+        assert_eq!(snapped, Vec2::new(4.0, 10.0));
+    }
+}
@@ -4,18 +4,28 @@ use bevy::{
     prelude::*,
     render::camera::NormalizedRenderTarget,
     window::{PrimaryWindow, SystemCursorIcon},
-    winit::cursor::CursorIcon,
 };
 use bevy_vector_shapes::{
     prelude::ShapePainter,
     shapes::{DiscPainter, LinePainter, RectPainter},
 };
 
-use crate::{bail, observe_component::Observe, viewport_delta::PointerDelta};
+use crate::{
+    bail, bevyhow,
+    config::{ConfigFile, save_config},
+    cursor::{CursorSource, Cursors},
+    despawn::SafeDespawn,
+    key_bindings::{Action, KeyBindings},
+    observe_component::Observe,
+    redraw::send_redraw_request,
+    viewport_delta::PointerDelta,
+};
 
 use super::{
-    CONTROL_LAYER, MainCamera,
+    CONTROL_LAYER, ImageFrame, MainCamera, arrange,
     camera_util::{CameraTranslator, RenderTargetHelper},
+    culling::{self, Culled},
+    grid_snap::GridSnap,
     picking::PickingAreaCircle,
 };
 
@@ -23,19 +33,40 @@ pub struct ControlHandlePlugin;
 
 impl Plugin for ControlHandlePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                track_main_camera_entity_transform,
-                (update_corner_handle, update_rotation_handle),
+        app.init_resource::<HandleScale>()
+            .init_resource::<ControlHandleSettings>()
+            .register_type::<ControlHandleSettings>()
+            .insert_resource(HandleTheme::load())
+            .register_type::<HandleTheme>()
+            .add_systems(Last, save_config::<HandleTheme>.before(send_redraw_request))
+            .add_systems(
+                Update,
+                (
+                    update_handle_scale,
+                    update_handle_picking_radius.run_if(resource_changed::<HandleScale>),
+                    track_main_camera_entity_transform,
+                    (update_corner_handle, update_rotation_handle),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                respawn_control_handle.run_if(resource_changed::<ControlHandleSettings>),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    draw_control_handle
+                        .after(TransformSystem::TransformPropagate)
+                        .after(culling::cull_frames),
+                    draw_angle_guide
+                        .after(TransformSystem::TransformPropagate)
+                        .run_if(resource_exists::<AngleGuide>),
+                ),
             )
-                .chain(),
-        )
-        .add_systems(
-            PostUpdate,
-            draw_control_handle.after(TransformSystem::TransformPropagate),
-        )
-        .add_observer(on_update_rotation_cursor);
+            .add_observer(on_update_rotation_cursor)
+            .add_observer(on_frame_culled)
+            .add_observer(on_frame_unculled);
     }
 }
 
@@ -50,8 +81,7 @@ pub struct ControlledSprite(Entity);
 #[derive(Resource, Debug)]
 pub struct CurrentControlHandle(pub Entity);
 
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Pivot {
     BottomLeft,
     BottomCenter,
@@ -84,15 +114,61 @@ struct ControlHandleCorner(Pivot);
 #[derive(Component)]
 struct ControlHandleRotation(Pivot);
 
+/// Handle under the pointer, drawn enlarged to show it can be grabbed.
+#[derive(Component)]
+struct HandleHovered;
+
 const CORNER_HANDLE_RADIUS: f32 = 6.0;
 
+/// Scale of the drawn dot of a hovered handle. The picking area keeps its size.
+const HOVERED_HANDLE_SCALE: f32 = 1.4;
+
+/// Scale of control handle sizes, following the primary window's scale factor so that handles
+/// look crisp and stay easy to grab on high-DPI displays.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+struct HandleScale(f32);
+
+impl Default for HandleScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+fn update_handle_scale(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut handle_scale: ResMut<HandleScale>,
+) {
+    handle_scale.set_if_neq(HandleScale(window.scale_factor()));
+}
+
+/// Keeps the hit areas of handles matching their scaled visuals.
+fn update_handle_picking_radius(
+    handle_scale: Res<HandleScale>,
+    mut areas: Query<
+        &mut PickingAreaCircle,
+        Or<(With<ControlHandleCorner>, With<ControlHandleRotation>)>,
+    >,
+) {
+    for mut area in &mut areas {
+        area.0.radius = CORNER_HANDLE_RADIUS * handle_scale.0;
+    }
+}
+
 /// Attach [`ControlHandle`] to Sprite `sprite_id`
 pub fn spawn_control_handle(sprite_id: Entity) -> impl Command<Result> {
     move |world: &mut World| -> Result {
         if let Some(current_handle) = world.get_resource::<CurrentControlHandle>() {
-            world.entity_mut(current_handle.0).despawn();
+            world.safe_despawn(current_handle.0);
         }
 
+        let radius = CORNER_HANDLE_RADIUS * world.resource::<HandleScale>().0;
+        let pivots = world.resource::<ControlHandleSettings>().resize_pivots();
+        let pickable = if world.get::<Culled>(sprite_id).is_some() {
+            Pickable::IGNORE
+        } else {
+            Pickable::default()
+        };
+
         let mut commands = world.commands();
 
         let mut handle = commands.spawn((
@@ -104,30 +180,24 @@ pub fn spawn_control_handle(sprite_id: Entity) -> impl Command<Result> {
             CONTROL_LAYER,
         ));
         handle.with_children(|parent| {
-            for pivot in [
-                Pivot::TopLeft,
-                Pivot::TopRight,
-                Pivot::BottomLeft,
-                Pivot::BottomRight,
-                // Pivot::TopCenter,
-                // Pivot::CenterLeft,
-                // Pivot::CenterRight,
-                // Pivot::BottomCenter,
-            ] {
+            for &pivot in pivots {
                 parent.spawn((
                     CONTROL_LAYER,
-                    PickingAreaCircle(Circle::new(CORNER_HANDLE_RADIUS)),
+                    PickingAreaCircle(Circle::new(radius)),
                     ControlHandleCorner(pivot),
                     Transform::from_translation(Vec3::new(0., 0., 2.)),
+                    pickable.clone(),
                     drag_handle_observers(pivot, sprite_id),
                 ));
             }
 
             parent.spawn((
                 CONTROL_LAYER,
-                PickingAreaCircle(Circle::new(CORNER_HANDLE_RADIUS)),
+                PickingAreaCircle(Circle::new(radius)),
                 ControlHandleRotation(Pivot::TopCenter),
-                Transform::from_translation(Vec3::new(0., 100., 2.)),
+                // Placed by `update_rotation_handle`
+                Transform::from_translation(Vec3::new(0., 0., 2.)),
+                pickable,
                 rotation_handle_observers(Pivot::TopCenter, sprite_id),
             ));
         });
@@ -139,24 +209,88 @@ pub fn spawn_control_handle(sprite_id: Entity) -> impl Command<Result> {
     }
 }
 
+/// Spawns the current control handle again to apply changed [`ControlHandleSettings`].
+fn respawn_control_handle(
+    mut commands: Commands,
+    current: Option<Res<CurrentControlHandle>>,
+    control_handle: Query<&ControlHandle>,
+) {
+    if let Some(current) = current
+        && let Ok(control_handle) = control_handle.get(current.0)
+    {
+        commands.queue(spawn_control_handle(control_handle.0));
+    }
+}
+
 /// Removes current control handle that [`CurrentControlHandle`] points to.
 pub fn despawn_control_handle(world: &mut World) {
     let current = world.get_resource::<CurrentControlHandle>();
     if let Some(current_handle) = current {
-        world.entity_mut(current_handle.0).despawn();
+        world.safe_despawn(current_handle.0);
         world.remove_resource::<CurrentControlHandle>();
     }
 }
 
+/// Handles of a [`Culled`] frame aren't moved, so they are made unpickable to avoid hits
+/// where the frame was last seen.
+fn on_frame_culled(
+    trigger: Trigger<OnAdd, Culled>,
+    mut commands: Commands,
+    controlled: Query<&ControlledSprite>,
+    children: Query<&Children>,
+) {
+    set_handles_pickable(
+        trigger.target(),
+        Pickable::IGNORE,
+        &mut commands,
+        controlled,
+        children,
+    );
+}
+
+fn on_frame_unculled(
+    trigger: Trigger<OnRemove, Culled>,
+    mut commands: Commands,
+    controlled: Query<&ControlledSprite>,
+    children: Query<&Children>,
+) {
+    set_handles_pickable(
+        trigger.target(),
+        Pickable::default(),
+        &mut commands,
+        controlled,
+        children,
+    );
+}
+
+fn set_handles_pickable(
+    frame: Entity,
+    pickable: Pickable,
+    commands: &mut Commands,
+    controlled: Query<&ControlledSprite>,
+    children: Query<&Children>,
+) {
+    let Ok(controlled) = controlled.get(frame) else {
+        return;
+    };
+    for handle in children.iter_descendants(controlled.0) {
+        commands.entity(handle).insert(pickable.clone());
+    }
+}
+
 #[derive(Component)]
 struct TrackMainCameraEntityTransform(Entity);
 
 fn track_main_camera_entity_transform(
     mut transform_params: ParamSet<(TransformHelper, CameraTranslator, Query<&mut Transform>)>,
     tracker: Query<(Entity, &TrackMainCameraEntityTransform)>,
+    culled: Query<(), With<Culled>>,
 ) -> Result {
     for (tracker_entity, target) in &tracker {
         let target_entity = target.0;
+        if culled.contains(target_entity) {
+            continue;
+        }
         let target_transform = transform_params
             .p0()
             .compute_global_transform(target_entity)?;
@@ -181,12 +315,12 @@ struct UpdateRotationCursor {
 
 fn on_update_rotation_cursor(
     trigger: Trigger<UpdateRotationCursor>,
-    mut commands: Commands,
+    mut cursors: ResMut<Cursors>,
     camera: Query<(&Camera, &GlobalTransform)>,
     transform: Query<&Transform>,
     helper: RenderTargetHelper<MainCamera>,
 ) -> Result {
-    let NormalizedRenderTarget::Window(target_window) = trigger.location.target else {
+    let NormalizedRenderTarget::Window(_) = trigger.location.target else {
         bail!("Not a window target: {:?}", trigger.location.target);
     };
 
@@ -198,8 +332,12 @@ fn on_update_rotation_cursor(
     let frame_view_pos =
         main_camera.world_to_viewport(main_camera_transform, frame_transform.translation)?;
     let delta = trigger.location.position - frame_view_pos;
+    // No direction with the cursor on the center
+    let Some(direction) = delta.try_normalize() else {
+        return Ok(());
+    };
 
-    let sector = ((delta.normalize().angle_to(Vec2::X) + std::f32::consts::PI)
+    let sector = ((direction.angle_to(Vec2::X) + std::f32::consts::PI)
         / std::f32::consts::FRAC_PI_8)
         .round() as i32;
 
@@ -215,9 +353,7 @@ fn on_update_rotation_cursor(
         _ => SystemCursorIcon::Default,
     };
 
-    commands
-        .entity(target_window.entity())
-        .insert(CursorIcon::System(cursor));
+    cursors.set(CursorSource::HandleHover, cursor);
 
     Ok(())
 }
@@ -245,38 +381,29 @@ fn drag_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
                     return;
                 };
 
-                // Resize with the opposite corner being fixed
-
-                transform.translation += delta.extend(0.0) / 2.0;
+                // Resize with the opposite corner (or edge) being fixed. Edge handles only
+                // resize along their axis.
 
-                let sign = match pivot {
-                    Pivot::TopLeft => Vec2::new(-1., 1.),
-                    Pivot::TopRight => Vec2::new(1., 1.),
-                    Pivot::BottomLeft => Vec2::new(-1., -1.),
-                    Pivot::BottomRight => Vec2::new(1., -1.),
-                    _ => {
-                        return;
-                    }
-                };
+                let sign = pivot.as_vec() * 2.0;
 
-                let rotated_delta = transform
-                    .rotation
-                    .inverse()
-                    .mul_vec3(delta.extend(0.))
-                    .truncate()
-                    * sign;
+                let rotation = transform.rotation;
+                let rotated_delta = rotation.inverse().mul_vec3(delta.extend(0.)).truncate();
 
-                if let Some(custom_size) = sprite.custom_size.as_mut() {
-                    *custom_size += rotated_delta;
-                } else {
+                let Some(custom_size) = sprite.custom_size.as_mut() else {
                     error_once!("Sprite is missing custom size");
-                }
+                    return;
+                };
+                *custom_size += rotated_delta * sign;
+
+                transform.translation +=
+                    rotation * ((rotated_delta * sign.abs()).extend(0.0)) / 2.0;
             },
         ),
         Observe::new(
             move |mut trigger: Trigger<Pointer<Over>>, mut commands: Commands| {
                 trigger.propagate(false);
 
+                commands.entity(trigger.target()).insert(HandleHovered);
                 commands.entity(sprite_id).trigger(UpdateRotationCursor {
                     location: trigger.pointer_location.clone(),
                 });
@@ -285,21 +412,18 @@ fn drag_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
         Observe::new(
             |mut trigger: Trigger<Pointer<Out>>,
              mut commands: Commands,
-             window: Query<Entity, With<Window>>| {
+             mut cursors: ResMut<Cursors>| {
                 trigger.propagate(false);
-                window.iter().for_each(|window| {
-                    commands.entity(window).remove::<CursorIcon>();
-                });
+                commands
+                    .entity(trigger.target())
+                    .try_remove::<HandleHovered>();
+                cursors.clear(CursorSource::HandleHover);
             },
         ),
         Observe::new(
-            |mut trigger: Trigger<Pointer<DragEnd>>,
-             mut commands: Commands,
-             window: Query<Entity, With<Window>>| {
+            |mut trigger: Trigger<Pointer<DragEnd>>, mut cursors: ResMut<Cursors>| {
                 trigger.propagate(false);
-                window.iter().for_each(|window| {
-                    commands.entity(window).remove::<CursorIcon>();
-                });
+                cursors.clear(CursorSource::HandleHover);
             },
         ),
         Observe::new(|mut trigger: Trigger<Pointer<Click>>| {
@@ -308,30 +432,160 @@ fn drag_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
     )
 }
 
-fn rotation_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
-    let remove_icon = |mut commands: Commands, window: Query<Entity, With<Window>>| {
-        window.iter().for_each(|window| {
-            commands.entity(window).remove::<CursorIcon>();
-        });
+/// Angle step of rotation while [`Action::SnapRotation`] is held, in degrees.
+const ROTATION_SNAP_STEP: f32 = 15.0;
+
+/// Text showing the angle of the frame being rotated.
+#[derive(Component)]
+struct RotationReadout;
+
+/// Vectors shorter than this are treated as having no direction when rotating.
+const MIN_DIRECTION_LENGTH: f32 = 1e-4;
+
+/// Offset of [`RotationReadout`] from the rotation handle.
+const ROTATION_READOUT_OFFSET: Vec3 = Vec3::new(0.0, 24.0, 1.0);
+
+/// Rounds the z angle of `rotation` to a multiple of `step` degrees.
+fn snap_rotation(rotation: Quat, step: f32) -> Quat {
+    let angle = rotation_degrees(rotation);
+    Quat::from_rotation_z(((angle / step).round() * step).to_radians())
+}
+
+/// Rotation that turns `pivot` towards `diff`, the cursor relative to the frame center. `None`
+/// when either is too short to have a direction, so that it doesn't turn into NaN.
+fn rotation_towards(pivot: Vec2, diff: Vec2) -> Option<Quat> {
+    if pivot.length_squared() < MIN_DIRECTION_LENGTH.powi(2)
+        || diff.length_squared() < MIN_DIRECTION_LENGTH.powi(2)
+    {
+        return None;
+    }
+    Some(Quat::from_rotation_arc_2d(
+        pivot.try_normalize()?,
+        diff.try_normalize()?,
+    ))
+}
+
+/// The z angle of `rotation` in degrees.
+fn rotation_degrees(rotation: Quat) -> f32 {
+    rotation.to_euler(EulerRot::XYZ).2.to_degrees()
+}
+
+/// Rotations within this many degrees of another frame's angle snap to it while [`GridSnap`]
+/// is enabled, unless [`Action::FreeAngle`] is held.
+const ANGLE_MATCH_THRESHOLD: f32 = 3.0;
+
+/// How the rotation of a dragged frame is snapped.
+#[derive(Debug, PartialEq, Eq)]
+enum RotationSnap {
+    /// To [`ROTATION_SNAP_STEP`] degree steps.
+    Step,
+    /// To the angle of another frame, with [`match_angle`].
+    MatchAngle,
+    None,
+}
+
+impl RotationSnap {
+    fn from_input(
+        key_bindings: &KeyBindings,
+        keyboard_input: &ButtonInput<KeyCode>,
+        grid_snap: &GridSnap,
+    ) -> Self {
+        if key_bindings.pressed(Action::SnapRotation, keyboard_input) {
+            RotationSnap::Step
+        } else if grid_snap.enabled && !key_bindings.pressed(Action::FreeAngle, keyboard_input) {
+            RotationSnap::MatchAngle
+        } else {
+            RotationSnap::None
+        }
+    }
+}
+
+/// Length of the angle guide beyond the edges of a frame, in pixels.
+const ANGLE_GUIDE_OVERHANG: f32 = 24.0;
+
+/// Frame being rotated and the frame whose angle it was snapped to.
+#[derive(Resource)]
+struct AngleGuide {
+    rotated: Entity,
+    matched: Entity,
+}
+
+/// Returns the item of `others` whose angle is nearest to `angle` within `threshold`, with
+/// its angle. All angles are in degrees.
+fn match_angle<T>(
+    angle: f32,
+    others: impl IntoIterator<Item = (T, f32)>,
+    threshold: f32,
+) -> Option<(T, f32)> {
+    let difference = |other: f32| {
+        let difference = (angle - other).rem_euclid(360.0);
+        difference.min(360.0 - difference)
     };
+    others
+        .into_iter()
+        .filter(|&(_, other)| difference(other) <= threshold)
+        .min_by(|(_, a), (_, b)| difference(*a).total_cmp(&difference(*b)))
+}
+
+fn draw_angle_guide(
+    guide: Res<AngleGuide>,
+    frames: Query<(&GlobalTransform, &Sprite)>,
+    camera_translator: CameraTranslator,
+    mut painter: ShapePainter,
+) -> Result {
+    painter.render_layers = Some(CONTROL_LAYER);
+    painter.color = ORANGE.into();
+
+    for entity in [guide.rotated, guide.matched] {
+        let Ok((transform, sprite)) = frames.get(entity) else {
+            continue;
+        };
+        let control_transform = camera_translator.to_control(transform)?;
+
+        // Line along the x axis of the frame, past its edges
+        let half_length = sprite.custom_size.unwrap_or(Vec2::ZERO).x.abs() / 2.0
+            * control_transform.scale.x
+            + ANGLE_GUIDE_OVERHANG;
+        painter.transform = control_transform.with_scale(Vec3::ONE);
+        painter.line(
+            Vec3::new(-half_length, 0.0, 0.0),
+            Vec3::new(half_length, 0.0, 0.0),
+        );
+    }
 
+    Ok(())
+}
+
+fn remove_angle_guide(mut commands: Commands) {
+    commands.remove_resource::<AngleGuide>();
+}
+
+fn despawn_rotation_readout(mut commands: Commands, readout: Query<Entity, With<RotationReadout>>) {
+    for entity in &readout {
+        commands.safe_despawn(entity);
+    }
+}
+
+fn rotation_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
     (
         Observe::new(
             move |mut trigger: Trigger<Pointer<Drag>>,
                   main_camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
                   primary_window: Query<Entity, With<PrimaryWindow>>,
-                  mut transform: Query<&mut Transform>,
+                  mut transform: Query<&mut Transform, Without<RotationReadout>>,
                   mut commands: Commands,
-                  window: Query<Entity, With<Window>>| {
+                  mut cursors: ResMut<Cursors>,
+                  keyboard_input: Res<ButtonInput<KeyCode>>,
+                  key_bindings: Res<KeyBindings>,
+                  global_transforms: Query<&GlobalTransform>,
+                  mut readout: Query<(&mut Text2d, &mut Transform), With<RotationReadout>>,
+                  grid_snap: Res<GridSnap>,
+                  frames: Query<Entity, (With<ImageFrame>, Without<Culled>)>| {
                 trigger.propagate(false);
 
-                window.iter().for_each(|window| {
-                    commands
-                        .entity(window)
-                        .insert(CursorIcon::System(SystemCursorIcon::Grabbing));
-                });
+                cursors.set(CursorSource::Drag, SystemCursorIcon::Grabbing);
 
-                let Ok(mut sprite_transform) = transform.get_mut(sprite_id) else {
+                let Ok(sprite_translation) = transform.get(sprite_id).map(|t| t.translation) else {
                     return;
                 };
 
@@ -352,34 +606,106 @@ fn rotation_handle_observers(pivot: Pivot, sprite_id: Entity) -> impl Bundle {
                     return;
                 };
 
-                let diff = cursor_world_pos - sprite_transform.translation.truncate();
-                sprite_transform.rotation =
-                    Quat::from_rotation_arc_2d(pivot.as_vec().normalize(), diff.normalize());
+                let diff = cursor_world_pos - sprite_translation.truncate();
+                // Keep the rotation while the cursor is on the center
+                let Some(mut rotation) = rotation_towards(pivot.as_vec(), diff) else {
+                    return;
+                };
+
+                let mut matched = None;
+                let snap = RotationSnap::from_input(&key_bindings, &keyboard_input, &grid_snap);
+                if snap == RotationSnap::Step {
+                    rotation = snap_rotation(rotation, ROTATION_SNAP_STEP);
+                } else if snap == RotationSnap::MatchAngle {
+                    // Match the angle of another visible frame
+                    let others = frames
+                        .iter()
+                        .filter(|&entity| entity != sprite_id)
+                        .filter_map(|entity| {
+                            let other = transform.get(entity).ok()?.rotation;
+                            Some((entity, rotation_degrees(other)))
+                        });
+                    if let Some((entity, angle)) =
+                        match_angle(rotation_degrees(rotation), others, ANGLE_MATCH_THRESHOLD)
+                    {
+                        rotation = Quat::from_rotation_z(angle.to_radians());
+                        matched = Some(entity);
+                    }
+                }
+
+                if let Ok(mut sprite_transform) = transform.get_mut(sprite_id) {
+                    sprite_transform.rotation = rotation;
+                }
+                match matched {
+                    Some(matched) => commands.insert_resource(AngleGuide {
+                        rotated: sprite_id,
+                        matched,
+                    }),
+                    None => commands.remove_resource::<AngleGuide>(),
+                }
+
+                // Show the angle near the rotation handle
+                let Ok(handle_transform) = global_transforms.get(trigger.target()) else {
+                    return;
+                };
+                // Same range as typed rotations
+                let mut label = format!(
+                    "{:.1} deg",
+                    arrange::normalize_degrees(rotation_degrees(rotation))
+                );
+                if matched.is_some() {
+                    label.push_str(" (matched)");
+                }
+                let readout_transform = Transform::from_translation(
+                    handle_transform.translation() + ROTATION_READOUT_OFFSET,
+                );
+                if let Ok((mut text, mut transform)) = readout.single_mut() {
+                    text.0 = label;
+                    *transform = readout_transform;
+                } else {
+                    commands.spawn((
+                        Name::new("RotationReadout"),
+                        RotationReadout,
+                        Text2d::new(label),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        readout_transform,
+                        CONTROL_LAYER,
+                    ));
+                }
             },
         ),
         Observe::new(
             move |mut trigger: Trigger<Pointer<Over>>,
                   mut commands: Commands,
-                  window: Query<Entity, With<Window>>| {
+                  mut cursors: ResMut<Cursors>| {
                 trigger.propagate(false);
-                window.iter().for_each(|window| {
-                    commands
-                        .entity(window)
-                        .insert(CursorIcon::System(SystemCursorIcon::Grab));
-                });
+                commands.entity(trigger.target()).insert(HandleHovered);
+                cursors.set(CursorSource::HandleHover, SystemCursorIcon::Grab);
             },
         ),
         Observe::new(
-            IntoSystem::into_system(|mut trigger: Trigger<Pointer<Out>>| {
+            |mut trigger: Trigger<Pointer<Out>>,
+             mut commands: Commands,
+             mut cursors: ResMut<Cursors>| {
                 trigger.propagate(false);
-            })
-            .pipe(remove_icon),
+                commands
+                    .entity(trigger.target())
+                    .try_remove::<HandleHovered>();
+                cursors.clear(CursorSource::HandleHover);
+            },
         ),
         Observe::new(
-            IntoSystem::into_system(|mut trigger: Trigger<Pointer<DragEnd>>| {
-                trigger.propagate(false);
-            })
-            .pipe(remove_icon),
+            IntoSystem::into_system(
+                |mut trigger: Trigger<Pointer<DragEnd>>, mut cursors: ResMut<Cursors>| {
+                    trigger.propagate(false);
+                    cursors.clear(CursorSource::Drag);
+                },
+            )
+            .pipe(despawn_rotation_readout)
+            .pipe(remove_angle_guide),
         ),
         Observe::new(|mut trigger: Trigger<Pointer<Click>>| {
             trigger.propagate(false);
@@ -393,10 +719,14 @@ fn update_corner_handle(
     handle: Query<(Entity, &ControlHandleCorner), Without<MainCamera>>,
     sprite: Query<(&GlobalTransform, &Sprite)>,
     images: Res<Assets<Image>>,
+    culled: Query<(), With<Culled>>,
     mut transform_params: ParamSet<(CameraTranslator, Query<&mut Transform>)>,
 ) -> Result {
     for (id, pivot) in &handle {
         let sprite_id = control_handle.get(child_of.get(id)?.parent())?.0;
+        if culled.contains(sprite_id) {
+            continue;
+        }
 
         let (sprite_transform, sprite) = sprite.get(sprite_id)?;
 
@@ -426,18 +756,153 @@ fn update_corner_handle(
     Ok(())
 }
 
-const ROTATION_HANDLE_EXTENSION: f32 = 30.0;
+/// Settings for control handles.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ControlHandleSettings {
+    /// Distance of the rotation handle from the frame edge, relative to the longer side of the
+    /// frame on screen.
+    pub rotation_handle_extension: f32,
+    /// Minimum distance of the rotation handle from the frame edge, in logical pixels.
+    pub min_rotation_handle_extension: f32,
+    /// Maximum distance of the rotation handle from the frame edge, in logical pixels.
+    pub max_rotation_handle_extension: f32,
+    /// Whether to add resize handles at the middle of the edges to the corner handles.
+    pub edge_handles: bool,
+}
+
+impl Default for ControlHandleSettings {
+    fn default() -> Self {
+        Self {
+            rotation_handle_extension: 0.125,
+            min_rotation_handle_extension: 20.0,
+            max_rotation_handle_extension: 80.0,
+            edge_handles: false,
+        }
+    }
+}
+
+/// Colors of control handles. Saved to the config directory when changed.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource, Default)]
+pub struct HandleTheme {
+    /// Inside of the handle dots.
+    pub fill: Color,
+    /// Outline of the handle dots.
+    pub ring: Color,
+    /// Border around the frame.
+    pub border: Color,
+    /// Line from the frame to the rotation handle.
+    pub rotation_line: Color,
+}
+
+impl Default for HandleTheme {
+    fn default() -> Self {
+        Self {
+            fill: Color::WHITE,
+            ring: LIGHT_GRAY.into(),
+            border: Color::WHITE,
+            rotation_line: Color::WHITE,
+        }
+    }
+}
+
+impl HandleTheme {
+    fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        match name {
+            "fill" => Some(&mut self.fill),
+            "ring" => Some(&mut self.ring),
+            "border" => Some(&mut self.border),
+            "rotation_line" => Some(&mut self.rotation_line),
+            _ => None,
+        }
+    }
+}
+
+impl ConfigFile for HandleTheme {
+    const FILE_NAME: &str = "handle_theme.cfg";
+
+    fn to_config(&self) -> String {
+        [
+            ("fill", self.fill),
+            ("ring", self.ring),
+            ("border", self.border),
+            ("rotation_line", self.rotation_line),
+        ]
+        .into_iter()
+        .map(|(name, color)| format!("{name} = {}\n", color.to_srgba().to_hex()))
+        .collect()
+    }
+
+    fn apply_config(&mut self, config: &str) -> Result {
+        for line in config.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, hex) = line
+                .split_once('=')
+                .ok_or_else(|| bevyhow!("Invalid line: {line}"))?;
+            let color = self
+                .color_mut(name.trim())
+                .ok_or_else(|| bevyhow!("Unknown color: {}", name.trim()))?;
+            *color = Srgba::hex(hex.trim())?.into();
+        }
+        Ok(())
+    }
+}
+
+impl ControlHandleSettings {
+    /// Pivots to spawn resize handles at.
+    fn resize_pivots(&self) -> &'static [Pivot] {
+        const CORNERS: [Pivot; 4] = [
+            Pivot::TopLeft,
+            Pivot::TopRight,
+            Pivot::BottomLeft,
+            Pivot::BottomRight,
+        ];
+        const CORNERS_AND_EDGES: [Pivot; 8] = [
+            Pivot::TopLeft,
+            Pivot::TopRight,
+            Pivot::BottomLeft,
+            Pivot::BottomRight,
+            Pivot::TopCenter,
+            Pivot::CenterLeft,
+            Pivot::CenterRight,
+            Pivot::BottomCenter,
+        ];
+
+        if self.edge_handles {
+            &CORNERS_AND_EDGES
+        } else {
+            &CORNERS
+        }
+    }
+
+    /// Distance of the rotation handle from the edge of a frame of `size` on screen.
+    fn rotation_handle_extension(&self, size: Vec2) -> f32 {
+        // The bounds are edited separately in the inspector, so they may be swapped for a while
+        let (min, max) = (
+            self.min_rotation_handle_extension,
+            self.max_rotation_handle_extension,
+        );
+        (size.abs().max_element() * self.rotation_handle_extension)
+            .max(min.min(max))
+            .min(min.max(max))
+    }
+}
 
 fn update_rotation_handle(
+    settings: Res<ControlHandleSettings>,
     mut transform_params: ParamSet<(CameraTranslator, Query<&mut Transform>)>,
     control_handle: Query<&ControlHandle>,
     child_of: Query<&ChildOf>,
     handle: Query<(Entity, &ControlHandleRotation), Without<MainCamera>>,
     sprite: Query<(&GlobalTransform, &Sprite)>,
     images: Res<Assets<Image>>,
+    culled: Query<(), With<Culled>>,
 ) -> Result {
     for (id, pivot) in &handle {
         let sprite_id = control_handle.get(child_of.get(id)?.parent())?.0;
+        if culled.contains(sprite_id) {
+            continue;
+        }
 
         let (sprite_transform, sprite) = sprite.get(sprite_id)?;
 
@@ -455,7 +920,7 @@ fn update_rotation_handle(
             let mut transform = transforms.get_mut(id)?;
 
             let v = pivot.0.as_vec();
-            let handle_extention = ROTATION_HANDLE_EXTENSION * v.normalize();
+            let handle_extention = settings.rotation_handle_extension(size) * v.normalize();
             let new_transform = transform.with_translation(
                 Vec3::new(size.x * v.x, size.y * v.y, transform.translation.z)
                     + handle_extention.extend(0.0),
@@ -473,15 +938,28 @@ fn draw_control_handle(
     camera_translator: CameraTranslator,
     handle_frames: Query<(&ControlHandle, &Children)>,
     handles: Query<
-        (&GlobalTransform, Option<&ControlHandleRotation>),
+        (
+            &GlobalTransform,
+            Option<&ControlHandleRotation>,
+            Has<HandleHovered>,
+        ),
         Or<(With<ControlHandleCorner>, With<ControlHandleRotation>)>,
     >,
     frame: Query<(&GlobalTransform, &Sprite)>,
+    culled: Query<(), With<Culled>>,
+    handle_scale: Res<HandleScale>,
+    settings: Res<ControlHandleSettings>,
+    theme: Res<HandleTheme>,
     mut painter: ShapePainter,
 ) -> Result {
     painter.render_layers = Some(CONTROL_LAYER);
 
+    let radius = CORNER_HANDLE_RADIUS * handle_scale.0;
+
     for (handle, children) in handle_frames.iter() {
+        if culled.contains(handle.0) {
+            continue;
+        }
         let (sprite_transform, sprite) = frame.get(handle.0)?;
 
         let Some(sprite_size) = sprite.custom_size else {
@@ -500,31 +978,37 @@ fn draw_control_handle(
 
         // border
         painter.hollow = true;
-        painter.color = Color::WHITE;
-        painter.thickness = HANDLE_WIDTH;
+        painter.color = theme.border;
+        painter.thickness = HANDLE_WIDTH * handle_scale.0;
         painter.rect(frame_size);
 
-        for (transform, rotation_handle) in handles.iter_many(children) {
+        for (transform, rotation_handle, hovered) in handles.iter_many(children) {
+            let radius = if hovered {
+                radius * HOVERED_HANDLE_SCALE
+            } else {
+                radius
+            };
             painter.transform.translation = transform.translation().with_z(3.0);
             painter.hollow = false;
             painter.thickness = 0.0;
-            painter.color = Color::WHITE;
-            painter.circle(CORNER_HANDLE_RADIUS);
+            painter.color = theme.fill;
+            painter.circle(radius);
 
             painter.hollow = true;
-            painter.color = LIGHT_GRAY.into();
-            painter.thickness = 1.0;
-            painter.circle(CORNER_HANDLE_RADIUS + painter.thickness / 2.);
+            painter.color = theme.ring;
+            painter.thickness = handle_scale.0;
+            painter.circle(radius + painter.thickness / 2.);
 
             if let Some(rotation_handle) = rotation_handle {
                 painter.transform = frame_transform;
-                painter.color = Color::WHITE;
+                painter.color = theme.rotation_line;
 
                 let v = rotation_handle.0.as_vec();
                 let start = v * frame_size;
                 painter.line(
                     start.extend(0.0),
-                    (start + v.normalize() * ROTATION_HANDLE_EXTENSION).extend(0.0),
+                    (start + v.normalize() * settings.rotation_handle_extension(frame_size))
+                        .extend(0.0),
                 );
             }
         }
@@ -532,3 +1016,161 @@ fn draw_control_handle(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_theme_config_round_trip() {
+        let theme = HandleTheme {
+            fill: Color::BLACK,
+            ring: Color::srgba(1.0, 0.0, 0.0, 0.5),
+            ..default()
+        };
+
+        let mut loaded = HandleTheme::default();
+        loaded.apply_config(&theme.to_config()).unwrap();
+        assert_eq!(loaded.to_config(), theme.to_config());
+
+        assert!(loaded.apply_config("glow = #FFFFFF").is_err());
+    }
+
+    #[test]
+    fn test_rotation_towards_center() {
+        let pivot = Pivot::TopCenter.as_vec();
+        let rotation = rotation_towards(pivot, Vec2::new(-1.0, 0.0)).unwrap();
+        assert!((rotation_degrees(rotation) - 90.0).abs() < 1e-3);
+
+        // The cursor exactly on the center leaves the rotation unchanged
+        let mut transform = Transform::from_rotation(rotation);
+        if let Some(rotation) = rotation_towards(pivot, Vec2::ZERO) {
+            transform.rotation = rotation;
+        }
+        assert_eq!(transform.rotation, rotation);
+        assert!(!transform.rotation.is_nan());
+
+        assert_eq!(rotation_towards(Vec2::ZERO, Vec2::X), None);
+        assert_eq!(rotation_towards(pivot, Vec2::splat(1e-6)), None);
+    }
+
+    #[test]
+    fn test_rotation_handle_extension() {
+        let settings = ControlHandleSettings::default();
+
+        // Kept away from the corner handles of a tiny sprite
+        assert_eq!(
+            settings.rotation_handle_extension(Vec2::new(4.0, 2.0)),
+            settings.min_rotation_handle_extension
+        );
+        // Grows with the sprite
+        assert_eq!(
+            settings.rotation_handle_extension(Vec2::new(200.0, 400.0)),
+            50.0
+        );
+        // But stays within reach for a large sprite
+        assert_eq!(
+            settings.rotation_handle_extension(Vec2::new(4000.0, 3000.0)),
+            settings.max_rotation_handle_extension
+        );
+        // Flipped sizes are negative
+        assert_eq!(
+            settings.rotation_handle_extension(Vec2::new(-240.0, 100.0)),
+            30.0
+        );
+
+        // Swapped bounds are used in order instead of panicking
+        let swapped = ControlHandleSettings {
+            min_rotation_handle_extension: 80.0,
+            max_rotation_handle_extension: 20.0,
+            ..default()
+        };
+        assert_eq!(swapped.rotation_handle_extension(Vec2::new(4.0, 2.0)), 20.0);
+        assert_eq!(
+            swapped.rotation_handle_extension(Vec2::new(200.0, 400.0)),
+            50.0
+        );
+        assert_eq!(
+            swapped.rotation_handle_extension(Vec2::new(4000.0, 3000.0)),
+            80.0
+        );
+    }
+
+    #[test]
+    fn test_snap_rotation() {
+        let snapped = rotation_degrees(snap_rotation(Quat::from_rotation_z(0.75), 15.0));
+        // 0.75 rad is about 43 degrees
+        assert!((snapped - 45.0).abs() < 1e-3);
+
+        let snapped = rotation_degrees(snap_rotation(Quat::from_rotation_z(-3.1), 15.0));
+        assert!((snapped.abs() - 180.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_match_angle() {
+        let others = [(0, 10.0), (1, 44.0), (2, -179.0), (3, 13.0)];
+
+        assert_eq!(match_angle(42.0, others, 3.0), Some((1, 44.0)));
+        // The nearest one within the threshold
+        assert_eq!(match_angle(12.0, others, 3.0), Some((3, 13.0)));
+        // Wraps around +-180 degrees
+        assert_eq!(match_angle(179.0, others, 3.0), Some((2, -179.0)));
+        assert_eq!(match_angle(90.0, others, 3.0), None);
+    }
+
+    #[test]
+    fn test_rotation_snap_input() {
+        let key_bindings = KeyBindings::default();
+        let mut input = ButtonInput::<KeyCode>::default();
+        let mut grid_snap = GridSnap {
+            enabled: true,
+            ..default()
+        };
+        let snap = |input: &ButtonInput<KeyCode>, grid_snap: &GridSnap| {
+            RotationSnap::from_input(&key_bindings, input, grid_snap)
+        };
+
+        assert_eq!(snap(&input, &grid_snap), RotationSnap::MatchAngle);
+        // Alt turns matching off while held
+        input.press(KeyCode::AltLeft);
+        assert_eq!(snap(&input, &grid_snap), RotationSnap::None);
+        input.release(KeyCode::AltLeft);
+        assert_eq!(snap(&input, &grid_snap), RotationSnap::MatchAngle);
+
+        input.press(KeyCode::ShiftLeft);
+        assert_eq!(snap(&input, &grid_snap), RotationSnap::Step);
+        input.release(KeyCode::ShiftLeft);
+
+        grid_snap.enabled = false;
+        assert_eq!(snap(&input, &grid_snap), RotationSnap::None);
+    }
+
+    #[test]
+    fn test_resize_pivots() {
+        let mut settings = ControlHandleSettings::default();
+        assert_eq!(settings.resize_pivots().len(), 4);
+
+        settings.edge_handles = true;
+        assert_eq!(settings.resize_pivots().len(), 8);
+        assert!(settings.resize_pivots().contains(&Pivot::CenterLeft));
+    }
+
+    #[test]
+    fn test_culled_frame_handles_unpickable() {
+        let mut world = World::new();
+        world.add_observer(on_frame_culled);
+        world.add_observer(on_frame_unculled);
+
+        let frame = world.spawn_empty().id();
+        let corner = world.spawn(ControlHandleCorner(Pivot::TopLeft)).id();
+        world.spawn(ControlHandle(frame)).add_child(corner);
+
+        world.entity_mut(frame).insert(Culled);
+        world.flush();
+        assert_eq!(world.get::<Pickable>(corner), Some(&Pickable::IGNORE));
+
+        world.entity_mut(frame).remove::<Culled>();
+        world.flush();
+        assert_eq!(world.get::<Pickable>(corner), Some(&Pickable::default()));
+    }
+}
@@ -0,0 +1,172 @@
+//! Adding many images at once.
+//!
+//! Files are queued in [`ImportQueue`] and loaded a few at a time, so that decoding hundreds
+//! of images doesn't stall the app. Frames are set up by `setup_sprite` as their images finish
+//! loading, and an import can be cancelled between batches.
+
+use std::{collections::VecDeque, path::PathBuf};
+
+use bevy::prelude::*;
+
+use crate::redraw::Redraw;
+
+use super::{Canvas, ImageFrame};
+
+/// Maximum number of images of an import loaded at once.
+const MAX_LOADING: usize = 8;
+
+pub struct ImportPlugin;
+
+impl Plugin for ImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, import_next.run_if(resource_exists::<ImportQueue>));
+    }
+}
+
+/// Files waiting to be added as frames. Present while an import is in progress.
+#[derive(Resource, Debug, Default)]
+pub struct ImportQueue {
+    /// Files with the canvas position to place their frames at, if any.
+    pending: VecDeque<(PathBuf, Option<Vec2>)>,
+    /// Number of images loading.
+    loading: usize,
+    /// Number of files queued since the import started.
+    total: usize,
+}
+
+impl ImportQueue {
+    fn add(&mut self, files: impl IntoIterator<Item = PathBuf>, position: Option<Vec2>) {
+        let len = self.pending.len();
+        self.pending
+            .extend(files.into_iter().map(|path| (path, position)));
+        self.total += self.pending.len() - len;
+    }
+
+    /// Number of files added or failed, and the number of files in the import.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.total - self.pending.len() - self.loading, self.total)
+    }
+
+    /// Stops queuing the remaining files. Images already loading are still added.
+    pub fn cancel(&mut self) {
+        info!("Cancelled importing {} file(s)", self.pending.len());
+        self.total -= self.pending.len();
+        self.pending.clear();
+    }
+}
+
+/// Frame of an import whose image is loading.
+#[derive(Component)]
+struct Importing;
+
+/// Queues `files` to be added to the canvas, joining the import in progress if any.
+pub fn import_files(commands: &mut Commands, files: Vec<PathBuf>) {
+    queue_files(commands, files, None);
+}
+
+/// Queues `files` like [`import_files`], placing their frames at `position` on the canvas.
+pub fn import_files_at(commands: &mut Commands, files: Vec<PathBuf>, position: Vec2) {
+    queue_files(commands, files, Some(position));
+}
+
+fn queue_files(commands: &mut Commands, files: Vec<PathBuf>, position: Option<Vec2>) {
+    if files.is_empty() {
+        return;
+    }
+    commands.queue(move |world: &mut World| {
+        world
+            .get_resource_or_init::<ImportQueue>()
+            .add(files, position);
+    });
+}
+
+/// Starts loading queued files as earlier ones finish.
+fn import_next(
+    mut commands: Commands,
+    mut queue: ResMut<ImportQueue>,
+    assets: Res<AssetServer>,
+    canvas: Single<Entity, With<Canvas>>,
+    importing: Query<(Entity, Has<Sprite>), With<Importing>>,
+    mut redraw: ResMut<Redraw>,
+) {
+    // Failed loads are despawned by `setup_sprite`
+    let mut loading = 0;
+    for (entity, loaded) in &importing {
+        if loaded {
+            commands.entity(entity).remove::<Importing>();
+        } else {
+            loading += 1;
+        }
+    }
+
+    while loading < MAX_LOADING
+        && let Some((path, position)) = queue.pending.pop_front()
+    {
+        let mut frame =
+            commands.spawn((ChildOf(*canvas), ImageFrame(assets.load(path)), Importing));
+        if let Some(position) = position {
+            frame.insert(Transform::from_translation(position.extend(0.0)));
+        }
+        loading += 1;
+    }
+    if queue.loading != loading {
+        queue.loading = loading;
+    }
+
+    if loading == 0 {
+        info!("Imported {} file(s)", queue.total);
+        commands.remove_resource::<ImportQueue>();
+        return;
+    }
+    // Loading doesn't wake up `desktop_app()`
+    redraw.request_redraw_once();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn test_import_progress() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_resource::<Redraw>();
+        let world = app.world_mut();
+        world.spawn(Canvas);
+        let files = (0..20).map(|i| PathBuf::from(format!("{i}.png")));
+        world.get_resource_or_init::<ImportQueue>().add(files, None);
+
+        let mut importing = world.query_filtered::<Entity, With<Importing>>();
+        let mut frames = world.query_filtered::<(), With<ImageFrame>>();
+        world.run_system_once(import_next).unwrap();
+        assert_eq!(importing.iter(world).count(), MAX_LOADING);
+        assert_eq!(world.resource::<ImportQueue>().progress(), (0, 20));
+
+        // Nothing more until an image loads
+        world.run_system_once(import_next).unwrap();
+        assert_eq!(frames.iter(world).count(), MAX_LOADING);
+
+        let loaded = importing.iter(world).next().unwrap();
+        world.entity_mut(loaded).insert(Sprite::default());
+        world.run_system_once(import_next).unwrap();
+        assert_eq!(importing.iter(world).count(), MAX_LOADING);
+        assert_eq!(frames.iter(world).count(), MAX_LOADING + 1);
+        assert_eq!(world.resource::<ImportQueue>().progress(), (1, 20));
+
+        // Images already loading are still added after cancelling, but no more
+        world.resource_mut::<ImportQueue>().cancel();
+        assert_eq!(
+            world.resource::<ImportQueue>().progress(),
+            (1, MAX_LOADING + 1)
+        );
+        for entity in importing.iter(world).collect::<Vec<_>>() {
+            world.entity_mut(entity).insert(Sprite::default());
+        }
+        world.run_system_once(import_next).unwrap();
+        assert_eq!(frames.iter(world).count(), MAX_LOADING + 1);
+        assert!(!world.contains_resource::<ImportQueue>());
+    }
+}
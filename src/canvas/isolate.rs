@@ -0,0 +1,119 @@
+//! Isolate mode: hides every frame except the selection to focus on editing it.
+//!
+//! Hidden frames keep their previous visibility in [`IsolationHidden`] and lose their
+//! [`Pickable`], so they can't be clicked or rectangle-selected until isolation ends.
+
+use bevy::prelude::*;
+
+use crate::key_bindings::{Action, action_just_pressed};
+
+use super::{Hovered, ImageFrame, Selected};
+
+pub struct IsolatePlugin;
+
+impl Plugin for IsolatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IsolateMode>().add_systems(
+            Update,
+            toggle_isolate_mode.run_if(action_just_pressed(Action::ToggleIsolateMode)),
+        );
+    }
+}
+
+/// Whether frames other than the isolated ones are hidden.
+#[derive(Resource, Default)]
+struct IsolateMode(bool);
+
+/// Frame hidden by isolate mode, with the state to restore on exit.
+#[derive(Component)]
+pub struct IsolationHidden {
+    /// Visibility before isolation. Systems that change the visibility of frames write here
+    /// instead while the frame is hidden.
+    pub visibility: Visibility,
+    pickable: Option<Pickable>,
+}
+
+fn toggle_isolate_mode(
+    mut commands: Commands,
+    mut mode: ResMut<IsolateMode>,
+    mut frames: Query<
+        (Entity, &mut Visibility, Option<&Pickable>, Has<Selected>),
+        (With<ImageFrame>, Without<IsolationHidden>),
+    >,
+    mut hidden: Query<(Entity, &IsolationHidden, &mut Visibility)>,
+) {
+    if mode.0 {
+        for (entity, isolation_hidden, mut visibility) in &mut hidden {
+            *visibility = isolation_hidden.visibility;
+            let mut entity = commands.entity(entity);
+            entity.remove::<IsolationHidden>();
+            if let Some(pickable) = isolation_hidden.pickable.clone() {
+                entity.insert(pickable);
+            }
+        }
+        mode.0 = false;
+        info!("Isolate mode disabled");
+        return;
+    }
+
+    if !frames.iter().any(|(.., selected)| selected) {
+        info!("Select frames to isolate");
+        return;
+    }
+
+    for (entity, mut visibility, pickable, selected) in &mut frames {
+        if selected {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .insert(IsolationHidden {
+                visibility: *visibility,
+                pickable: pickable.cloned(),
+            })
+            .remove::<(Pickable, Hovered)>();
+        *visibility = Visibility::Hidden;
+    }
+    mode.0 = true;
+    info!("Isolate mode enabled");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_isolate_mode() {
+        let mut world = World::new();
+        world.init_resource::<IsolateMode>();
+
+        let selected = world
+            .spawn((ImageFrame(default()), Visibility::Inherited, Selected))
+            .id();
+        let visible = world
+            .spawn((ImageFrame(default()), Visibility::Visible, Pickable::IGNORE))
+            .id();
+        let hidden = world
+            .spawn((ImageFrame(default()), Visibility::Hidden))
+            .id();
+
+        world.run_system_cached(toggle_isolate_mode).unwrap();
+        assert_eq!(
+            world.get::<Visibility>(selected),
+            Some(&Visibility::Inherited)
+        );
+        assert_eq!(world.get::<Visibility>(visible), Some(&Visibility::Hidden));
+        assert!(world.get::<Pickable>(visible).is_none());
+        assert!(world.get::<IsolationHidden>(hidden).is_some());
+
+        world.run_system_cached(toggle_isolate_mode).unwrap();
+        assert_eq!(
+            world.get::<Visibility>(selected),
+            Some(&Visibility::Inherited)
+        );
+        assert_eq!(world.get::<Visibility>(visible), Some(&Visibility::Visible));
+        assert_eq!(world.get::<Pickable>(visible), Some(&Pickable::IGNORE));
+        assert_eq!(world.get::<Visibility>(hidden), Some(&Visibility::Hidden));
+        assert!(world.get::<IsolationHidden>(hidden).is_none());
+    }
+}
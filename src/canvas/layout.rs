@@ -0,0 +1,625 @@
+//! Saving the layout of frames to a file, and periodic auto-save to a recovery file.
+//!
+//! A layout file has a line per frame: translation, rotation and size, then the image path.
+//! Optional properties follow the path as tab-separated `key=value` fields, so that older
+//! layout files still load.
+//! The recovery file is removed on a clean exit, so finding it on startup means the last
+//! session crashed.
+//!
+//! [`Dirty`] tracks changes not yet saved to the [`LayoutFile`], shown by a `*` in the window
+//! title.
+
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    diagnostic::FrameCount,
+    ecs::{entity::EntityHashMap, query::QueryItem},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    bevyhow,
+    config::{ConfigFile, config_path, save_config},
+    key_bindings::{Action, action_just_pressed},
+    modal,
+    redraw::send_redraw_request,
+};
+
+use super::{
+    Canvas, ImageFrame, handle::HandleTheme, naming::FrameName, pin::ScreenPinned,
+    sampling::SampleMode, style::FrameStyle,
+};
+
+/// File name of the default recovery file in the config directory.
+const RECOVERY_FILE: &str = "recovery.layout";
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AutoSave::load())
+            .register_type::<AutoSave>()
+            .init_resource::<AutoSaveState>()
+            .init_resource::<Dirty>()
+            .init_resource::<LayoutFile>()
+            .add_systems(
+                Update,
+                (
+                    save.run_if(action_just_pressed(Action::SaveLayout)),
+                    mark_layout_changed,
+                    mark_settings_changed::<AutoSave>,
+                    mark_settings_changed::<HandleTheme>,
+                    auto_save,
+                    update_title.run_if(resource_changed::<Dirty>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Last,
+                (
+                    remove_recovery_on_exit,
+                    save_config::<AutoSave>.before(send_redraw_request),
+                ),
+            );
+    }
+}
+
+/// Periodic save of the layout to a recovery file. Disabled by default. `enabled` and
+/// `interval` are saved to the config directory when changed.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct AutoSave {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub path: PathBuf,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(60),
+            path: config_path(RECOVERY_FILE).unwrap_or_else(|| PathBuf::from(RECOVERY_FILE)),
+        }
+    }
+}
+
+impl AutoSave {
+    /// Whether a recovery file was left by a previous session.
+    pub fn has_recovery(&self) -> bool {
+        self.path.is_file()
+    }
+}
+
+impl ConfigFile for AutoSave {
+    const FILE_NAME: &str = "auto_save.cfg";
+
+    fn to_config(&self) -> String {
+        format!(
+            "enabled = {}\ninterval = {}\n",
+            self.enabled,
+            self.interval.as_secs_f32()
+        )
+    }
+
+    fn apply_config(&mut self, config: &str) -> Result {
+        for line in config.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| bevyhow!("Invalid line: {line}"))?;
+            match name.trim() {
+                "enabled" => self.enabled = value.trim().parse()?,
+                "interval" => self.interval = Duration::try_from_secs_f32(value.trim().parse()?)?,
+                name => return Err(bevyhow!("Unknown setting: {name}")),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether frames were added, removed, moved or resized, or saved settings changed, since the
+/// layout was last saved to [`LayoutFile`].
+#[derive(Resource, Default, PartialEq)]
+pub struct Dirty(pub bool);
+
+/// File the layout was last saved to.
+#[derive(Resource, Default)]
+pub struct LayoutFile(pub Option<PathBuf>);
+
+#[derive(Resource, Default)]
+struct AutoSaveState {
+    /// Whether frames changed since the last auto-save.
+    changed: bool,
+    last_save: Duration,
+}
+
+/// A frame in a layout file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedFrame {
+    pub image_path: String,
+    pub translation: Vec3,
+    /// Rotation around the z axis in radians.
+    pub rotation: f32,
+    pub size: Vec2,
+    pub name: Option<String>,
+    pub style: FrameStyle,
+    /// Offset from the view center and scale per camera scale of a [`ScreenPinned`] frame.
+    pub pin: Option<(Vec2, f32)>,
+    /// Filtering of the frame. Frames of layouts saved without it follow `ImportSettings`.
+    pub sample_mode: Option<SampleMode>,
+}
+
+/// Size of a frame restored from a layout, used by `setup_sprite` instead of the image size.
+#[derive(Component)]
+pub struct SavedSize(pub Vec2);
+
+/// Style of a frame restored from a layout, applied by `setup_sprite`.
+#[derive(Component)]
+pub struct SavedStyle(pub FrameStyle);
+
+/// Frame components saved to a layout file.
+pub type LayoutData = (
+    &'static ImageFrame,
+    &'static Transform,
+    &'static Sprite,
+    Option<&'static FrameName>,
+    Option<&'static ScreenPinned>,
+    Option<&'static SampleMode>,
+);
+
+impl SavedFrame {
+    /// Tab-separated `key=value` properties written after the image path, each with a
+    /// leading tab. Properties at their default are left out.
+    fn properties(&self) -> String {
+        let mut properties = String::new();
+        if let Some(name) = &self.name {
+            // Tabs and line breaks would end the property
+            let name = name.replace(['\t', '\n', '\r'], " ");
+            let _ = write!(properties, "\tname={name}");
+        }
+        let flip = match (self.style.flip_x, self.style.flip_y) {
+            (false, false) => "",
+            (true, false) => "x",
+            (false, true) => "y",
+            (true, true) => "xy",
+        };
+        if !flip.is_empty() {
+            let _ = write!(properties, "\tflip={flip}");
+        }
+        if self.style.color != Color::WHITE {
+            let _ = write!(
+                properties,
+                "\tcolor={}",
+                self.style.color.to_srgba().to_hex()
+            );
+        }
+        if let Some((offset, scale)) = self.pin {
+            let _ = write!(properties, "\tpin={},{},{scale}", offset.x, offset.y);
+        }
+        if let Some(sample_mode) = self.sample_mode {
+            let _ = write!(properties, "\tsample={}", sample_mode.to_property());
+        }
+        properties
+    }
+
+    fn apply_property(&mut self, property: &str) -> Result {
+        let (key, value) = property
+            .split_once('=')
+            .ok_or_else(|| bevyhow!("Invalid property: {property}"))?;
+        match key {
+            "name" => self.name = Some(value.to_string()),
+            "flip" => {
+                self.style.flip_x = value.contains('x');
+                self.style.flip_y = value.contains('y');
+            }
+            "color" => self.style.color = Srgba::hex(value)?.into(),
+            "pin" => {
+                let numbers = value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<f32>, _>>()?;
+                let [x, y, scale] = numbers[..] else {
+                    return Err(bevyhow!("Invalid pin: {value}"));
+                };
+                self.pin = Some((Vec2::new(x, y), scale));
+            }
+            "sample" => {
+                let sample_mode = SampleMode::from_property(value)
+                    .ok_or_else(|| bevyhow!("Invalid sample mode: {value}"))?;
+                self.sample_mode = Some(sample_mode);
+            }
+            // Written by a newer version
+            _ => debug!("Unknown layout property: {key}"),
+        }
+        Ok(())
+    }
+}
+
+pub fn layout_to_string(frames: &[SavedFrame]) -> String {
+    let mut layout = String::new();
+    for frame in frames {
+        let t = frame.translation;
+        // Infallible for `String`
+        let _ = writeln!(
+            layout,
+            "{} {} {} {} {} {} {}{}",
+            t.x,
+            t.y,
+            t.z,
+            frame.rotation,
+            frame.size.x,
+            frame.size.y,
+            frame.image_path,
+            frame.properties()
+        );
+    }
+    layout
+}
+
+pub fn parse_layout(layout: &str) -> Result<Vec<SavedFrame>> {
+    layout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            // The path comes last as it may contain spaces
+            let mut fields = line.splitn(7, ' ');
+            let mut number = || -> Result<f32> {
+                let field = fields
+                    .next()
+                    .ok_or_else(|| bevyhow!("Missing field: {line}"))?;
+                Ok(field.parse()?)
+            };
+            let translation = Vec3::new(number()?, number()?, number()?);
+            let rotation = number()?;
+            let size = Vec2::new(number()?, number()?);
+            let mut rest = fields
+                .next()
+                .ok_or_else(|| bevyhow!("Missing image path: {line}"))?
+                .split('\t');
+            let mut frame = SavedFrame {
+                // `split` yields at least one item
+                image_path: rest.next().unwrap_or_default().to_string(),
+                translation,
+                rotation,
+                size,
+                name: None,
+                style: FrameStyle::of(&Sprite::default()),
+                pin: None,
+                sample_mode: None,
+            };
+            for property in rest {
+                frame.apply_property(property)?;
+            }
+            Ok(frame)
+        })
+        .collect()
+}
+
+/// Writes the layout of loaded frames to `path`.
+pub fn save_layout<'a>(
+    path: &Path,
+    frames: impl IntoIterator<Item = QueryItem<'a, LayoutData>>,
+) -> Result {
+    let mut frames = frames
+        .into_iter()
+        .filter_map(
+            |(image_frame, transform, sprite, name, pinned, sample_mode)| {
+                Some(SavedFrame {
+                    image_path: image_frame.0.path()?.to_string(),
+                    translation: transform.translation,
+                    rotation: transform.rotation.to_euler(EulerRot::ZYX).0,
+                    size: sprite.custom_size?,
+                    name: name.map(|name| name.0.clone()),
+                    style: FrameStyle::of(sprite),
+                    pin: pinned.map(ScreenPinned::saved),
+                    sample_mode: sample_mode.copied(),
+                })
+            },
+        )
+        .collect::<Vec<_>>();
+    // Frames are stacked in the order they are loaded
+    frames.sort_by(|a, b| a.translation.z.total_cmp(&b.translation.z));
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, layout_to_string(&frames))?;
+    Ok(())
+}
+
+/// One-shot system to add the frames of the layout file at `path` to the canvas. Use
+/// `Commands::run_system_cached_with` to run it.
+pub fn load_layout(
+    In(path): In<PathBuf>,
+    mut commands: Commands,
+    canvas: Single<Entity, With<Canvas>>,
+    assets: Res<AssetServer>,
+) {
+    let frames = match std::fs::read_to_string(&path)
+        .map_err(BevyError::from)
+        .and_then(|layout| parse_layout(&layout))
+    {
+        Ok(frames) => frames,
+        Err(error) => {
+            warn!("Failed to load {}: {error}", path.display());
+            return;
+        }
+    };
+
+    for frame in &frames {
+        let mut entity = commands.spawn((
+            ImageFrame(assets.load(&frame.image_path)),
+            Transform::from_translation(frame.translation)
+                .with_rotation(Quat::from_rotation_z(frame.rotation)),
+            SavedSize(frame.size),
+            SavedStyle(frame.style),
+            ChildOf(*canvas),
+        ));
+        if let Some(name) = &frame.name {
+            entity.insert((FrameName(name.clone()), Name::new(name.clone())));
+        }
+        if let Some((offset, scale)) = frame.pin {
+            entity.insert(ScreenPinned::restore(offset, scale, frame.translation.xy()));
+        }
+        if let Some(sample_mode) = frame.sample_mode {
+            entity.insert(sample_mode);
+        }
+    }
+    info!("Loaded {} frame(s) from {}", frames.len(), path.display());
+}
+
+/// Saves the layout to [`LayoutFile`], asking for a path the first time. Returns whether it
+/// was saved.
+pub fn save_layout_file(world: &mut World) -> bool {
+    let path = match world.resource::<LayoutFile>().0.clone() {
+        Some(path) => path,
+        None => {
+            let path = rfd::FileDialog::new()
+                .add_filter("Layout", &["layout"])
+                .save_file();
+            modal::block_native_dialog(world.resource::<FrameCount>()).apply(world);
+            let Some(path) = path else {
+                return false;
+            };
+            path
+        }
+    };
+
+    let mut frames = world.query::<LayoutData>();
+    if let Err(error) = save_layout(&path, frames.iter(world)) {
+        warn!("Failed to save {}: {error}", path.display());
+        return false;
+    }
+    info!("Saved the layout to {}", path.display());
+
+    world.resource_mut::<LayoutFile>().0 = Some(path);
+    world.resource_mut::<Dirty>().set_if_neq(Dirty(false));
+    true
+}
+
+fn save(world: &mut World) {
+    save_layout_file(world);
+}
+
+/// What is saved of the placement of a pinned frame: its [`ScreenPinned::saved`] offset and
+/// scale, z and rotation.
+type PinnedPlacement = ((Vec2, f32), f32, Quat);
+
+/// Sets [`Dirty`] when frames change. The translation and scale of pinned frames follow the
+/// camera, so only their pinned placement counts.
+fn mark_layout_changed(
+    mut state: ResMut<AutoSaveState>,
+    mut dirty: ResMut<Dirty>,
+    changed: Query<
+        (),
+        (
+            With<ImageFrame>,
+            Without<ScreenPinned>,
+            Or<(Changed<Transform>, Changed<Sprite>, Changed<FrameName>)>,
+        ),
+    >,
+    changed_pinned: Query<
+        (),
+        (
+            With<ImageFrame>,
+            Or<(Added<ScreenPinned>, Changed<Sprite>, Changed<FrameName>)>,
+        ),
+    >,
+    moved_pinned: Query<
+        (Entity, &ScreenPinned, &Transform),
+        Or<(Changed<ScreenPinned>, Changed<Transform>)>,
+    >,
+    mut placements: Local<EntityHashMap<PinnedPlacement>>,
+    mut removed: RemovedComponents<ImageFrame>,
+    mut removed_names: RemovedComponents<FrameName>,
+    mut removed_pins: RemovedComponents<ScreenPinned>,
+) {
+    let mut moved = false;
+    for (entity, pinned, transform) in &moved_pinned {
+        let placement = (pinned.saved(), transform.translation.z, transform.rotation);
+        moved |= placements
+            .insert(entity, placement)
+            .is_some_and(|previous| previous != placement);
+    }
+    for entity in removed_pins.read() {
+        placements.remove(&entity);
+        moved = true;
+    }
+
+    if moved
+        || !changed.is_empty()
+        || !changed_pinned.is_empty()
+        || removed.read().count() + removed_names.read().count() > 0
+    {
+        state.changed = true;
+        dirty.set_if_neq(Dirty(true));
+    }
+}
+
+/// Sets [`Dirty`] when the settings saved to the config file of `R` change.
+fn mark_settings_changed<R: ConfigFile>(config: Res<R>, mut dirty: ResMut<Dirty>) {
+    // Loaded from the file already
+    if config.is_changed() && !config.is_added() {
+        dirty.set_if_neq(Dirty(true));
+    }
+}
+
+fn update_title(dirty: Res<Dirty>, mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    let title = window.title.trim_end_matches(" *").to_string();
+    window.title = if dirty.0 { format!("{title} *") } else { title };
+}
+
+/// Saves the layout to [`AutoSave::path`] once the interval has passed since the last save,
+/// if frames changed. Runs only when the app updates, so a save may come later while idle.
+fn auto_save(
+    settings: Res<AutoSave>,
+    mut state: ResMut<AutoSaveState>,
+    time: Res<Time<Real>>,
+    frames: Query<LayoutData>,
+) {
+    if !settings.enabled || !state.changed || time.elapsed() < state.last_save + settings.interval {
+        return;
+    }
+
+    state.last_save = time.elapsed();
+    state.changed = false;
+    match save_layout(&settings.path, frames) {
+        Ok(()) => debug!("Auto-saved the layout to {}", settings.path.display()),
+        Err(error) => warn!("Failed to auto-save the layout: {error}"),
+    }
+}
+
+fn remove_recovery_on_exit(mut exit: EventReader<AppExit>, settings: Res<AutoSave>) {
+    if exit.read().count() == 0 || !settings.has_recovery() {
+        return;
+    }
+    if let Err(error) = std::fs::remove_file(&settings.path) {
+        warn!("Failed to remove {}: {error}", settings.path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_round_trip() {
+        let frames = vec![
+            SavedFrame {
+                image_path: "images/a.png".to_string(),
+                translation: Vec3::new(1.5, -2.0, 0.25),
+                rotation: 0.5,
+                size: Vec2::new(16.0, 32.0),
+                name: None,
+                style: FrameStyle::of(&Sprite::default()),
+                pin: None,
+                sample_mode: None,
+            },
+            SavedFrame {
+                image_path: "/home/user/my images/b.png".to_string(),
+                translation: Vec3::ZERO,
+                rotation: 0.0,
+                size: Vec2::splat(8.0),
+                name: Some("tile 1".to_string()),
+                style: FrameStyle {
+                    color: Srgba::hex("#FF000080").unwrap().into(),
+                    flip_x: true,
+                    flip_y: false,
+                },
+                pin: Some((Vec2::new(-20.0, 10.5), 0.5)),
+                sample_mode: Some(SampleMode::Linear),
+            },
+        ];
+
+        assert_eq!(parse_layout(&layout_to_string(&frames)).unwrap(), frames);
+        assert!(parse_layout("1 2 3 path.png").is_err());
+        assert!(parse_layout("0 0 0 0 8 8 images/a.png\tsample=blurry").is_err());
+
+        // Layouts without properties still load
+        let frames = parse_layout("0 0 0 0 8 8 images/a.png").unwrap();
+        assert_eq!(frames[0].image_path, "images/a.png");
+        assert_eq!(frames[0].name, None);
+    }
+
+    #[test]
+    fn test_pinned_frame_changes() {
+        let mut world = World::new();
+        world.init_resource::<AutoSaveState>();
+        world.init_resource::<Dirty>();
+        let frame = world
+            .spawn((
+                ImageFrame(default()),
+                Transform::default(),
+                Sprite::default(),
+                ScreenPinned::restore(Vec2::ZERO, 1.0, Vec2::ZERO),
+            ))
+            .id();
+        let mark = |world: &mut World| {
+            world.resource_mut::<Dirty>().0 = false;
+            world.run_system_cached(mark_layout_changed).unwrap();
+            world.resource::<Dirty>().0
+        };
+        assert!(mark(&mut world));
+
+        // Following the camera
+        world.entity_mut(frame).insert((
+            Transform::from_xyz(5.0, 0.0, 0.0),
+            ScreenPinned::restore(Vec2::ZERO, 1.0, Vec2::new(5.0, 0.0)),
+        ));
+        assert!(!mark(&mut world));
+
+        // Dragged on screen
+        world
+            .entity_mut(frame)
+            .insert(ScreenPinned::restore(Vec2::X, 1.0, Vec2::new(5.0, 0.0)));
+        assert!(mark(&mut world));
+
+        world.entity_mut(frame).remove::<ScreenPinned>();
+        assert!(mark(&mut world));
+    }
+
+    #[test]
+    fn test_auto_save_config() {
+        let auto_save = AutoSave {
+            enabled: true,
+            interval: Duration::from_secs(30),
+            ..default()
+        };
+        let mut loaded = AutoSave::default();
+        loaded.apply_config(&auto_save.to_config()).unwrap();
+        assert!(loaded.enabled);
+        assert_eq!(loaded.interval, Duration::from_secs(30));
+        assert!(loaded.apply_config("enabled = maybe").is_err());
+    }
+
+    #[test]
+    fn test_settings_changes() {
+        let mut world = World::new();
+        world.init_resource::<Dirty>();
+        world.init_resource::<AutoSave>();
+        world.init_resource::<HandleTheme>();
+        let mark = |world: &mut World| {
+            world.resource_mut::<Dirty>().0 = false;
+            world
+                .run_system_cached(mark_settings_changed::<AutoSave>)
+                .unwrap();
+            world
+                .run_system_cached(mark_settings_changed::<HandleTheme>)
+                .unwrap();
+            world.resource::<Dirty>().0
+        };
+        // Loaded settings
+        assert!(!mark(&mut world));
+
+        world.resource_mut::<AutoSave>().enabled = true;
+        assert!(mark(&mut world));
+        assert!(!mark(&mut world));
+        world.resource_mut::<HandleTheme>().fill = Color::BLACK;
+        assert!(mark(&mut world));
+    }
+}
@@ -0,0 +1,261 @@
+//! Adjustable marquee selection.
+//!
+//! With [`MarqueeSettings::adjustable`], a rectangle selection drag leaves a [`PendingMarquee`]
+//! instead of selecting right away. Its edges and body can be dragged to resize or move the
+//! region, which is then selected on [`Action::CommitMarquee`] or discarded on
+//! [`Action::CancelMarquee`].
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::RectPainter};
+
+use crate::{
+    despawn::SafeDespawn,
+    key_bindings::{Action, KeyBindings, action_just_pressed},
+    viewport_delta::PointerDelta,
+};
+
+use super::{
+    CONTROL_LAYER, MainCamera, SelectionRegion, camera_util::CameraTranslator, pan::PanBinding,
+    picking::PickingAreaRect, select_frames,
+};
+
+/// Half of the width of the edge areas in logical pixels.
+const EDGE_HALF_WIDTH: f32 = 6.0;
+
+pub struct MarqueePlugin;
+
+impl Plugin for MarqueePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MarqueeSettings>()
+            .register_type::<MarqueeSettings>()
+            .add_systems(
+                Update,
+                (
+                    spawn_marquee_areas.run_if(resource_added::<PendingMarquee>),
+                    despawn_marquee_areas.run_if(resource_removed::<PendingMarquee>),
+                    commit_marquee.run_if(action_just_pressed(Action::CommitMarquee)),
+                    cancel_marquee.run_if(action_just_pressed(Action::CancelMarquee)),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (update_marquee_areas, draw_pending_marquee)
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(resource_exists::<PendingMarquee>),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource, Default)]
+pub struct MarqueeSettings {
+    /// Keep the rectangle of a selection drag adjustable until committed.
+    pub adjustable: bool,
+}
+
+/// Rectangle selection waiting to be committed.
+#[derive(Resource)]
+pub struct PendingMarquee {
+    /// Region in [`MainCamera`] world space.
+    rect: Rect,
+    add_to_selection: bool,
+}
+
+impl PendingMarquee {
+    pub fn new(rect: Rect, add_to_selection: bool) -> Self {
+        Self {
+            rect,
+            add_to_selection,
+        }
+    }
+}
+
+/// Draggable part of the [`PendingMarquee`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum MarqueeArea {
+    /// Moves the whole region.
+    Body,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl MarqueeArea {
+    /// Returns `rect` with this part moved by `delta`.
+    fn drag(&self, rect: Rect, delta: Vec2) -> Rect {
+        let (mut min, mut max) = (rect.min, rect.max);
+        match self {
+            MarqueeArea::Body => {
+                min += delta;
+                max += delta;
+            }
+            MarqueeArea::Left => min.x += delta.x,
+            MarqueeArea::Right => max.x += delta.x,
+            MarqueeArea::Top => max.y += delta.y,
+            MarqueeArea::Bottom => min.y += delta.y,
+        }
+        // Dragging an edge past the opposite one flips the region
+        Rect::from_corners(min, max)
+    }
+
+    /// Center and half size of the area for the region `rect` in control space.
+    fn bounds(&self, rect: Rect) -> (Vec2, Vec2) {
+        let center = rect.center();
+        let half = rect.half_size();
+        match self {
+            MarqueeArea::Body => (center, half),
+            MarqueeArea::Left => (
+                Vec2::new(rect.min.x, center.y),
+                Vec2::new(EDGE_HALF_WIDTH, half.y),
+            ),
+            MarqueeArea::Right => (
+                Vec2::new(rect.max.x, center.y),
+                Vec2::new(EDGE_HALF_WIDTH, half.y),
+            ),
+            MarqueeArea::Top => (
+                Vec2::new(center.x, rect.max.y),
+                Vec2::new(half.x, EDGE_HALF_WIDTH),
+            ),
+            MarqueeArea::Bottom => (
+                Vec2::new(center.x, rect.min.y),
+                Vec2::new(half.x, EDGE_HALF_WIDTH),
+            ),
+        }
+    }
+}
+
+fn spawn_marquee_areas(mut commands: Commands) {
+    for area in [
+        MarqueeArea::Body,
+        MarqueeArea::Left,
+        MarqueeArea::Right,
+        MarqueeArea::Top,
+        MarqueeArea::Bottom,
+    ] {
+        // Edges are picked before the body
+        let z = if area == MarqueeArea::Body { 0.0 } else { 1.0 };
+        commands
+            .spawn((
+                Name::new(format!("MarqueeArea{area:?}")),
+                area,
+                PickingAreaRect(Rectangle::default()),
+                Transform::from_xyz(0.0, 0.0, z),
+                CONTROL_LAYER,
+            ))
+            .observe(drag_marquee_area);
+    }
+}
+
+fn despawn_marquee_areas(mut commands: Commands, areas: Query<Entity, With<MarqueeArea>>) {
+    for entity in &areas {
+        commands.safe_despawn(entity);
+    }
+}
+
+fn drag_marquee_area(
+    mut trigger: Trigger<Pointer<Drag>>,
+    areas: Query<&MarqueeArea>,
+    pending: Option<ResMut<PendingMarquee>>,
+    viewport_delta: PointerDelta<With<MainCamera>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pan_binding: Res<PanBinding>,
+) {
+    if trigger.button != PointerButton::Primary
+        || pan_binding.is_pan(trigger.button, &keyboard_input)
+    {
+        return;
+    }
+    trigger.propagate(false);
+
+    let (Ok(area), Some(mut pending)) = (areas.get(trigger.target()), pending) else {
+        return;
+    };
+    if let Some((world_delta, _)) =
+        viewport_delta.get_world(&trigger.pointer_location, trigger.delta)
+    {
+        pending.rect = area.drag(pending.rect, world_delta);
+    }
+}
+
+fn update_marquee_areas(
+    pending: Res<PendingMarquee>,
+    camera_translator: CameraTranslator,
+    mut areas: Query<(&MarqueeArea, &mut PickingAreaRect, &mut Transform)>,
+) -> Result {
+    let rect = camera_translator.map_rect_to_control(&pending.rect)?;
+    for (area, mut picking_area, mut transform) in &mut areas {
+        let (center, half_size) = area.bounds(rect);
+        picking_area.0.half_size = half_size;
+        transform.translation = center.extend(transform.translation.z);
+    }
+    Ok(())
+}
+
+fn draw_pending_marquee(
+    pending: Res<PendingMarquee>,
+    camera_translator: CameraTranslator,
+    mut painter: ShapePainter,
+) -> Result {
+    let rect = camera_translator.map_rect_to_control(&pending.rect)?;
+
+    painter.render_layers = Some(CONTROL_LAYER);
+    painter.hollow = true;
+    painter.thickness = 2.0;
+    painter.color = Color::srgba(0.5, 0.5, 1.0, 0.8);
+    painter.transform = Transform::from_translation(rect.center().extend(0.0));
+    painter.rect(rect.size());
+
+    Ok(())
+}
+
+fn commit_marquee(
+    mut commands: Commands,
+    pending: Option<Res<PendingMarquee>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    // Holding the modifier when committing adds to the selection too
+    let add_to_selection =
+        pending.add_to_selection || key_bindings.pressed(Action::AddToSelection, &keyboard_input);
+    commands.run_system_cached_with(
+        select_frames,
+        (SelectionRegion::Rect(pending.rect), add_to_selection),
+    );
+    commands.remove_resource::<PendingMarquee>();
+}
+
+fn cancel_marquee(mut commands: Commands) {
+    commands.remove_resource::<PendingMarquee>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_marquee_area() {
+        let rect = Rect::new(0.0, 0.0, 10.0, 20.0);
+
+        assert_eq!(
+            MarqueeArea::Body.drag(rect, Vec2::new(5.0, -5.0)),
+            Rect::new(5.0, -5.0, 15.0, 15.0)
+        );
+        assert_eq!(
+            MarqueeArea::Right.drag(rect, Vec2::new(5.0, 100.0)),
+            Rect::new(0.0, 0.0, 15.0, 20.0)
+        );
+        assert_eq!(
+            MarqueeArea::Top.drag(rect, Vec2::new(0.0, -4.0)),
+            Rect::new(0.0, 0.0, 10.0, 16.0)
+        );
+        // Past the opposite edge
+        assert_eq!(
+            MarqueeArea::Left.drag(rect, Vec2::new(15.0, 0.0)),
+            Rect::new(10.0, 0.0, 15.0, 20.0)
+        );
+    }
+}
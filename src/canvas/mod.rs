@@ -1,34 +1,76 @@
+use std::path::PathBuf;
+
 use crate::{
+    cursor::{CursorSource, Cursors},
+    despawn::SafeDespawn,
+    key_bindings::{Action, KeyBindings},
+    modal::ModalActive,
     packing::{EdgeVectors, ShapePosition},
+    redraw::Redraw,
     sprite_picking::{SpritePickingMode, SpritePickingSettings},
     viewport_delta::PointerDelta,
 };
 use bevy::{
     asset::LoadState,
     ecs::schedule::common_conditions,
+    picking::pointer::PointerId,
+    platform::collections::HashMap,
     prelude::*,
-    render::view::RenderLayers,
-    window::{PrimaryWindow, RequestRedraw},
+    render::{camera::CameraUpdateSystem, view::RenderLayers},
+    window::{PrimaryWindow, SystemCursorIcon},
 };
 use bevy_vector_shapes::{
     Shape2dPlugin,
     prelude::ShapePainter,
     shapes::{DiscPainter, LinePainter, RectPainter},
 };
+use camera_tween::CameraTween;
 use camera_util::CameraTranslator;
+use culling::Culled;
 use handle::{ControlHandle, CurrentControlHandle};
 
+pub mod arrange;
+mod camera_tween;
 mod camera_util;
+mod culling;
+mod edge_pan;
+pub mod export;
+mod eyedropper;
+pub mod grid_snap;
 mod handle;
+pub mod import;
+mod isolate;
+pub mod layout;
+mod marquee;
+pub mod naming;
+mod outline;
+mod overlap;
+pub mod pan;
 mod picking;
+pub mod pin;
+pub mod sampling;
+mod scale;
+mod selection_bounds;
+pub mod selection_history;
+mod shadow;
+pub mod style;
+pub mod undo;
+pub mod z_order;
+mod zoom;
 
 pub struct CanvasPlugin;
 
-/// Resource to track the state of a rectangular selection drag.
-#[derive(Default, Resource)]
+/// State of a rectangular selection drag.
+#[derive(Default)]
 struct SelectionDrag {
     start: Option<Vec2>,
     end: Option<Vec2>,
+    /// Zoom into the dragged region instead of selecting frames.
+    zoom: bool,
+    /// Select frames inside the freeform `path` instead of the rectangle.
+    lasso: bool,
+    /// Viewport points of the lasso path.
+    path: Vec<Vec2>,
 }
 
 impl SelectionDrag {
@@ -37,18 +79,90 @@ impl SelectionDrag {
     }
 }
 
+/// Resource to track the selection drags in progress, per pointer so that touches select
+/// independently.
+#[derive(Default, Resource, Deref, DerefMut)]
+struct SelectionDrags(HashMap<PointerId, SelectionDrag>);
+
+impl SelectionDrags {
+    fn is_dragging(&self) -> bool {
+        self.values().any(SelectionDrag::is_dragging)
+    }
+}
+
+/// Distance in logical pixels the pointer has to move from where a drag started before the
+/// drag moves a frame or draws a selection, so that a jittery click does neither.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, Default)]
+pub struct DragThreshold(pub f32);
+
+impl Default for DragThreshold {
+    fn default() -> Self {
+        Self(4.0)
+    }
+}
+
+/// Frame moved by a drag past the [`DragThreshold`].
+#[derive(Component)]
+struct DragMoving;
+
+/// Frame centered on the cursor by [`Action::GrabCenter`], with its transform before the drag.
+/// The whole drag is recorded for undo when it ends.
+#[derive(Component)]
+struct GrabbedCenter(Transform);
+
+/// Tool deciding what clicking a frame does.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Select frames.
+    #[default]
+    Select,
+    /// Copy the style of a frame to others. See [`eyedropper`].
+    Eyedropper,
+}
+
 impl Plugin for CanvasPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(SpritePickingSettings {
             require_markers: false,
             picking_mode: SpritePickingMode::BoundingBox,
         })
-        .insert_resource(SelectionDrag::default())
+        .init_resource::<SelectionDrags>()
+        .init_resource::<arrange::ArrangeSettings>()
+        .register_type::<arrange::ArrangeSettings>()
+        .init_resource::<DragThreshold>()
+        .register_type::<DragThreshold>()
+        .init_resource::<ImportSettings>()
+        .init_resource::<Tool>()
         .add_plugins(Shape2dPlugin::default())
         .add_plugins(picking::AreaPickingPlugin {
             require_markers: false,
         })
         .add_plugins(handle::ControlHandlePlugin)
+        .add_plugins(camera_tween::CameraTweenPlugin)
+        .add_plugins(selection_history::SelectionHistoryPlugin)
+        .add_plugins(selection_bounds::SelectionBoundsPlugin)
+        .add_plugins(edge_pan::EdgePanPlugin)
+        .add_plugins(outline::OutlinePlugin)
+        .add_plugins(culling::CullingPlugin)
+        .add_plugins(isolate::IsolatePlugin)
+        .add_plugins(undo::UndoPlugin)
+        .add_plugins(eyedropper::EyedropperPlugin)
+        .add_plugins(grid_snap::GridSnapPlugin)
+        .add_plugins(scale::ScalePlugin)
+        .add_plugins(overlap::OverlapPlugin)
+        .add_plugins(zoom::ZoomPlugin)
+        .add_plugins(marquee::MarqueePlugin)
+        .add_plugins(pan::PanPlugin)
+        .add_plugins(layout::LayoutPlugin)
+        .add_plugins(shadow::ShadowPlugin)
+        .add_plugins(pin::PinPlugin)
+        .add_plugins(import::ImportPlugin)
+        .add_plugins(sampling::SamplingPlugin)
+        .add_observer(z_order::on_add_frame_sprite)
+        .add_observer(z_order::on_remove_frame)
+        .add_observer(on_add_selected)
+        .add_observer(on_remove_selected)
         .add_systems(Startup, startup)
         .add_systems(
             Update,
@@ -58,6 +172,10 @@ impl Plugin for CanvasPlugin {
             ),
         )
         .add_systems(Update, draw_selection_rectangle)
+        .add_systems(
+            PostUpdate,
+            camera_util::sync_control_viewport.before(CameraUpdateSystem),
+        )
         .add_systems(
             Update,
             (
@@ -70,6 +188,7 @@ impl Plugin for CanvasPlugin {
             PostUpdate,
             draw_border
                 .after(TransformSystem::TransformPropagate)
+                .after(culling::cull_frames)
                 .run_if(|current: Option<Res<CurrentControlHandle>>| current.is_none()),
         );
     }
@@ -79,7 +198,7 @@ impl Plugin for CanvasPlugin {
 pub struct Canvas;
 
 #[derive(Component)]
-struct MainCamera;
+pub struct MainCamera;
 
 /// Camera for control handles.
 #[derive(Component)]
@@ -116,17 +235,21 @@ fn startup(world: &mut World) {
     world
         .entity_mut(primary_window)
         .observe(zoom_with_mouse_wheel)
-        .observe(drag_with_middle_mouse_button)
+        .observe(pan::drag_to_pan)
         .observe(
             |trigger: Trigger<Pointer<Click>>,
              mut commands: Commands,
+             modal: Option<Res<ModalActive>>,
+             panning: Res<pan::Panning>,
              #[cfg(feature = "dev")] egui_wants_input_resource: Res<
                 bevy_inspector_egui::bevy_egui::input::EguiWantsInput,
             >| {
-                if egui_wants_input_resource.wants_any_input() {
+                if modal.is_some() || egui_wants_input_resource.wants_any_input() {
                     return;
                 }
-                if trigger.event().button == PointerButton::Primary {
+                if trigger.event().button == PointerButton::Primary
+                    && !panning.is_panning(trigger.pointer_id)
+                {
                     commands.queue(handle::despawn_control_handle);
                 }
             },
@@ -148,47 +271,34 @@ fn dummy_paint(mut painter: ShapePainter) {
 
 fn zoom_with_mouse_wheel(
     trigger: Trigger<Pointer<Scroll>>,
-    mut camera: Query<&mut Transform, With<MainCamera>>,
+    mut commands: Commands,
+    mut camera: Query<(Entity, &mut Transform), With<MainCamera>>,
+    zoom_settings: Res<zoom::ZoomSettings>,
 ) {
-    let Ok(mut transform) = camera.single_mut() else {
+    let Ok((camera_id, mut transform)) = camera.single_mut() else {
         return;
     };
+    commands.entity(camera_id).remove::<CameraTween>();
 
     let event = trigger.event();
-    if event.y > 0.0 {
-        transform.scale *= Vec3::new(1.1, 1.1, 1.0);
-    } else {
-        transform.scale /= Vec3::new(1.1, 1.1, 1.0);
-    }
-}
-
-fn drag_with_middle_mouse_button(
-    trigger: Trigger<Pointer<Drag>>,
-    mut camera: Query<&mut Transform, With<Camera>>,
-    pointer_delta: PointerDelta<With<MainCamera>>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
-) {
-    if !mouse_buttons.pressed(MouseButton::Middle)
-        || mouse_buttons.any_pressed([MouseButton::Left, MouseButton::Right])
-    {
-        return;
-    }
-
-    let event = trigger.event();
-    if event.button != PointerButton::Middle {
-        return;
-    }
-    if let Some((world_delta, camera_id)) =
-        pointer_delta.get_world(&trigger.pointer_location, trigger.delta)
-        && let Ok(mut transform) = camera.get_mut(camera_id)
-    {
-        transform.translation -= world_delta.extend(0.0);
-    }
+    let scale = zoom_settings.step(transform.scale.x, event.y <= 0.0);
+    transform.scale = Vec3::new(scale, scale, transform.scale.z);
 }
 
 #[derive(Component)]
 pub struct ImageFrame(pub Handle<Image>);
 
+/// Settings applied when image frames are added to the canvas.
+#[derive(Resource, Default)]
+pub struct ImportSettings {
+    /// Select a newly added frame and attach the control handle to it.
+    pub select_on_add: bool,
+    /// Move a newly added frame overlapping existing frames to the nearest free spot.
+    pub avoid_overlap: bool,
+    /// Filtering of newly added frames.
+    pub sample_mode: sampling::SampleMode,
+}
+
 /// Currently hovered frame.
 #[derive(Component)]
 pub struct Hovered;
@@ -197,66 +307,280 @@ pub struct Hovered;
 #[derive(Component, Default)]
 pub struct Selected;
 
+/// Order in which a [`Selected`] frame was selected, increasing with each selection. Actions
+/// taking multiple frames can sort by it to follow the click order.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SelectionOrder(pub u64);
+
+fn on_add_selected(
+    trigger: Trigger<OnAdd, Selected>,
+    mut commands: Commands,
+    mut next: Local<u64>,
+) {
+    commands
+        .entity(trigger.target())
+        .try_insert(SelectionOrder(*next));
+    *next += 1;
+}
+
+fn on_remove_selected(trigger: Trigger<OnRemove, Selected>, mut commands: Commands) {
+    // The frame may be despawning
+    commands
+        .entity(trigger.target())
+        .try_remove::<SelectionOrder>();
+}
+
 fn setup_sprite(
     mut commands: Commands,
-    images: Res<Assets<Image>>,
+    mut images: ResMut<Assets<Image>>,
     asset_server: Res<AssetServer>,
-    image_frames: Query<(Entity, &ImageFrame, Option<&Transform>), Without<Sprite>>,
-    mut index: Local<u32>,
+    image_frames: Query<
+        (
+            Entity,
+            &ImageFrame,
+            Option<&Transform>,
+            Option<&layout::SavedSize>,
+            Option<&layout::SavedStyle>,
+            Option<&sampling::SampleMode>,
+        ),
+        Without<Sprite>,
+    >,
+    placed_frames: Query<(&Transform, &Sprite), With<ImageFrame>>,
+    import_settings: Res<ImportSettings>,
+    pixel_snap: Res<grid_snap::PixelSnap>,
+    selected_query: Query<Entity, With<Selected>>,
+    mut sampled: ResMut<sampling::SampledImages>,
 ) {
-    for (entity, image_frame, orig_transform) in image_frames {
+    // Frames set up in this run are avoided by the following ones too
+    let mut placed = vec![];
+    if import_settings.avoid_overlap && !image_frames.is_empty() {
+        placed.extend(placed_frames.iter().map(|(transform, sprite)| {
+            frame_shape(transform, sprite.custom_size.unwrap_or(Vec2::ZERO))
+        }));
+    }
+
+    for (entity, image_frame, orig_transform, saved_size, saved_style, sample_mode) in image_frames
+    {
         let Some(image) = images.get(&image_frame.0) else {
             if matches!(
                 asset_server.get_load_state(&image_frame.0),
                 Some(LoadState::Failed(..)),
             ) {
-                commands.entity(entity).despawn();
+                commands.safe_despawn(entity);
             }
 
             continue;
         };
         let size = image.texture_descriptor.size;
-
+        let size = saved_size.map_or(Vec2::new(size.width as f32, size.height as f32), |saved| {
+            saved.0
+        });
+        // Read before `SampledImages` borrows the images mutably
+        let image_size = image.size_f32();
+        let sample_mode = sample_mode.copied().unwrap_or(import_settings.sample_mode);
+        let shown_image = sampled.image(&mut images, &image_frame.0, sample_mode);
+
+        // z is assigned by `z_order::on_add_frame_sprite`
         let mut transform = orig_transform.copied().unwrap_or_default();
-        transform.translation.z = *index as f32 / 65536.0;
+
+        // Frames restored from a layout keep their saved position
+        if import_settings.avoid_overlap && saved_size.is_none() {
+            let shape = frame_shape(&transform, size);
+            // Keep the position if it is free, or if there is no free spot
+            if placed.iter().any(|placed| shape.is_overlapping(placed))
+                && let Some(free) = crate::packing::fill(&placed, &shape, PLACEMENT_GAP, Some(4))
+            {
+                transform.translation = free.translation.extend(transform.translation.z);
+                placed.push(free);
+            } else {
+                placed.push(shape);
+            }
+        }
+        if pixel_snap.enabled && saved_size.is_none() {
+            transform.translation = grid_snap::PixelSnap::snap(
+                transform.translation.xy(),
+                size,
+                image_size,
+                transform.rotation,
+            )
+            .extend(transform.translation.z);
+        }
+
+        let mut sprite = Sprite {
+            image: shown_image,
+            custom_size: Some(size),
+            ..default()
+        };
+        if let Some(saved_style) = saved_style {
+            saved_style.0.apply(&mut sprite);
+        }
 
         commands
             .entity(entity)
-            .insert((
-                Sprite {
-                    image: image_frame.0.clone(),
-                    custom_size: Some(Vec2::new(size.width as f32, size.height as f32)),
-                    ..default()
-                },
-                transform,
-                Pickable::default(),
-            ))
+            .insert((sprite, transform, sample_mode, Pickable::default()))
+            .remove::<(layout::SavedSize, layout::SavedStyle)>()
             .observe(
                 |mut trigger: Trigger<Pointer<Drag>>,
-                 mut transform: Query<&mut Transform>,
-                 viewport_delta: PointerDelta<With<MainCamera>>| {
-                    if trigger.event().button != PointerButton::Primary {
+                 mut commands: Commands,
+                 mut frames: Query<(
+                    &mut Transform,
+                    &Sprite,
+                    Option<&mut grid_snap::Unsnapped>,
+                    Has<DragMoving>,
+                )>,
+                 viewport_delta: PointerDelta<With<MainCamera>>,
+                 grid_snap: Res<grid_snap::GridSnap>,
+                 threshold: Res<DragThreshold>,
+                 modal: Option<Res<ModalActive>>,
+                 keyboard_input: Res<ButtonInput<KeyCode>>,
+                 pan_binding: Res<pan::PanBinding>| {
+                    if trigger.event().button != PointerButton::Primary || modal.is_some() {
+                        return;
+                    }
+                    // Let the drag bubble up to the window to pan instead
+                    if pan_binding.is_pan(trigger.event().button, &keyboard_input) {
                         return;
                     }
 
                     trigger.propagate(false);
 
-                    let Ok(mut sprite_tr) = transform.get_mut(trigger.target()) else {
+                    let Ok((mut sprite_tr, sprite, unsnapped, moving)) =
+                        frames.get_mut(trigger.target())
+                    else {
                         return;
                     };
 
-                    if let Some((world_delta, _)) =
-                        viewport_delta.get_world(&trigger.pointer_location, trigger.delta)
-                    {
+                    // Hold the frame still until the pointer leaves the threshold, then catch up
+                    let delta = if moving {
+                        trigger.delta
+                    } else if trigger.distance.length() >= threshold.0 {
+                        commands.entity(trigger.target()).insert(DragMoving);
+                        trigger.distance
+                    } else {
+                        return;
+                    };
+
+                    let Some((world_delta, _)) =
+                        viewport_delta.get_world(&trigger.pointer_location, delta)
+                    else {
+                        return;
+                    };
+
+                    if !grid_snap.enabled {
                         sprite_tr.translation += world_delta.extend(0.0);
+                        return;
+                    }
+
+                    // Accumulate the drag apart from the snapped translation, so that small
+                    // moves are not rounded away
+                    let translation = match unsnapped {
+                        Some(mut unsnapped) => {
+                            unsnapped.0 += world_delta;
+                            unsnapped.0
+                        }
+                        None => {
+                            let translation = sprite_tr.translation.xy() + world_delta;
+                            commands
+                                .entity(trigger.target())
+                                .insert(grid_snap::Unsnapped(translation));
+                            translation
+                        }
+                    };
+                    let size = sprite.custom_size.unwrap_or(Vec2::ZERO) * sprite_tr.scale.xy();
+                    sprite_tr.translation = grid_snap
+                        .snap(translation, size, sprite_tr.rotation)
+                        .extend(sprite_tr.translation.z);
+                },
+            )
+            .observe(
+                |trigger: Trigger<Pointer<DragStart>>,
+                 mut commands: Commands,
+                 mut frames: Query<(&mut Transform, &Sprite), With<ImageFrame>>,
+                 camera_translator: CameraTranslator,
+                 grid_snap: Res<grid_snap::GridSnap>,
+                 keyboard_input: Res<ButtonInput<KeyCode>>,
+                 key_bindings: Res<KeyBindings>,
+                 pan_binding: Res<pan::PanBinding>,
+                 modal: Option<Res<ModalActive>>,
+                 mut cursors: ResMut<Cursors>|
+                 -> Result {
+                    if trigger.event().button != PointerButton::Primary
+                        || modal.is_some()
+                        || pan_binding.is_pan(trigger.event().button, &keyboard_input)
+                    {
+                        return Ok(());
+                    }
+                    cursors.set(CursorSource::Drag, SystemCursorIcon::Grabbing);
+                    if !key_bindings.pressed(Action::GrabCenter, &keyboard_input) {
+                        return Ok(());
+                    }
+
+                    // Center the frame under the cursor, so that it follows the cursor exactly
+                    let cursor =
+                        camera_translator.viewport_to_main(trigger.pointer_location.position)?;
+                    let Ok((mut transform, sprite)) = frames.get_mut(trigger.target()) else {
+                        return Ok(());
+                    };
+                    let mut frame = commands.entity(trigger.target());
+                    frame.insert(GrabbedCenter(*transform));
+                    let translation = if grid_snap.enabled {
+                        frame.insert(grid_snap::Unsnapped(cursor));
+                        let size = sprite.custom_size.unwrap_or(Vec2::ZERO) * transform.scale.xy();
+                        grid_snap.snap(cursor, size, transform.rotation)
+                    } else {
+                        cursor
+                    };
+                    transform.translation = translation.extend(transform.translation.z);
+                    Ok(())
+                },
+            )
+            .observe(
+                |trigger: Trigger<Pointer<DragEnd>>,
+                 mut commands: Commands,
+                 mut cursors: ResMut<Cursors>,
+                 mut frames: Query<(&mut Transform, &Sprite, Option<&GrabbedCenter>)>,
+                 images: Res<Assets<Image>>,
+                 pixel_snap: Res<grid_snap::PixelSnap>,
+                 mut undo_stack: ResMut<undo::UndoStack>| {
+                    cursors.clear(CursorSource::Drag);
+                    commands.entity(trigger.target()).try_remove::<(
+                        grid_snap::Unsnapped,
+                        DragMoving,
+                        GrabbedCenter,
+                    )>();
+
+                    let Ok((mut transform, sprite, grabbed)) = frames.get_mut(trigger.target())
+                    else {
+                        return;
+                    };
+                    if pixel_snap.enabled
+                        && let Some(image) = images.get(&sprite.image)
+                    {
+                        let size = sprite.custom_size.unwrap_or(Vec2::ZERO) * transform.scale.xy();
+                        let translation = grid_snap::PixelSnap::snap(
+                            transform.translation.xy(),
+                            size,
+                            image.size_f32(),
+                            transform.rotation,
+                        );
+                        transform.translation = translation.extend(transform.translation.z);
+                    }
+                    if let Some(GrabbedCenter(before)) = grabbed {
+                        arrange::record_move(
+                            &mut undo_stack,
+                            trigger.target(),
+                            sprite,
+                            *before,
+                            *transform,
+                        );
                     }
                 },
             )
             .observe(
                 |trigger: Trigger<Pointer<Over>>,
                  mut commands: Commands,
-                 selection_drag: Res<SelectionDrag>| {
-                    if selection_drag.is_dragging() {
+                 selection_drags: Res<SelectionDrags>| {
+                    if selection_drags.is_dragging() {
                         return;
                     }
                     commands.entity(trigger.target()).insert(Hovered);
@@ -269,16 +593,28 @@ fn setup_sprite(
                 |mut trigger: Trigger<Pointer<Click>>,
                  mut commands: Commands,
                  selected_query: Query<Entity, With<Selected>>,
-                 keyboard_input: Res<ButtonInput<KeyCode>>| {
-                    if trigger.button != PointerButton::Primary {
+                 keyboard_input: Res<ButtonInput<KeyCode>>,
+                 key_bindings: Res<KeyBindings>,
+                 tool: Res<Tool>,
+                 modal: Option<Res<ModalActive>>,
+                 panning: Res<pan::Panning>| {
+                    if trigger.button != PointerButton::Primary
+                        || modal.is_some()
+                        || panning.is_panning(trigger.pointer_id)
+                    {
                         return;
                     }
 
                     // Prevent click from propagating to canvas background
                     trigger.propagate(false);
 
+                    if *tool == Tool::Eyedropper {
+                        commands.run_system_cached_with(eyedropper::eyedrop, trigger.target());
+                        return;
+                    }
+
                     let ctrl_pressed =
-                        keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+                        key_bindings.pressed(Action::AddToSelection, &keyboard_input);
                     let target_entity = trigger.target();
 
                     if ctrl_pressed {
@@ -314,20 +650,33 @@ fn setup_sprite(
                 },
             );
 
-        *index += 1;
+        if import_settings.select_on_add {
+            for selected in selected_query.iter() {
+                commands.entity(selected).remove::<Selected>();
+            }
+            commands.entity(entity).insert(Selected);
+            commands.queue(handle::spawn_control_handle(entity));
+        }
     }
 }
 
 fn draw_border(
     camera_translator: CameraTranslator,
-    query: Query<(&GlobalTransform, &Sprite, AnyOf<(&Hovered, &Selected)>)>,
+    query: Query<
+        (
+            &GlobalTransform,
+            &Sprite,
+            AnyOf<(&Hovered, &Selected, &overlap::Overlapping)>,
+        ),
+        Without<Culled>,
+    >,
     mut painter: ShapePainter,
 ) -> Result {
     painter.render_layers = Some(CONTROL_LAYER);
     painter.hollow = true;
     painter.corner_radii = Vec4::splat(5.0);
 
-    for (transform, sprite, (hovered, selected)) in query.iter() {
+    for (transform, sprite, (hovered, selected, overlapping)) in query.iter() {
         let control_transform = camera_translator.to_control(transform)?;
 
         let size = sprite.custom_size.unwrap_or(Vec2::new(0.0, 0.0)) * control_transform.scale.xy();
@@ -335,6 +684,8 @@ fn draw_border(
 
         if selected.is_some() {
             painter.color = Color::srgb(0.0, 1.0, 0.0);
+        } else if overlapping.is_some() {
+            painter.color = Color::srgb(1.0, 0.5, 0.0);
         } else if hovered.is_some() {
             painter.color = Color::srgb(1.0, 1.0, 1.0);
         }
@@ -345,28 +696,31 @@ fn draw_border(
     Ok(())
 }
 
+/// Gap between frames placed by [`organize_canvas`] or [`ImportSettings::avoid_overlap`].
+const PLACEMENT_GAP: f32 = 10.0;
+
+/// Shape of a frame of `size` for [`packing`](crate::packing).
+fn frame_shape(transform: &Transform, size: Vec2) -> ShapePosition {
+    let z_angle = transform.rotation.to_euler(EulerRot::XYZ).2;
+    ShapePosition {
+        translation: transform.translation.xy(),
+        edges: EdgeVectors::with_rect_size_rotation(size, z_angle),
+    }
+}
+
 /// One-time system to organize the canvas. Use `Commands::run_system_cached_with` to run it
-/// with [`ImageFrame`] entities.
+/// with [`ImageFrame`] entities. Frames pinned to the screen are left where they are.
 pub fn organize_canvas(
     In(target): In<Vec<Entity>>,
-    mut sprite: Query<(&mut Sprite, &mut Transform)>,
+    mut sprite: Query<(&mut Sprite, &mut Transform), Without<pin::ScreenPinned>>,
 ) {
-    if target.is_empty() {
-        return;
-    }
-
     let mut shape_ids = Vec::with_capacity(target.len());
 
     for &target in &target {
-        let (sprite, transform) = sprite.get(target).unwrap();
-        let z_angle = transform.rotation.to_euler(EulerRot::XYZ).2;
-        let shape = ShapePosition {
-            translation: transform.translation.xy(),
-            edges: EdgeVectors::with_rect_size_rotation(
-                sprite.custom_size.unwrap_or(Vec2::ZERO),
-                z_angle,
-            ),
+        let Ok((sprite, transform)) = sprite.get(target) else {
+            continue;
         };
+        let shape = frame_shape(transform, sprite.custom_size.unwrap_or(Vec2::ZERO));
 
         shape_ids.push((target, shape));
     }
@@ -382,9 +736,10 @@ pub fn organize_canvas(
         let new_shape = crate::packing::fill(
             placed.iter().map(|t: &(Entity, ShapePosition)| &t.1),
             &shape,
-            10.0,
+            PLACEMENT_GAP,
             Some(4),
-        );
+        )
+        .unwrap_or(shape);
         placed.push((target, new_shape));
     }
 
@@ -394,15 +749,16 @@ pub fn organize_canvas(
     }
 }
 
+/// Image file dropped onto the window. It is imported at the cursor position once that is
+/// available.
 #[derive(Component)]
-struct DropImageFrame(Handle<Image>);
+pub struct DropImageFrame(pub PathBuf);
 
 fn file_drop(
     mut commands: Commands,
     mut reader: EventReader<FileDragAndDrop>,
-    assets: Res<AssetServer>,
     main_window: Single<Entity, With<PrimaryWindow>>,
-    canvas_id: Single<Entity, With<Canvas>>,
+    mut redraw: ResMut<Redraw>,
 ) {
     for ev in reader.read() {
         match ev {
@@ -414,10 +770,9 @@ fn file_drop(
                 // `Window::cursor_position` would return `None` at this point, so we need to spawn the frame
                 // after we get the cursor position.
 
-                let img: Handle<Image> = assets.load(path_buf.clone());
-                commands.entity(*canvas_id).with_child(DropImageFrame(img));
+                commands.spawn(DropImageFrame(path_buf.clone()));
 
-                commands.send_event(RequestRedraw);
+                redraw.request_redraw_once();
             }
             FileDragAndDrop::HoveredFile { .. } => {}
             FileDragAndDrop::HoveredFileCanceled { .. } => {}
@@ -429,7 +784,7 @@ fn place_drop_image_frame(
     mut commands: Commands,
     main_window: Single<&Window, With<PrimaryWindow>>,
     main_camera: Single<(&Camera, &GlobalTransform), With<ControlCamera>>,
-    image_frames: Query<(Entity, &DropImageFrame, &ChildOf)>,
+    dropped: Query<(Entity, &DropImageFrame)>,
 ) {
     let Some(cursor_position) = main_window.cursor_position() else {
         return;
@@ -442,66 +797,124 @@ fn place_drop_image_frame(
         return;
     };
 
-    for (entity, image_frame, child_of) in image_frames {
-        commands.entity(child_of.parent()).with_child((
-            ImageFrame(image_frame.0.clone()),
-            Transform::from_translation(world_position.extend(0.0)),
-        ));
-        commands.entity(entity).despawn();
+    let mut files = Vec::new();
+    for (entity, dropped) in &dropped {
+        files.push(dropped.0.clone());
+        commands.safe_despawn(entity);
     }
+    import::import_files_at(&mut commands, files, world_position);
 }
 
 /// System to handle the start of a selection drag on the canvas background.
 fn handle_selection_drag_start(
     trigger: Trigger<Pointer<DragStart>>,
-    mut drag_state: ResMut<SelectionDrag>,
+    mut drag_states: ResMut<SelectionDrags>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    modal: Option<Res<ModalActive>>,
+    pan_binding: Res<pan::PanBinding>,
 ) {
-    if trigger.event().button != PointerButton::Primary {
+    if trigger.event().button != PointerButton::Primary
+        || modal.is_some()
+        || pan_binding.is_pan(trigger.event().button, &keyboard_input)
+    {
         return;
     }
 
-    drag_state.start = Some(trigger.pointer_location.position);
+    let position = trigger.pointer_location.position;
+    // Hold Z to zoom into the dragged region
+    let zoom = key_bindings.pressed(Action::ZoomBox, &keyboard_input);
+    // Hold L to draw a lasso
+    let lasso = !zoom && key_bindings.pressed(Action::Lasso, &keyboard_input);
+    drag_states.insert(
+        trigger.pointer_id,
+        SelectionDrag {
+            start: Some(position),
+            end: None,
+            zoom,
+            lasso,
+            path: if lasso { vec![position] } else { Vec::new() },
+        },
+    );
 }
 
 /// System to handle the ongoing selection drag.
-fn handle_selection_drag(trigger: Trigger<Pointer<Drag>>, mut drag_state: ResMut<SelectionDrag>) {
-    if drag_state.start.is_none() {
+fn handle_selection_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    mut drag_states: ResMut<SelectionDrags>,
+    mut cursors: ResMut<Cursors>,
+    threshold: Res<DragThreshold>,
+) {
+    let Some(drag_state) = drag_states.get_mut(&trigger.pointer_id) else {
         return;
-    }
+    };
+    let Some(start) = drag_state.start else {
+        return;
+    };
     if trigger.event().button != PointerButton::Primary {
         return;
     }
 
-    drag_state.end = Some(trigger.pointer_location.position);
+    let position = trigger.pointer_location.position;
+    // Not a selection until the pointer leaves the threshold
+    if drag_state.end.is_none() && start.distance(position) < threshold.0 {
+        return;
+    }
+
+    // Drags on frames don't reach the window, so this is a selection
+    if drag_state.end.is_none() {
+        let icon = if drag_state.zoom {
+            SystemCursorIcon::ZoomIn
+        } else {
+            SystemCursorIcon::Crosshair
+        };
+        cursors.set(CursorSource::Marquee, icon);
+    }
+
+    drag_state.end = Some(position);
+
+    if drag_state.lasso
+        && drag_state
+            .path
+            .last()
+            .is_none_or(|last| last.distance(position) >= MIN_LASSO_STEP)
+    {
+        drag_state.path.push(position);
+    }
 }
 
+/// Minimum distance in viewport pixels between points of a lasso path.
+const MIN_LASSO_STEP: f32 = 4.0;
+
+/// Minimum size of the dragged region in viewport pixels to zoom into.
+const MIN_ZOOM_DRAG: f32 = 4.0;
+
 /// System to handle the end of a selection drag.
 fn handle_selection_drag_end(
     trigger: Trigger<Pointer<DragEnd>>,
     mut commands: Commands,
-    mut drag_state: ResMut<SelectionDrag>,
-    image_frames: Query<(Entity, &GlobalTransform, &Sprite), With<ImageFrame>>,
+    mut drag_states: ResMut<SelectionDrags>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    selected_query: Query<Entity, With<Selected>>,
+    key_bindings: Res<KeyBindings>,
     control_camera: Single<(&Camera, &GlobalTransform), With<ControlCamera>>,
+    main_camera: Single<(Entity, &Camera, &Transform), With<MainCamera>>,
     camera_translator: CameraTranslator,
+    marquee_settings: Res<marquee::MarqueeSettings>,
+    mut cursors: ResMut<Cursors>,
 ) -> Result {
-    let (Some(start), Some(end)) = (drag_state.start.take(), drag_state.end.take()) else {
+    let Some(mut drag_state) = drag_states.remove(&trigger.pointer_id) else {
+        return Ok(());
+    };
+    if !drag_states.is_dragging() {
+        cursors.clear(CursorSource::Marquee);
+    }
+    let (Some(start), Some(end)) = (drag_state.start, drag_state.end) else {
         return Ok(());
     };
     if trigger.event().button != PointerButton::Primary {
         return Ok(());
     }
 
-    let ctrl_pressed = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
-
-    if !ctrl_pressed {
-        // Deselect all if Ctrl is not held
-        for entity in selected_query.iter() {
-            commands.entity(entity).remove::<Selected>();
-        }
-    }
-
     let selection_rect = Rect::from_corners(
         control_camera
             .0
@@ -512,43 +925,148 @@ fn handle_selection_drag_end(
     );
     let selection_rect = camera_translator.map_rect_to_main(&selection_rect)?;
 
-    for (entity, transform, sprite) in image_frames.iter() {
-        let sprite_size = sprite.custom_size.unwrap_or(Vec2::ZERO);
-        let sprite_rect = Rect::from_center_size(
-            transform.translation().xy(),
-            sprite_size * transform.scale().xy(),
-        );
+    if drag_state.zoom {
+        // Ignore tiny drags which would zoom in too far
+        if (end - start).abs().min_element() < MIN_ZOOM_DRAG {
+            return Ok(());
+        }
 
-        if !selection_rect.intersect(sprite_rect).is_empty() {
-            commands.entity(entity).insert(Selected);
+        let (camera_id, camera, camera_transform) = *main_camera;
+        if let Some(fit_transform) =
+            camera_tween::fit_rect(camera, camera_transform, selection_rect)
+        {
+            commands
+                .entity(camera_id)
+                .insert(CameraTween::new(*camera_transform, fit_transform));
         }
+        return Ok(());
+    }
+
+    let region = if drag_state.lasso {
+        SelectionRegion::Lasso(
+            std::mem::take(&mut drag_state.path)
+                .into_iter()
+                .map(|position| camera_translator.viewport_to_main(position))
+                .collect::<Result<Vec<_>>>()?,
+        )
+    } else {
+        SelectionRegion::Rect(selection_rect)
+    };
+
+    let add_to_selection = key_bindings.pressed(Action::AddToSelection, &keyboard_input);
+
+    if let SelectionRegion::Rect(rect) = region
+        && marquee_settings.adjustable
+    {
+        commands.insert_resource(marquee::PendingMarquee::new(rect, add_to_selection));
+        return Ok(());
     }
 
+    commands.run_system_cached_with(select_frames, (region, add_to_selection));
+
     Ok(())
 }
 
-/// System to draw the selection rectangle.
+/// Region of the world to select frames in, in [`MainCamera`] world space.
+enum SelectionRegion {
+    /// Frames intersecting the rectangle.
+    Rect(Rect),
+    /// Frames whose centers are inside the closed path.
+    Lasso(Vec<Vec2>),
+}
+
+/// One-shot system to select frames in a region, replacing the selection unless adding to it.
+/// Use `Commands::run_system_cached_with` to run it.
+fn select_frames(
+    In((region, add_to_selection)): In<(SelectionRegion, bool)>,
+    mut commands: Commands,
+    image_frames: Query<
+        (Entity, &GlobalTransform, &Sprite),
+        (With<ImageFrame>, Without<isolate::IsolationHidden>),
+    >,
+    selected_query: Query<Entity, With<Selected>>,
+) {
+    if !add_to_selection {
+        for entity in selected_query.iter() {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+
+    for (entity, transform, sprite) in image_frames.iter() {
+        let hit = match &region {
+            SelectionRegion::Lasso(polygon) => {
+                picking::point_in_polygon(transform.translation().xy(), polygon)
+            }
+            SelectionRegion::Rect(rect) => {
+                let sprite_size = sprite.custom_size.unwrap_or(Vec2::ZERO);
+                let sprite_rect = Rect::from_center_size(
+                    transform.translation().xy(),
+                    sprite_size * transform.scale().xy(),
+                );
+                !rect.intersect(sprite_rect).is_empty()
+            }
+        };
+
+        if hit {
+            commands.entity(entity).insert(Selected);
+        }
+    }
+}
+
+/// System to draw the selection rectangles.
 fn draw_selection_rectangle(
-    drag_state: Res<SelectionDrag>,
+    drag_states: Res<SelectionDrags>,
     mut painter: ShapePainter,
     control_camera: Single<(&Camera, &GlobalTransform), With<ControlCamera>>,
+) -> Result {
+    painter.render_layers = Some(CONTROL_LAYER);
+    for drag_state in drag_states.values() {
+        draw_selection_drag(drag_state, &mut painter, *control_camera)?;
+    }
+    Ok(())
+}
+
+/// Draws the rectangle, or lasso path, of one selection drag.
+fn draw_selection_drag(
+    drag_state: &SelectionDrag,
+    painter: &mut ShapePainter,
+    (camera, camera_transform): (&Camera, &GlobalTransform),
 ) -> Result {
     let (Some(start), Some(end)) = (drag_state.start, drag_state.end) else {
         return Ok(());
     };
+    painter.transform = Transform::IDENTITY;
+
+    if drag_state.lasso {
+        let points = drag_state
+            .path
+            .iter()
+            .map(|&position| camera.viewport_to_world_2d(camera_transform, position))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        painter.color = Color::srgba(0.5, 0.5, 1.0, 0.8);
+        for segment in points.windows(2) {
+            painter.line(segment[0].extend(0.0), segment[1].extend(0.0));
+        }
+        // The path is closed on release
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            painter.color = Color::srgba(0.5, 0.5, 1.0, 0.3);
+            painter.line(last.extend(0.0), first.extend(0.0));
+        }
+        return Ok(());
+    }
 
-    let start = control_camera
-        .0
-        .viewport_to_world_2d(control_camera.1, start)?;
-    let end = control_camera
-        .0
-        .viewport_to_world_2d(control_camera.1, end)?;
+    let start = camera.viewport_to_world_2d(camera_transform, start)?;
+    let end = camera.viewport_to_world_2d(camera_transform, end)?;
 
     let selection_rect = Rect::from_corners(start, end);
 
-    painter.render_layers = Some(CONTROL_LAYER);
     painter.hollow = true;
-    painter.color = Color::srgba(0.5, 0.5, 1.0, 0.5);
+    painter.color = if drag_state.zoom {
+        Color::srgba(1.0, 1.0, 0.5, 0.5)
+    } else {
+        Color::srgba(0.5, 0.5, 1.0, 0.5)
+    };
     painter.transform = Transform::from_translation(selection_rect.center().extend(0.0));
     painter.rect(selection_rect.size());
 
@@ -560,13 +1078,31 @@ fn handle_canvas_click(
     trigger: Trigger<Pointer<Click>>,
     mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     selected_query: Query<Entity, With<Selected>>,
+    drag_states: Res<SelectionDrags>,
+    modal: Option<Res<ModalActive>>,
+    panning: Res<pan::Panning>,
 ) {
-    if trigger.button != PointerButton::Primary {
+    if trigger.button != PointerButton::Primary
+        || modal.is_some()
+        || panning.is_panning(trigger.pointer_id)
+    {
         return;
     }
 
-    let ctrl_pressed = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    // Clicking outside an adjustable marquee discards it
+    commands.remove_resource::<marquee::PendingMarquee>();
+
+    // Keep the selection when releasing a zoom drag
+    if drag_states
+        .get(&trigger.pointer_id)
+        .is_some_and(|drag_state| drag_state.zoom && drag_state.is_dragging())
+    {
+        return;
+    }
+
+    let ctrl_pressed = key_bindings.pressed(Action::AddToSelection, &keyboard_input);
 
     if ctrl_pressed {
         return;
@@ -577,3 +1113,193 @@ fn handle_canvas_click(
         commands.entity(entity).remove::<Selected>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        picking::{
+            backend::HitData,
+            pointer::{Location, PointerId},
+        },
+        render::camera::NormalizedRenderTarget,
+        window::WindowRef,
+    };
+
+    use super::*;
+
+    fn trigger_pointer<E: std::fmt::Debug + Clone + Reflect>(
+        world: &mut World,
+        target: Entity,
+        position: Vec2,
+        event: E,
+    ) {
+        trigger_pointer_with_id(world, PointerId::Mouse, target, position, event);
+    }
+
+    fn trigger_pointer_with_id<E: std::fmt::Debug + Clone + Reflect>(
+        world: &mut World,
+        pointer: PointerId,
+        target: Entity,
+        position: Vec2,
+        event: E,
+    ) {
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Entity(target).normalize(None).unwrap(),
+            ),
+            position,
+        };
+        world.trigger_targets(Pointer::new(pointer, location, target, event), target);
+        world.flush();
+    }
+
+    fn click(world: &mut World, target: Entity) {
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(target, 0.0, None, None),
+            duration: Duration::ZERO,
+        };
+        trigger_pointer(world, target, Vec2::ZERO, click);
+    }
+
+    #[test]
+    fn test_canvas_click_ignored_while_modal() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<SelectionDrags>();
+        world.init_resource::<pan::Panning>();
+        world.add_observer(handle_canvas_click);
+        world.flush();
+
+        let window = world.spawn_empty().id();
+        let frame = world.spawn(Selected).id();
+
+        world.insert_resource(ModalActive::default());
+        click(&mut world, window);
+        assert!(world.get::<Selected>(frame).is_some());
+
+        world.remove_resource::<ModalActive>();
+        click(&mut world, window);
+        assert!(world.get::<Selected>(frame).is_none());
+    }
+
+    #[test]
+    fn test_small_drag_does_not_start_marquee() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<SelectionDrags>();
+        world.init_resource::<DragThreshold>();
+        world.init_resource::<pan::PanBinding>();
+        world.init_resource::<Cursors>();
+        world.add_observer(handle_selection_drag_start);
+        world.add_observer(handle_selection_drag);
+        world.flush();
+
+        let window = world.spawn_empty().id();
+        let start = Vec2::new(100.0, 100.0);
+        let drag_start = DragStart {
+            button: PointerButton::Primary,
+            hit: HitData::new(window, 0.0, None, None),
+        };
+        trigger_pointer(&mut world, window, start, drag_start);
+        let drag_to = |world: &mut World, distance: Vec2| {
+            let drag = Drag {
+                button: PointerButton::Primary,
+                distance,
+                delta: distance,
+            };
+            trigger_pointer(world, window, start + distance, drag);
+        };
+
+        let end = |world: &World| world.resource::<SelectionDrags>()[&PointerId::Mouse].end;
+
+        drag_to(&mut world, Vec2::new(1.0, 0.0));
+        assert_eq!(end(&world), None);
+        assert_eq!(world.resource::<Cursors>().icon(), None);
+
+        drag_to(&mut world, Vec2::new(10.0, 0.0));
+        assert_eq!(end(&world), Some(Vec2::new(110.0, 100.0)));
+    }
+
+    #[test]
+    fn test_selection_drag_per_touch() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<SelectionDrags>();
+        world.init_resource::<DragThreshold>();
+        world.init_resource::<pan::PanBinding>();
+        world.init_resource::<Cursors>();
+        world.add_observer(handle_selection_drag_start);
+        world.add_observer(handle_selection_drag);
+        world.flush();
+
+        let window = world.spawn_empty().id();
+        let touches = [
+            (PointerId::Touch(0), Vec2::new(100.0, 100.0)),
+            (PointerId::Touch(1), Vec2::new(400.0, 300.0)),
+        ];
+        for (pointer, start) in touches {
+            let drag_start = DragStart {
+                button: PointerButton::Primary,
+                hit: HitData::new(window, 0.0, None, None),
+            };
+            trigger_pointer_with_id(&mut world, pointer, window, start, drag_start);
+        }
+        for (i, (pointer, start)) in touches.into_iter().enumerate() {
+            let distance = Vec2::splat(10.0 * (i + 1) as f32);
+            let drag = Drag {
+                button: PointerButton::Primary,
+                distance,
+                delta: distance,
+            };
+            trigger_pointer_with_id(&mut world, pointer, window, start + distance, drag);
+        }
+
+        // Each touch draws its own rectangle
+        let drags = world.resource::<SelectionDrags>();
+        for (i, (pointer, start)) in touches.into_iter().enumerate() {
+            assert_eq!(drags[&pointer].start, Some(start));
+            assert_eq!(
+                drags[&pointer].end,
+                Some(start + Vec2::splat(10.0 * (i + 1) as f32))
+            );
+        }
+    }
+
+    #[test]
+    fn test_organize_canvas_separates_frames() {
+        let mut world = World::new();
+        let size = Vec2::new(10.0, 20.0);
+        let frame = |x: f32| {
+            (
+                Sprite {
+                    custom_size: Some(size),
+                    ..default()
+                },
+                Transform::from_xyz(x, 0.0, 0.0),
+            )
+        };
+        let a = world.spawn(frame(0.0)).id();
+        let b = world.spawn(frame(2.0)).id();
+
+        world
+            .run_system_cached_with(organize_canvas, vec![a, b])
+            .unwrap();
+
+        // The last frame stays and the others are placed around it
+        assert_eq!(
+            world.get::<Transform>(b).unwrap().translation,
+            Vec3::new(2.0, 0.0, 0.0)
+        );
+        let bounds = |frame: Entity| {
+            let transform = GlobalTransform::from(*world.get::<Transform>(frame).unwrap());
+            culling::frame_bounds(&transform, size)
+        };
+        assert!(bounds(a).intersect(bounds(b)).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Names of frames, set in batches from a pattern such as `tile_{index}`.
+
+use bevy::prelude::*;
+
+use super::{
+    ImageFrame,
+    undo::{EditAction, UndoStack},
+};
+
+/// Placeholder in a naming pattern replaced by the number of each frame.
+pub const INDEX_PLACEHOLDER: &str = "{index}";
+
+/// Name of a frame, to be used as its key instead of the image path.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct FrameName(pub String);
+
+/// Returns the name of the frame numbered `index` for `pattern`. A pattern without
+/// [`INDEX_PLACEHOLDER`] gets the number appended.
+pub fn format_name(pattern: &str, index: usize) -> String {
+    if pattern.contains(INDEX_PLACEHOLDER) {
+        pattern.replace(INDEX_PLACEHOLDER, &index.to_string())
+    } else {
+        format!("{pattern}{index}")
+    }
+}
+
+/// Input of [`rename_frames`].
+pub struct RenameRequest {
+    /// Frames in the order of numbering.
+    pub targets: Vec<Entity>,
+    pub pattern: String,
+    /// Number of the first frame.
+    pub start: usize,
+}
+
+/// One-shot system to name frames from a pattern, numbering them in the order given. Use
+/// `Commands::run_system_cached_with` to run it.
+pub fn rename_frames(
+    In(request): In<RenameRequest>,
+    mut commands: Commands,
+    frames: Query<Option<&FrameName>, With<ImageFrame>>,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    let mut renamed = vec![];
+    for (index, &entity) in (request.start..).zip(&request.targets) {
+        let Ok(before) = frames.get(entity) else {
+            continue;
+        };
+        let after = FrameName(format_name(&request.pattern, index));
+        commands
+            .entity(entity)
+            .insert((after.clone(), Name::new(after.0.clone())));
+        renamed.push((entity, before.cloned(), after));
+    }
+
+    if renamed.is_empty() {
+        return;
+    }
+    info!("Renamed {} frame(s)", renamed.len());
+    undo_stack.push(RenameEdit { renamed });
+}
+
+/// Sets [`FrameName`] and [`Name`], which the inspector shows, or removes them.
+fn set_name(world: &mut World, entity: Entity, name: Option<&FrameName>) {
+    let Ok(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    match name {
+        Some(name) => {
+            entity.insert((name.clone(), Name::new(name.0.clone())));
+        }
+        None => {
+            entity.remove::<(FrameName, Name)>();
+        }
+    }
+}
+
+/// Frames named by [`rename_frames`], with their previous names.
+struct RenameEdit {
+    renamed: Vec<(Entity, Option<FrameName>, FrameName)>,
+}
+
+impl EditAction for RenameEdit {
+    fn describe(&self) -> String {
+        format!("rename {} frame(s)", self.renamed.len())
+    }
+
+    fn thumbnail_frame(&self) -> Option<Entity> {
+        self.renamed.first().map(|(entity, ..)| *entity)
+    }
+
+    fn undo(&self, world: &mut World) {
+        for (entity, before, _) in &self.renamed {
+            set_name(world, *entity, before.as_ref());
+        }
+    }
+
+    fn redo(&self, world: &mut World) {
+        for (entity, _, after) in &self.renamed {
+            set_name(world, *entity, Some(after));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::canvas::undo;
+
+    use super::*;
+
+    #[test]
+    fn test_format_name() {
+        assert_eq!(format_name("tile_{index}", 3), "tile_3");
+        assert_eq!(format_name("{index}-{index}", 1), "1-1");
+        assert_eq!(format_name("tile_", 0), "tile_0");
+    }
+
+    #[test]
+    fn test_rename_frames() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+        let a = world.spawn(ImageFrame(default())).id();
+        let b = world
+            .spawn((ImageFrame(default()), FrameName("old".to_string())))
+            .id();
+
+        world
+            .run_system_cached_with(
+                rename_frames,
+                RenameRequest {
+                    targets: vec![b, a],
+                    pattern: "tile_{index}".to_string(),
+                    start: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(world.get::<FrameName>(b).unwrap().0, "tile_1");
+        assert_eq!(world.get::<FrameName>(a).unwrap().0, "tile_2");
+        assert_eq!(world.get::<Name>(a).unwrap().as_str(), "tile_2");
+
+        undo::undo(&mut world);
+        assert!(world.get::<FrameName>(a).is_none());
+        assert_eq!(world.get::<FrameName>(b).unwrap().0, "old");
+    }
+}
@@ -0,0 +1,220 @@
+//! Outline rendering mode for huge boards.
+//!
+//! With many frames or when zoomed far out, frames are drawn as rectangles filled with the
+//! average color of their images, in a single batch instead of one draw call per image.
+//! Sprites are hidden meanwhile but stay pickable by their real bounds.
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::RectPainter};
+
+use crate::{
+    key_bindings::{Action, action_just_pressed},
+    sprite_picking::PickHidden,
+};
+
+use super::{ImageFrame, MainCamera, culling, isolate::IsolationHidden};
+
+/// Number of samples per axis to compute the average color of an image.
+const COLOR_SAMPLES: u32 = 8;
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OutlineSettings>()
+            .register_type::<OutlineSettings>()
+            .init_resource::<OutlineMode>()
+            .add_systems(
+                Update,
+                (
+                    toggle_outline_mode.run_if(action_just_pressed(Action::ToggleOutlineMode)),
+                    update_outline_mode,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_outlines
+                    .after(TransformSystem::TransformPropagate)
+                    .after(culling::cull_frames)
+                    .run_if(|mode: Res<OutlineMode>| mode.0),
+            );
+    }
+}
+
+/// Settings for switching to outline rendering.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct OutlineSettings {
+    /// Whether outline rendering may be used at all.
+    pub enabled: bool,
+    /// Render outlines when there are more frames than this.
+    pub frame_threshold: usize,
+    /// Render outlines when the [`MainCamera`] scale (world units per pixel) is above this.
+    pub zoom_threshold: f32,
+}
+
+impl Default for OutlineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            frame_threshold: 300,
+            zoom_threshold: 8.0,
+        }
+    }
+}
+
+/// Whether frames are currently rendered as outlines.
+#[derive(Resource, Default)]
+struct OutlineMode(bool);
+
+/// Average color of a frame's image, drawn in outline mode.
+#[derive(Component)]
+struct OutlineColor(Color);
+
+fn toggle_outline_mode(mut settings: ResMut<OutlineSettings>) {
+    settings.enabled = !settings.enabled;
+    info!(
+        "Outline mode {}",
+        if settings.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+}
+
+fn update_outline_mode(
+    mut commands: Commands,
+    settings: Res<OutlineSettings>,
+    mut mode: ResMut<OutlineMode>,
+    camera: Single<&Transform, With<MainCamera>>,
+    mut frames: Query<
+        (
+            Entity,
+            &mut Visibility,
+            Ref<Sprite>,
+            Option<&mut IsolationHidden>,
+        ),
+        With<ImageFrame>,
+    >,
+) {
+    let active = settings.enabled
+        && (frames.iter().len() > settings.frame_threshold
+            || camera.scale.x > settings.zoom_threshold);
+    let changed = mode.0 != active;
+    mode.0 = active;
+
+    for (entity, mut visibility, sprite, isolation_hidden) in &mut frames {
+        // Only new frames need updating while the mode stays the same
+        if !changed && !sprite.is_added() {
+            continue;
+        }
+
+        let new_visibility = if active {
+            commands.entity(entity).insert(PickHidden);
+            Visibility::Hidden
+        } else {
+            commands.entity(entity).remove::<PickHidden>();
+            Visibility::Inherited
+        };
+        // Frames hidden by isolate mode get it on exit
+        match isolation_hidden {
+            Some(mut isolation_hidden) => isolation_hidden.visibility = new_visibility,
+            None => {
+                visibility.set_if_neq(new_visibility);
+            }
+        }
+    }
+}
+
+fn draw_outlines(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    frames: Query<
+        (
+            Entity,
+            &ImageFrame,
+            &GlobalTransform,
+            &Sprite,
+            Option<&OutlineColor>,
+        ),
+        (Without<culling::Culled>, Without<IsolationHidden>),
+    >,
+    mut painter: ShapePainter,
+) {
+    for (entity, image_frame, transform, sprite, color) in &frames {
+        let color = match color {
+            Some(color) => color.0,
+            None => {
+                let color = images
+                    .get(&image_frame.0)
+                    .and_then(average_color)
+                    .unwrap_or(Color::srgb(0.5, 0.5, 0.5));
+                commands.entity(entity).insert(OutlineColor(color));
+                color
+            }
+        };
+
+        painter.transform = transform.compute_transform();
+        painter.color = color;
+        painter.rect(sprite.custom_size.unwrap_or(Vec2::ZERO));
+    }
+}
+
+/// Average color of `image`, sampled on a grid.
+fn average_color(image: &Image) -> Option<Color> {
+    let (width, height) = (image.width(), image.height());
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut sum = Vec4::ZERO;
+    for i in 0..COLOR_SAMPLES {
+        for j in 0..COLOR_SAMPLES {
+            let x = (2 * i + 1) * width / (2 * COLOR_SAMPLES);
+            let y = (2 * j + 1) * height / (2 * COLOR_SAMPLES);
+            sum += image.get_color_at(x, y).ok()?.to_linear().to_vec4();
+        }
+    }
+
+    Some(LinearRgba::from_vec4(sum / (COLOR_SAMPLES * COLOR_SAMPLES) as f32).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::RenderAssetUsages,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_average_color() {
+        // Left half red, right half blue
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 4,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        for y in 0..2 {
+            for x in 2..4 {
+                image
+                    .set_color_at(x, y, Color::srgb(0.0, 0.0, 1.0))
+                    .unwrap();
+            }
+        }
+
+        let color = average_color(&image).unwrap().to_linear();
+        assert!((color.red - 0.5).abs() < 1e-3);
+        assert!(color.green.abs() < 1e-3);
+        assert!((color.blue - 0.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,282 @@
+//! Overlap check mode: flags frames overlapping others, to spot unintended collisions.
+//!
+//! Frames are compared by their oriented bounds with the SAT test of [`packing`](crate::packing).
+//! Overlaps are only recomputed while the mode is enabled and frames move or resize.
+
+use bevy::prelude::*;
+
+use crate::{
+    key_bindings::{Action, action_just_pressed},
+    packing::{EdgeVectors, ShapePosition},
+};
+
+use super::{
+    ImageFrame, MainCamera,
+    camera_tween::{self, CameraTween},
+    culling::frame_bounds,
+};
+
+/// Margin around an overlap cluster when jumping to it, relative to the cluster size.
+const JUMP_MARGIN: f32 = 0.25;
+
+/// Depth in world units up to which frames count as touching rather than overlapping, so that
+/// frames placed edge to edge are not flagged because of rounding errors.
+const TOUCH_TOLERANCE: f32 = 1e-3;
+
+pub struct OverlapPlugin;
+
+impl Plugin for OverlapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OverlapCheck>()
+            .add_systems(
+                Update,
+                (
+                    toggle_overlap_check.run_if(action_just_pressed(Action::ToggleOverlapCheck)),
+                    jump_to_next_overlap.run_if(action_just_pressed(Action::NextOverlap)),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                detect_overlaps
+                    .after(TransformSystem::TransformPropagate)
+                    .before(super::draw_border)
+                    .run_if(|check: Res<OverlapCheck>| check.enabled),
+            );
+    }
+}
+
+/// Marks a frame overlapping another while the overlap check is enabled.
+#[derive(Component)]
+pub struct Overlapping;
+
+/// State of the overlap check mode.
+#[derive(Resource, Default)]
+struct OverlapCheck {
+    enabled: bool,
+    /// Groups of frames connected by overlaps.
+    clusters: Vec<Vec<Entity>>,
+    /// Index of the cluster last jumped to.
+    current: Option<usize>,
+}
+
+fn toggle_overlap_check(
+    mut commands: Commands,
+    mut check: ResMut<OverlapCheck>,
+    overlapping: Query<Entity, With<Overlapping>>,
+) {
+    check.enabled = !check.enabled;
+    if check.enabled {
+        info!("Overlap check enabled");
+    } else {
+        for entity in &overlapping {
+            commands.entity(entity).remove::<Overlapping>();
+        }
+        *check = OverlapCheck::default();
+        info!("Overlap check disabled");
+    }
+}
+
+fn detect_overlaps(
+    mut commands: Commands,
+    mut check: ResMut<OverlapCheck>,
+    frames: Query<(Entity, &GlobalTransform, &Sprite, Has<Overlapping>), With<ImageFrame>>,
+    changed: Query<
+        (),
+        (
+            With<ImageFrame>,
+            Or<(Changed<GlobalTransform>, Changed<Sprite>)>,
+        ),
+    >,
+    mut removed: RemovedComponents<ImageFrame>,
+) {
+    let removed = removed.read().count() > 0;
+    if !check.is_changed() && changed.is_empty() && !removed {
+        return;
+    }
+
+    let frames = frames
+        .iter()
+        .map(|(entity, transform, sprite, overlapping)| {
+            let size = sprite.custom_size.unwrap_or(Vec2::ZERO);
+            let half = size / 2.0;
+            let vertices = [
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(half.x, half.y),
+                Vec2::new(-half.x, half.y),
+            ]
+            .map(|corner| transform.transform_point(corner.extend(0.0)).xy());
+            let shape = ShapePosition {
+                translation: transform.translation().xy(),
+                edges: EdgeVectors::from_vertices(&vertices),
+            };
+            (entity, frame_bounds(transform, size), shape, overlapping)
+        })
+        .collect::<Vec<_>>();
+
+    // Union-find over overlapping pairs
+    let mut parent = (0..frames.len()).collect::<Vec<_>>();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut is_overlapping = vec![false; frames.len()];
+    for i in 0..frames.len() {
+        for j in i + 1..frames.len() {
+            // Cheap bounding box test first
+            if frames[i].1.intersect(frames[j].1).is_empty()
+                || frames[i].2.separation(&frames[j].2) >= -TOUCH_TOLERANCE
+            {
+                continue;
+            }
+            is_overlapping[i] = true;
+            is_overlapping[j] = true;
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            parent[a] = b;
+        }
+    }
+
+    let mut clusters = Vec::<(usize, Vec<Entity>)>::new();
+    for (i, &(entity, _, _, was_overlapping)) in frames.iter().enumerate() {
+        if is_overlapping[i] != was_overlapping {
+            if is_overlapping[i] {
+                commands.entity(entity).insert(Overlapping);
+            } else {
+                commands.entity(entity).remove::<Overlapping>();
+            }
+        }
+        if !is_overlapping[i] {
+            continue;
+        }
+        let root = root(&mut parent, i);
+        match clusters.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, cluster)) => cluster.push(entity),
+            None => clusters.push((root, vec![entity])),
+        }
+    }
+
+    check.clusters = clusters.into_iter().map(|(_, cluster)| cluster).collect();
+    if check
+        .current
+        .is_some_and(|current| current >= check.clusters.len())
+    {
+        check.current = None;
+    }
+}
+
+/// Moves the camera to fit the next group of overlapping frames.
+fn jump_to_next_overlap(
+    mut commands: Commands,
+    mut check: ResMut<OverlapCheck>,
+    frames: Query<(&GlobalTransform, &Sprite)>,
+    main_camera: Single<(Entity, &Camera, &Transform), With<MainCamera>>,
+) {
+    if !check.enabled {
+        info!("Enable the overlap check to jump between overlaps");
+        return;
+    }
+    if check.clusters.is_empty() {
+        info!("No overlapping frames");
+        return;
+    }
+
+    let next = check
+        .current
+        .map_or(0, |current| (current + 1) % check.clusters.len());
+    check.current = Some(next);
+
+    let bounds = frames
+        .iter_many(&check.clusters[next])
+        .map(|(transform, sprite)| {
+            frame_bounds(transform, sprite.custom_size.unwrap_or(Vec2::ZERO))
+        })
+        .fold(Rect::EMPTY, |bounds, frame| bounds.union(frame));
+    if bounds.is_empty() {
+        return;
+    }
+    let bounds = bounds.inflate(bounds.size().max_element() * JUMP_MARGIN / 2.0);
+
+    let (camera_id, camera, camera_transform) = *main_camera;
+    if let Some(fit_transform) = camera_tween::fit_rect(camera, camera_transform, bounds) {
+        commands
+            .entity(camera_id)
+            .insert(CameraTween::new(*camera_transform, fit_transform));
+    }
+    info!(
+        "Overlap {} of {} ({} frames)",
+        next + 1,
+        check.clusters.len(),
+        check.clusters[next].len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn frame(transform: Transform, size: Vec2) -> impl Bundle {
+        (
+            ImageFrame(default()),
+            GlobalTransform::from(transform),
+            Sprite {
+                custom_size: Some(size),
+                ..default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_detect_overlaps() {
+        let mut world = World::new();
+        world.insert_resource(OverlapCheck {
+            enabled: true,
+            ..default()
+        });
+
+        let a = world
+            .spawn(frame(Transform::from_xyz(0.0, 0.0, 0.0), Vec2::splat(10.0)))
+            .id();
+        let b = world
+            .spawn(frame(Transform::from_xyz(8.0, 0.0, 0.0), Vec2::splat(10.0)))
+            .id();
+        // Its bounding box overlaps `a`, but the rotated frame doesn't
+        let c = world
+            .spawn(frame(
+                Transform::from_xyz(-12.0, 12.0, 0.0)
+                    .with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                Vec2::new(20.0, 2.0),
+            ))
+            .id();
+
+        world.run_system_cached(detect_overlaps).unwrap();
+        assert!(world.get::<Overlapping>(a).is_some());
+        assert!(world.get::<Overlapping>(b).is_some());
+        assert!(world.get::<Overlapping>(c).is_none());
+        assert_eq!(world.resource::<OverlapCheck>().clusters.len(), 1);
+
+        // Rotated frames touching at an edge, whose bounding boxes overlap
+        let rotated = |x: f32, y: f32| {
+            frame(
+                Transform::from_xyz(x, y, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                Vec2::splat(10.0),
+            )
+        };
+        let offset = 10.0 * FRAC_PI_4.cos();
+        let d = world.spawn(rotated(100.0, 100.0)).id();
+        let e = world.spawn(rotated(100.0 + offset, 100.0 + offset)).id();
+        world.run_system_cached(detect_overlaps).unwrap();
+        assert!(world.get::<Overlapping>(d).is_none());
+        assert!(world.get::<Overlapping>(e).is_none());
+
+        *world.get_mut::<GlobalTransform>(b).unwrap() = GlobalTransform::from_xyz(20.0, 0.0, 0.0);
+        world.run_system_cached(detect_overlaps).unwrap();
+        assert!(world.get::<Overlapping>(a).is_none());
+        assert!(world.resource::<OverlapCheck>().clusters.is_empty());
+    }
+}
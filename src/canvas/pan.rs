@@ -0,0 +1,139 @@
+//! Panning the canvas by dragging, with a configurable [`PanBinding`] for users without a
+//! middle mouse button.
+
+use bevy::{
+    picking::pointer::PointerId, platform::collections::HashSet, prelude::*,
+    window::SystemCursorIcon,
+};
+
+use crate::{
+    cursor::{CursorSource, Cursors},
+    viewport_delta::PointerDelta,
+};
+
+use super::{MainCamera, camera_tween::CameraTween};
+
+pub struct PanPlugin;
+
+impl Plugin for PanPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PanBinding>()
+            .register_type::<PanBinding>()
+            .init_resource::<Panning>()
+            .add_observer(end_pan);
+    }
+}
+
+/// Pointer button, and optionally a key to hold, that pans the canvas when dragged.
+#[derive(Resource, Reflect, Debug, Clone, Copy)]
+#[reflect(Resource, Default)]
+pub struct PanBinding {
+    pub button: PointerButton,
+    pub modifier: Option<KeyCode>,
+}
+
+impl Default for PanBinding {
+    fn default() -> Self {
+        Self {
+            button: PointerButton::Middle,
+            modifier: None,
+        }
+    }
+}
+
+impl PanBinding {
+    /// Whether a drag with `button` pans instead of selecting or moving frames.
+    pub fn is_pan(&self, button: PointerButton, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        button == self.button && self.modifier.is_none_or(|key| keyboard_input.pressed(key))
+    }
+
+    fn mouse_button(&self) -> MouseButton {
+        match self.button {
+            PointerButton::Primary => MouseButton::Left,
+            PointerButton::Secondary => MouseButton::Right,
+            PointerButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+/// Pointers whose current drag pans the canvas. The click ending a pan is fired before its
+/// `DragEnd`, so click handlers check this to ignore it.
+#[derive(Resource, Default)]
+pub struct Panning(HashSet<PointerId>);
+
+impl Panning {
+    pub fn is_panning(&self, pointer: PointerId) -> bool {
+        self.0.contains(&pointer)
+    }
+}
+
+pub(super) fn drag_to_pan(
+    trigger: Trigger<Pointer<Drag>>,
+    mut commands: Commands,
+    mut camera: Query<&mut Transform, With<Camera>>,
+    pointer_delta: PointerDelta<With<MainCamera>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pan_binding: Res<PanBinding>,
+    mut panning: ResMut<Panning>,
+    mut cursors: ResMut<Cursors>,
+) {
+    let button = pan_binding.mouse_button();
+    if !mouse_buttons.pressed(button)
+        || mouse_buttons
+            .get_pressed()
+            .any(|pressed| *pressed != button)
+    {
+        return;
+    }
+
+    let event = trigger.event();
+    if !pan_binding.is_pan(event.button, &keyboard_input) {
+        return;
+    }
+    if panning.0.insert(trigger.pointer_id) {
+        cursors.set(CursorSource::Pan, SystemCursorIcon::Move);
+    }
+
+    if let Some((world_delta, camera_id)) =
+        pointer_delta.get_world(&trigger.pointer_location, trigger.delta)
+        && let Ok(mut transform) = camera.get_mut(camera_id)
+    {
+        commands.entity(camera_id).remove::<CameraTween>();
+        transform.translation -= world_delta.extend(0.0);
+    }
+}
+
+fn end_pan(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut panning: ResMut<Panning>,
+    mut cursors: ResMut<Cursors>,
+) {
+    panning.0.remove(&trigger.pointer_id);
+    if panning.0.is_empty() {
+        cursors.clear(CursorSource::Pan);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pan() {
+        let mut keyboard_input = ButtonInput::<KeyCode>::default();
+        let binding = PanBinding {
+            button: PointerButton::Primary,
+            modifier: Some(KeyCode::Space),
+        };
+
+        assert!(!binding.is_pan(PointerButton::Primary, &keyboard_input));
+        keyboard_input.press(KeyCode::Space);
+        assert!(binding.is_pan(PointerButton::Primary, &keyboard_input));
+        assert!(!binding.is_pan(PointerButton::Middle, &keyboard_input));
+
+        // The default keeps selection and frame drags on the primary button
+        assert!(!PanBinding::default().is_pan(PointerButton::Primary, &keyboard_input));
+        assert!(PanBinding::default().is_pan(PointerButton::Middle, &keyboard_input));
+    }
+}
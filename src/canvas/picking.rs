@@ -1,5 +1,17 @@
 use bevy::{picking::backend::prelude::*, prelude::*, render::view::RenderLayers};
 
+#[cfg(feature = "dev")]
+use bevy::color::palettes::css::MAGENTA;
+#[cfg(feature = "dev")]
+use bevy_vector_shapes::{
+    prelude::ShapePainter,
+    shapes::{DiscPainter, RectPainter},
+};
+
+#[cfg(feature = "dev")]
+use crate::key_bindings::{Action, action_just_pressed};
+use crate::modal::ModalActive;
+
 /// Picking plugin for invisible hover area.
 #[derive(Default, Clone, Resource)]
 pub struct AreaPickingPlugin {
@@ -8,8 +20,63 @@ pub struct AreaPickingPlugin {
 
 impl Plugin for AreaPickingPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(self.clone())
-            .add_systems(PreUpdate, pick_shape.in_set(PickSet::Backend));
+        app.insert_resource(self.clone()).add_systems(
+            PreUpdate,
+            pick_shape
+                .in_set(PickSet::Backend)
+                .run_if(not(resource_exists::<ModalActive>)),
+        );
+
+        #[cfg(feature = "dev")]
+        app.init_resource::<ShowPickingAreas>()
+            .add_systems(
+                Update,
+                toggle_picking_areas.run_if(action_just_pressed(Action::TogglePickingAreas)),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_picking_areas
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(|show: Res<ShowPickingAreas>| show.0),
+            );
+    }
+}
+
+/// Whether to draw the hit areas of [`PickingAreaCircle`]s and [`PickingAreaRect`]s, to compare
+/// them with the drawn handles.
+#[cfg(feature = "dev")]
+#[derive(Resource, Default)]
+struct ShowPickingAreas(bool);
+
+#[cfg(feature = "dev")]
+fn toggle_picking_areas(mut show: ResMut<ShowPickingAreas>) {
+    show.0 = !show.0;
+}
+
+#[cfg(feature = "dev")]
+fn draw_picking_areas(
+    areas: Query<(
+        &GlobalTransform,
+        AnyOf<(&PickingAreaCircle, &PickingAreaRect)>,
+        Option<&RenderLayers>,
+    )>,
+    mut painter: ShapePainter,
+) {
+    painter.hollow = true;
+    painter.thickness = 1.0;
+    painter.color = MAGENTA.into();
+
+    for (transform, (circle, rect), render_layers) in &areas {
+        // Same space as the hit test in `pick_shape`
+        painter.transform = transform.compute_transform();
+        painter.transform.translation.z += 1.0;
+        painter.render_layers = Some(render_layers.cloned().unwrap_or_default());
+        if let Some(circle) = circle {
+            painter.circle(circle.0.radius);
+        }
+        if let Some(rect) = rect {
+            painter.rect(rect.0.size());
+        }
     }
 }
 
@@ -18,6 +85,11 @@ impl Plugin for AreaPickingPlugin {
 #[require(Transform)]
 pub struct PickingAreaCircle(pub Circle);
 
+/// Defines a rectangular picking area, centered at the entity.
+#[derive(Component)]
+#[require(Transform)]
+pub struct PickingAreaRect(pub Rectangle);
+
 fn pick_shape(
     ray_map: Res<RayMap>,
     cameras: Query<(
@@ -30,7 +102,7 @@ fn pick_shape(
     handle_shapes: Query<(
         Entity,
         &GlobalTransform,
-        &PickingAreaCircle,
+        AnyOf<(&PickingAreaCircle, &PickingAreaRect)>,
         Option<&Pickable>,
         Option<&RenderLayers>,
     )>,
@@ -43,7 +115,7 @@ fn pick_shape(
         .iter()
         .filter(|(_, transform, .., pickable, _)| {
             !transform.affine().is_nan()
-                && (!settings.require_markers || pickable.is_some_and(|p| p.is_hoverable))
+                && pickable.map_or(!settings.require_markers, |p| p.is_hoverable)
         })
         .collect::<Vec<_>>();
     radsort::sort_by_key(&mut sorted_handles, |(_, transform, ..)| {
@@ -66,7 +138,7 @@ fn pick_shape(
 
         let mut picks = vec![];
 
-        for (entity, handle_transform, circle, pickable, render_layers) in &sorted_handles {
+        for (entity, handle_transform, (circle, rect), pickable, render_layers) in &sorted_handles {
             if !render_layers
                 .unwrap_or_default()
                 .intersects(camera_render_layers)
@@ -91,7 +163,8 @@ fn pick_shape(
                 continue;
             };
 
-            let hit = cursor_pos_handle.length() < circle.0.radius;
+            let hit = circle.is_some_and(|circle| cursor_pos_handle.length() < circle.0.radius)
+                || rect.is_some_and(|rect| cursor_pos_handle.abs().cmplt(rect.0.half_size).all());
 
             if hit {
                 let hit_pos_world = handle_transform.transform_point(cursor_pos_handle.extend(0.0));
@@ -123,3 +196,47 @@ fn pick_shape(
         output.write(PointerHits::new(ray_id.pointer, picks, order));
     }
 }
+
+/// Returns whether `point` is inside the closed `polygon`, by the even-odd rule.
+pub fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut prev = match polygon.last() {
+        Some(&last) => last,
+        None => return false,
+    };
+    for &vertex in polygon {
+        // Count crossings of the edge with a ray towards +x
+        if (vertex.y > point.y) != (prev.y > point.y) {
+            let x = vertex.x + (point.y - vertex.y) / (prev.y - vertex.y) * (prev.x - vertex.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+        prev = vertex;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_in_polygon() {
+        // Concave "L" shape
+        let polygon = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        assert!(point_in_polygon(Vec2::new(0.5, 0.5), &polygon));
+        assert!(point_in_polygon(Vec2::new(1.5, 0.5), &polygon));
+        assert!(point_in_polygon(Vec2::new(0.5, 1.5), &polygon));
+        assert!(!point_in_polygon(Vec2::new(1.5, 1.5), &polygon));
+        assert!(!point_in_polygon(Vec2::new(-0.5, 0.5), &polygon));
+        assert!(!point_in_polygon(Vec2::new(0.5, 0.5), &[]));
+    }
+}
@@ -0,0 +1,175 @@
+//! Frames pinned to the screen, such as a reference image or a color key, which keep their
+//! position and size in the window while the [`MainCamera`] pans and zooms.
+
+use bevy::prelude::*;
+
+use super::{ImageFrame, MainCamera};
+
+pub struct PinPlugin;
+
+impl Plugin for PinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            follow_camera.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Keeps a frame at a fixed place on screen by moving and scaling it with the [`MainCamera`].
+/// Pinned frames are left out of organizing.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ScreenPinned {
+    /// Offset of the frame from the view center in logical pixels, y up.
+    offset: Vec2,
+    /// Scale of the frame per camera scale.
+    scale: f32,
+    /// Translation last set by [`follow_camera`], to tell when the frame was dragged.
+    translation: Vec2,
+}
+
+impl ScreenPinned {
+    fn new(transform: &Transform, camera: &Transform) -> Self {
+        let mut pinned = Self {
+            offset: Vec2::ZERO,
+            scale: transform.scale.x / camera.scale.x,
+            translation: transform.translation.xy(),
+        };
+        pinned.set_offset(transform, camera);
+        pinned
+    }
+
+    /// Pin restored from a layout for a frame at `translation`, from the values of
+    /// [`Self::saved`].
+    pub fn restore(offset: Vec2, scale: f32, translation: Vec2) -> Self {
+        Self {
+            offset,
+            scale,
+            translation,
+        }
+    }
+
+    /// Offset from the view center and scale per camera scale, to be saved in a layout.
+    pub fn saved(&self) -> (Vec2, f32) {
+        (self.offset, self.scale)
+    }
+
+    fn set_offset(&mut self, transform: &Transform, camera: &Transform) {
+        self.offset = (transform.translation.xy() - camera.translation.xy()) / camera.scale.x;
+    }
+}
+
+/// One-shot system to pin `target` frames to the screen where they are, or unpin them,
+/// following the first frame. Use `Commands::run_system_cached_with` to run it.
+pub fn toggle_pin(
+    In(target): In<Vec<Entity>>,
+    mut commands: Commands,
+    mut frames: Query<(Entity, &mut Transform, &mut Sprite, Has<ScreenPinned>), With<ImageFrame>>,
+    camera: Single<&Transform, (With<MainCamera>, Without<ImageFrame>)>,
+) {
+    let Some(pin) = frames.iter_many(&target).next().map(|(.., pinned)| !pinned) else {
+        return;
+    };
+
+    let mut iter = frames.iter_many_mut(&target);
+    while let Some((entity, mut transform, mut sprite, pinned)) = iter.fetch_next() {
+        if pin && !pinned {
+            commands
+                .entity(entity)
+                .insert(ScreenPinned::new(&transform, &camera));
+        } else if !pin && pinned {
+            commands.entity(entity).remove::<ScreenPinned>();
+            // Frames are sized by `custom_size` elsewhere, so keep the pinned scale there
+            let scale = transform.scale.xy();
+            if let Some(size) = &mut sprite.custom_size {
+                *size *= scale;
+            }
+            transform.scale = Vec3::ONE;
+        }
+    }
+    info!(
+        "{} {} frame(s)",
+        if pin { "Pinned" } else { "Unpinned" },
+        target.len()
+    );
+}
+
+fn follow_camera(
+    camera: Single<&Transform, (With<MainCamera>, Without<ScreenPinned>)>,
+    mut frames: Query<(&mut Transform, &mut ScreenPinned)>,
+) {
+    for (mut transform, mut pinned) in &mut frames {
+        if transform.translation.xy() != pinned.translation {
+            // Dragged to a new place on screen
+            pinned.set_offset(&transform, &camera);
+        }
+
+        let translation = camera.translation.xy() + pinned.offset * camera.scale.x;
+        let scale = Vec3::splat(pinned.scale * camera.scale.x);
+        if transform.translation.xy() != translation || transform.scale != scale {
+            transform.translation = translation.extend(transform.translation.z);
+            transform.scale = scale;
+        }
+        pinned.set_if_neq(ScreenPinned {
+            translation,
+            ..*pinned
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_frame_follows_camera() {
+        let mut world = World::new();
+        let camera = world
+            .spawn((MainCamera, Transform::from_xyz(100.0, 0.0, 0.0)))
+            .id();
+        let frame = world
+            .spawn((
+                ImageFrame(default()),
+                Transform::from_xyz(110.0, 20.0, 0.5),
+                Sprite {
+                    custom_size: Some(Vec2::splat(10.0)),
+                    ..default()
+                },
+            ))
+            .id();
+
+        world
+            .run_system_cached_with(toggle_pin, vec![frame])
+            .unwrap();
+        assert!(world.get::<ScreenPinned>(frame).is_some());
+
+        // Pan and zoom out by 2
+        *world.get_mut::<Transform>(camera).unwrap() =
+            Transform::from_xyz(0.0, 50.0, 0.0).with_scale(Vec3::splat(2.0));
+        world.run_system_cached(follow_camera).unwrap();
+        let transform = *world.get::<Transform>(frame).unwrap();
+        assert_eq!(transform.translation, Vec3::new(20.0, 90.0, 0.5));
+        assert_eq!(transform.scale, Vec3::splat(2.0));
+
+        // Dragged by 10 pixels
+        world.get_mut::<Transform>(frame).unwrap().translation.x += 20.0;
+        world.run_system_cached(follow_camera).unwrap();
+        *world.get_mut::<Transform>(camera).unwrap() = Transform::from_scale(Vec3::splat(2.0));
+        world.run_system_cached(follow_camera).unwrap();
+        assert_eq!(
+            world.get::<Transform>(frame).unwrap().translation,
+            Vec3::new(40.0, 40.0, 0.5)
+        );
+
+        // Unpinning keeps the size on screen
+        world
+            .run_system_cached_with(toggle_pin, vec![frame])
+            .unwrap();
+        assert!(world.get::<ScreenPinned>(frame).is_none());
+        assert_eq!(world.get::<Transform>(frame).unwrap().scale, Vec3::ONE);
+        assert_eq!(
+            world.get::<Sprite>(frame).unwrap().custom_size,
+            Some(Vec2::splat(20.0))
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Texture filtering of frame images. Pixel art stays crisp when zoomed in with
+//! [`SampleMode::Nearest`].
+//!
+//! The sampler belongs to the image, which frames of the same file share. Each frame has its
+//! own [`SampleMode`], and [`SampledImages`] keeps one copy of a source image for each mode
+//! other than the one of the source, shared by all frames of that image in the mode.
+
+use bevy::{
+    image::{ImageFilterMode, ImageSampler},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use super::ImageFrame;
+
+pub struct SamplingPlugin;
+
+impl Plugin for SamplingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SampledImages>();
+    }
+}
+
+/// How the image of a frame is filtered when scaled. Frames without one are set up in the mode
+/// of `ImportSettings`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+pub enum SampleMode {
+    /// Crisp pixels, for pixel art.
+    #[default]
+    Nearest,
+    /// Smooth interpolation, for photos and illustrations.
+    Linear,
+}
+
+impl SampleMode {
+    /// Mode of `sampler`. [`ImageSampler::Default`] is linear as set up by `ImagePlugin`.
+    pub fn of(sampler: &ImageSampler) -> Self {
+        match sampler {
+            ImageSampler::Descriptor(descriptor)
+                if matches!(descriptor.mag_filter, ImageFilterMode::Nearest) =>
+            {
+                SampleMode::Nearest
+            }
+            _ => SampleMode::Linear,
+        }
+    }
+
+    fn sampler(&self) -> ImageSampler {
+        match self {
+            SampleMode::Nearest => ImageSampler::nearest(),
+            SampleMode::Linear => ImageSampler::linear(),
+        }
+    }
+
+    /// Sets the sampler of `image`, unless it is in this mode already (changing it re-uploads
+    /// the image).
+    pub fn apply(&self, images: &mut Assets<Image>, image: &Handle<Image>) {
+        if images
+            .get(image)
+            .is_none_or(|image| SampleMode::of(&image.sampler) == *self)
+        {
+            return;
+        }
+        if let Some(image) = images.get_mut(image) {
+            image.sampler = self.sampler();
+        }
+    }
+
+    fn toggled(&self) -> Self {
+        match self {
+            SampleMode::Nearest => SampleMode::Linear,
+            SampleMode::Linear => SampleMode::Nearest,
+        }
+    }
+
+    /// Value of the `sample` layout property.
+    pub fn to_property(self) -> &'static str {
+        match self {
+            SampleMode::Nearest => "nearest",
+            SampleMode::Linear => "linear",
+        }
+    }
+
+    pub fn from_property(value: &str) -> Option<Self> {
+        match value {
+            "nearest" => Some(SampleMode::Nearest),
+            "linear" => Some(SampleMode::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// Images shown by frames in each [`SampleMode`]. A source image is filtered in the mode of the
+/// first frame set up with it, and never changed afterwards.
+#[derive(Resource, Default)]
+pub struct SampledImages {
+    /// Mode each source image was set to.
+    sources: HashMap<AssetId<Image>, SampleMode>,
+    /// Copy of a source image in another mode.
+    copies: HashMap<(AssetId<Image>, SampleMode), Handle<Image>>,
+}
+
+impl SampledImages {
+    /// Returns the image showing `source` in `mode`, copying `source` the first time it is
+    /// needed in a mode other than its own.
+    pub fn image(
+        &mut self,
+        images: &mut Assets<Image>,
+        source: &Handle<Image>,
+        mode: SampleMode,
+    ) -> Handle<Image> {
+        let source_mode = *self.sources.entry(source.id()).or_insert_with(|| {
+            mode.apply(images, source);
+            mode
+        });
+        if source_mode == mode {
+            return source.clone();
+        }
+        if let Some(copy) = self.copies.get(&(source.id(), mode)) {
+            return copy.clone();
+        }
+
+        let Some(mut image) = images.get(source).cloned() else {
+            return source.clone();
+        };
+        image.sampler = mode.sampler();
+        let copy = images.add(image);
+        self.copies.insert((source.id(), mode), copy.clone());
+        copy
+    }
+}
+
+/// One-shot system to switch `target` frames between nearest and linear filtering, following
+/// the first frame. Use `Commands::run_system_cached_with` to run it.
+pub fn toggle_sample_mode(
+    In(target): In<Vec<Entity>>,
+    mut frames: Query<(&ImageFrame, &mut SampleMode, &mut Sprite)>,
+    mut images: ResMut<Assets<Image>>,
+    mut sampled: ResMut<SampledImages>,
+) {
+    let Some(mode) = frames
+        .iter_many(&target)
+        .next()
+        .map(|(_, mode, _)| mode.toggled())
+    else {
+        return;
+    };
+
+    let mut iter = frames.iter_many_mut(&target);
+    while let Some((frame, mut frame_mode, mut sprite)) = iter.fetch_next() {
+        *frame_mode = mode;
+        let image = sampled.image(&mut images, &frame.0, mode);
+        if sprite.image != image {
+            sprite.image = image;
+        }
+    }
+    info!("Sample mode of {} frame(s) set to {mode:?}", target.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_sample_mode() {
+        let mut world = World::new();
+        let mut images = Assets::<Image>::default();
+        let image = images.add(Image::default());
+        let mut sampled = SampledImages::default();
+        let shown = sampled.image(&mut images, &image, SampleMode::Linear);
+        world.insert_resource(images);
+        world.insert_resource(sampled);
+
+        // Frames of the same image
+        let [a, b, c] = [(); 3].map(|_| {
+            world
+                .spawn((
+                    ImageFrame(image.clone()),
+                    SampleMode::Linear,
+                    Sprite::from_image(shown.clone()),
+                ))
+                .id()
+        });
+        let mode = |world: &World, frame: Entity| {
+            let sprite = world.get::<Sprite>(frame).unwrap();
+            let images = world.resource::<Assets<Image>>();
+            SampleMode::of(&images.get(&sprite.image).unwrap().sampler)
+        };
+        let image_of =
+            |world: &World, frame: Entity| world.get::<Sprite>(frame).unwrap().image.clone();
+
+        assert_eq!(mode(&world, a), SampleMode::Linear);
+        world
+            .run_system_cached_with(toggle_sample_mode, vec![a])
+            .unwrap();
+        assert_eq!(mode(&world, a), SampleMode::Nearest);
+        assert_eq!(world.get::<SampleMode>(a), Some(&SampleMode::Nearest));
+        assert_eq!(mode(&world, b), SampleMode::Linear);
+
+        // Toggled separately, but showing the same copy
+        world
+            .run_system_cached_with(toggle_sample_mode, vec![b])
+            .unwrap();
+        assert_eq!(image_of(&world, b), image_of(&world, a));
+        assert_eq!(world.resource::<Assets<Image>>().len(), 2);
+
+        // Back to the source image
+        world
+            .run_system_cached_with(toggle_sample_mode, vec![a, b])
+            .unwrap();
+        assert_eq!(image_of(&world, a), image);
+        assert_eq!(image_of(&world, b), image);
+        assert_eq!(mode(&world, c), SampleMode::Linear);
+        assert_eq!(world.resource::<Assets<Image>>().len(), 2);
+    }
+}
@@ -0,0 +1,249 @@
+//! Scaling of the selected frames with the keyboard.
+//!
+//! A single frame is scaled about its center, multiple frames about the center of the group so
+//! that their arrangement is kept. A burst of presses is recorded as one undo entry.
+
+use bevy::prelude::*;
+
+use crate::{
+    key_bindings::{Action, InvokeAction, KeyBindings},
+    modal::ModalActive,
+};
+
+use super::{
+    ImageFrame, Selected,
+    undo::{EditAction, UndoStack},
+};
+
+/// Factor applied by one press of [`Action::ScaleUp`], and its inverse by [`Action::ScaleDown`].
+const SCALE_STEP: f32 = 1.1;
+/// Minimum width and height of a frame scaled down.
+const MIN_FRAME_SIZE: f32 = 4.0;
+/// Presses within this many seconds of the previous one are merged into one undo entry.
+const COALESCE_SECS: f64 = 1.0;
+
+pub struct ScalePlugin;
+
+impl Plugin for ScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, scale_selection);
+    }
+}
+
+/// Translation and size of a frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameGeometry {
+    translation: Vec3,
+    size: Vec2,
+}
+
+/// Frames scaled by a burst of presses.
+struct ScaleEdit {
+    /// Sorted by entity.
+    frames: Vec<(Entity, FrameGeometry, FrameGeometry)>,
+    /// Elapsed seconds at the last press.
+    last_press: f64,
+}
+
+impl ScaleEdit {
+    fn apply(world: &mut World, entity: Entity, geometry: FrameGeometry) {
+        let Ok(mut frame) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut transform) = frame.get_mut::<Transform>() {
+            transform.translation = geometry.translation;
+        }
+        if let Some(mut sprite) = frame.get_mut::<Sprite>() {
+            sprite.custom_size = Some(geometry.size);
+        }
+    }
+}
+
+impl EditAction for ScaleEdit {
+    fn describe(&self) -> String {
+        format!("scale {} frame(s)", self.frames.len())
+    }
+
+    fn thumbnail_frame(&self) -> Option<Entity> {
+        self.frames.first().map(|(entity, ..)| *entity)
+    }
+
+    fn undo(&self, world: &mut World) {
+        for &(entity, before, _) in &self.frames {
+            Self::apply(world, entity, before);
+        }
+    }
+
+    fn redo(&self, world: &mut World) {
+        for &(entity, _, after) in &self.frames {
+            Self::apply(world, entity, after);
+        }
+    }
+}
+
+fn scale_selection(
+    key_bindings: Res<KeyBindings>,
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut frames: Query<(Entity, &mut Transform, &mut Sprite), (With<ImageFrame>, With<Selected>)>,
+    mut undo_stack: ResMut<UndoStack>,
+    modal: Option<Res<ModalActive>>,
+    mut invoked: EventReader<InvokeAction>,
+) {
+    if modal.is_some() {
+        return;
+    }
+    let invoked = invoked.read().map(|event| event.0).collect::<Vec<_>>();
+    let just_pressed =
+        |action| invoked.contains(&action) || key_bindings.just_pressed(action, &input);
+    let scale_up = if just_pressed(Action::ScaleUp) {
+        true
+    } else if just_pressed(Action::ScaleDown) {
+        false
+    } else {
+        return;
+    };
+
+    let mut frames = frames
+        .iter_mut()
+        .filter(|(_, _, sprite)| sprite.custom_size.is_some())
+        .collect::<Vec<_>>();
+    if frames.is_empty() {
+        return;
+    }
+    frames.sort_by_key(|(entity, ..)| *entity);
+
+    let factor = if scale_up {
+        SCALE_STEP
+    } else {
+        // Shrink no further than the smallest frame reaching the minimum size
+        let min_side = frames
+            .iter()
+            .map(|(_, _, sprite)| sprite.custom_size.unwrap().abs().min_element())
+            .fold(f32::INFINITY, f32::min);
+        SCALE_STEP.recip().max(MIN_FRAME_SIZE / min_side)
+    };
+    if !scale_up && factor >= 1.0 {
+        return;
+    }
+
+    let center = if frames.len() == 1 {
+        frames[0].1.translation.xy()
+    } else {
+        let (min, max) = frames.iter().fold(
+            (Vec2::INFINITY, Vec2::NEG_INFINITY),
+            |(min, max), (_, transform, _)| {
+                let translation = transform.translation.xy();
+                (min.min(translation), max.max(translation))
+            },
+        );
+        (min + max) / 2.0
+    };
+
+    let mut scaled = Vec::with_capacity(frames.len());
+    for (entity, transform, sprite) in &mut frames {
+        let before = FrameGeometry {
+            translation: transform.translation,
+            size: sprite.custom_size.unwrap(),
+        };
+        let after = FrameGeometry {
+            translation: (center + (before.translation.xy() - center) * factor)
+                .extend(before.translation.z),
+            size: before.size * factor,
+        };
+        transform.translation = after.translation;
+        sprite.custom_size = Some(after.size);
+        scaled.push((*entity, before, after));
+    }
+
+    let now = time.elapsed_secs_f64();
+    if let Some(edit) = undo_stack.last_mut::<ScaleEdit>()
+        && now - edit.last_press < COALESCE_SECS
+        && edit
+            .frames
+            .iter()
+            .map(|(entity, ..)| *entity)
+            .eq(scaled.iter().map(|(entity, ..)| *entity))
+    {
+        for ((_, _, after), (_, _, scaled_after)) in edit.frames.iter_mut().zip(&scaled) {
+            *after = *scaled_after;
+        }
+        edit.last_press = now;
+        return;
+    }
+
+    undo_stack.push(ScaleEdit {
+        frames: scaled,
+        last_press: now,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::canvas::undo;
+
+    use super::*;
+
+    fn press(world: &mut World, key: KeyCode) {
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        input.press(key);
+        world.run_system_cached(scale_selection).unwrap();
+    }
+
+    #[test]
+    fn test_scale_selection() {
+        let mut world = World::new();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<Events<InvokeAction>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<Time>();
+        world.init_resource::<UndoStack>();
+
+        let frame = |x: f32, size: f32| {
+            (
+                ImageFrame(default()),
+                Selected,
+                Transform::from_xyz(x, 0.0, 1.0),
+                Sprite {
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+            )
+        };
+        let a = world.spawn(frame(-10.0, 10.0)).id();
+        let b = world.spawn(frame(30.0, 20.0)).id();
+
+        press(&mut world, KeyCode::Equal);
+        press(&mut world, KeyCode::Equal);
+        let sprite = world.get::<Sprite>(a).unwrap();
+        assert!(
+            sprite
+                .custom_size
+                .unwrap()
+                .abs_diff_eq(Vec2::splat(12.1), 1e-4)
+        );
+        // Scaled about the group center at x = 10
+        let translation = world.get::<Transform>(b).unwrap().translation;
+        assert!(translation.abs_diff_eq(Vec3::new(34.2, 0.0, 1.0), 1e-4));
+
+        // The burst is undone at once
+        undo::undo(&mut world);
+        assert_eq!(
+            world.get::<Sprite>(a).unwrap().custom_size,
+            Some(Vec2::splat(10.0))
+        );
+        assert_eq!(
+            world.get::<Transform>(b).unwrap().translation,
+            Vec3::new(30.0, 0.0, 1.0)
+        );
+
+        // Scaling down stops at the minimum size
+        for _ in 0..20 {
+            press(&mut world, KeyCode::Minus);
+        }
+        let size = world.get::<Sprite>(a).unwrap().custom_size.unwrap();
+        assert!(size.abs_diff_eq(Vec2::splat(MIN_FRAME_SIZE), 1e-4));
+    }
+}
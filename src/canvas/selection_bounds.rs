@@ -0,0 +1,137 @@
+//! Bounding box of the whole selection, with its size, shown while two or more frames are
+//! selected.
+
+use bevy::{prelude::*, text::Update2dText};
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::RectPainter};
+
+use crate::despawn::SafeDespawn;
+
+use super::{CONTROL_LAYER, ImageFrame, Selected, camera_util::CameraTranslator, culling};
+
+/// Offset of the readout above the top edge of the box in logical pixels.
+const READOUT_OFFSET: Vec3 = Vec3::new(0.0, 16.0, 1.0);
+
+pub struct SelectionBoundsPlugin;
+
+impl Plugin for SelectionBoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectionBounds>().add_systems(
+            PostUpdate,
+            (draw_selection_bounds, update_readout)
+                .chain()
+                .after(TransformSystem::TransformPropagate)
+                // The readout text is laid out in the same frame
+                .before(Update2dText),
+        );
+    }
+}
+
+/// Bounding box of the selection in world space and in control space, if shown.
+#[derive(Resource, Default)]
+struct SelectionBounds(Option<(Rect, Rect)>);
+
+/// Text showing the size of the selection bounding box.
+#[derive(Component)]
+struct SelectionBoundsReadout;
+
+/// World space bounding box of `frames`, or `None` for fewer than two frames.
+fn selection_bounds<'a>(
+    frames: impl IntoIterator<Item = (&'a GlobalTransform, &'a Sprite)>,
+) -> Option<Rect> {
+    let (count, bounds) =
+        frames
+            .into_iter()
+            .fold((0, Rect::EMPTY), |(count, bounds), (transform, sprite)| {
+                let size = sprite.custom_size.unwrap_or(Vec2::ZERO);
+                (
+                    count + 1,
+                    bounds.union(culling::frame_bounds(transform, size)),
+                )
+            });
+    (count >= 2).then_some(bounds)
+}
+
+fn draw_selection_bounds(
+    mut shown: ResMut<SelectionBounds>,
+    camera_translator: CameraTranslator,
+    frames: Query<(&GlobalTransform, &Sprite), (With<ImageFrame>, With<Selected>)>,
+    mut painter: ShapePainter,
+) -> Result {
+    shown.0 = None;
+    let Some(bounds) = selection_bounds(frames) else {
+        return Ok(());
+    };
+    let rect = camera_translator.map_rect_to_control(&bounds)?;
+    shown.0 = Some((bounds, rect));
+
+    painter.render_layers = Some(CONTROL_LAYER);
+    painter.hollow = true;
+    painter.thickness = 1.0;
+    painter.color = Color::srgba(0.0, 1.0, 0.0, 0.6);
+    painter.transform = Transform::from_translation(rect.center().extend(0.0));
+    painter.rect(rect.size());
+
+    Ok(())
+}
+
+fn update_readout(
+    mut commands: Commands,
+    shown: Res<SelectionBounds>,
+    mut readout: Query<
+        (Entity, &mut Text2d, &mut Transform, &mut GlobalTransform),
+        With<SelectionBoundsReadout>,
+    >,
+) {
+    let Some((bounds, rect)) = shown.0 else {
+        for (entity, ..) in &readout {
+            commands.safe_despawn(entity);
+        }
+        return;
+    };
+
+    let size = bounds.size();
+    let label = format!("{:.0} x {:.0}", size.x, size.y);
+    let readout_transform =
+        Transform::from_translation(Vec3::new(rect.center().x, rect.max.y, 0.0) + READOUT_OFFSET);
+    if let Ok((_, mut text, mut transform, mut global_transform)) = readout.single_mut() {
+        if text.0 != label {
+            text.0 = label;
+        }
+        transform.set_if_neq(readout_transform);
+        // Already past transform propagation
+        global_transform.set_if_neq(readout_transform.into());
+    } else {
+        commands.spawn((
+            Name::new("SelectionBoundsReadout"),
+            SelectionBoundsReadout,
+            Text2d::new(label),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            readout_transform,
+            CONTROL_LAYER,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_bounds() {
+        let sprite = Sprite {
+            custom_size: Some(Vec2::new(10.0, 20.0)),
+            ..default()
+        };
+        let a = GlobalTransform::from_xyz(0.0, 0.0, 0.0);
+        let b = GlobalTransform::from_xyz(100.0, 50.0, 0.0);
+
+        assert_eq!(selection_bounds([(&a, &sprite)]), None);
+        assert_eq!(
+            selection_bounds([(&a, &sprite), (&b, &sprite)]),
+            Some(Rect::new(-5.0, -10.0, 105.0, 60.0))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! History of recent selections, to recover a selection lost by a stray click.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::key_bindings::{Action, action_just_pressed};
+
+use super::{ImageFrame, Selected};
+
+/// Maximum number of previous selections to keep.
+const HISTORY_DEPTH: usize = 16;
+
+pub struct SelectionHistoryPlugin;
+
+impl Plugin for SelectionHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectionHistory>()
+            .add_systems(
+                Update,
+                reselect_last.run_if(action_just_pressed(Action::ReselectLast)),
+            )
+            .add_systems(Last, track_selection);
+    }
+}
+
+/// Recent non-empty selections, newest last.
+#[derive(Resource, Default)]
+pub struct SelectionHistory {
+    /// The current selection, sorted.
+    current: Vec<Entity>,
+    previous: VecDeque<Vec<Entity>>,
+}
+
+impl SelectionHistory {
+    /// Records `selection` as the current one, pushing the replaced selection to the history.
+    fn record(&mut self, mut selection: Vec<Entity>) {
+        selection.sort();
+        if selection == self.current {
+            return;
+        }
+
+        let replaced = std::mem::replace(&mut self.current, selection);
+        if replaced.is_empty() {
+            return;
+        }
+        if self.previous.len() == HISTORY_DEPTH {
+            self.previous.pop_front();
+        }
+        self.previous.push_back(replaced);
+    }
+}
+
+fn track_selection(
+    mut history: ResMut<SelectionHistory>,
+    added: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let any_removed = removed.read().count() > 0;
+    if added.is_empty() && !any_removed {
+        return;
+    }
+
+    history.record(selected.iter().collect());
+}
+
+/// Restores the previous selection. Repeating it walks further back in the history.
+/// Frames that no longer exist are skipped.
+pub fn reselect_last(
+    mut commands: Commands,
+    mut history: ResMut<SelectionHistory>,
+    selected: Query<Entity, With<Selected>>,
+    frames: Query<(), With<ImageFrame>>,
+) {
+    while let Some(mut selection) = history.previous.pop_back() {
+        selection.retain(|&entity| frames.contains(entity));
+        if selection.is_empty() {
+            continue;
+        }
+
+        for entity in &selected {
+            commands.entity(entity).remove::<Selected>();
+        }
+        for &entity in &selection {
+            commands.entity(entity).insert(Selected);
+        }
+        // Restoring is not a new selection to push to the history
+        history.current = selection;
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(world: &mut World, frames: &[Entity]) {
+        let mut selected = world.query_filtered::<Entity, With<Selected>>();
+        for entity in selected.iter(world).collect::<Vec<_>>() {
+            world.entity_mut(entity).remove::<Selected>();
+        }
+        for &entity in frames {
+            world.entity_mut(entity).insert(Selected);
+        }
+        world.run_system_cached(track_selection).unwrap();
+    }
+
+    fn selection(world: &mut World) -> Vec<Entity> {
+        let mut selected = world.query_filtered::<Entity, With<Selected>>();
+        let mut selection = selected.iter(world).collect::<Vec<_>>();
+        selection.sort();
+        selection
+    }
+
+    #[test]
+    fn test_reselect_last() {
+        let mut world = World::new();
+        world.init_resource::<SelectionHistory>();
+
+        let a = world.spawn(ImageFrame(Handle::default())).id();
+        let b = world.spawn(ImageFrame(Handle::default())).id();
+        let c = world.spawn(ImageFrame(Handle::default())).id();
+
+        select(&mut world, &[a, b]);
+        select(&mut world, &[c]);
+        // A stray click
+        select(&mut world, &[]);
+
+        world.run_system_cached(reselect_last).unwrap();
+        world.run_system_cached(track_selection).unwrap();
+        assert_eq!(selection(&mut world), vec![c]);
+
+        world.despawn(b);
+        world.run_system_cached(reselect_last).unwrap();
+        world.run_system_cached(track_selection).unwrap();
+        assert_eq!(selection(&mut world), vec![a]);
+
+        // Nothing left to restore
+        world.run_system_cached(reselect_last).unwrap();
+        assert_eq!(selection(&mut world), vec![a]);
+    }
+}
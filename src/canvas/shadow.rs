@@ -0,0 +1,121 @@
+//! Drop shadows beneath frames, so that stacked frames read clearly.
+//!
+//! A shadow is drawn just below its frame in z, so it falls on the frames beneath but not on
+//! the frame itself. Blur is approximated by a few translucent rectangles of growing size.
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::RectPainter};
+
+use super::{ImageFrame, MainCamera, culling, z_order::Z_STEP};
+
+/// Number of rectangles drawn per shadow when blurred.
+const BLUR_LAYERS: usize = 4;
+
+pub struct ShadowPlugin;
+
+impl Plugin for ShadowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShadowSettings>()
+            .register_type::<ShadowSettings>()
+            .add_systems(
+                PostUpdate,
+                draw_shadows
+                    .after(TransformSystem::TransformPropagate)
+                    .after(culling::cull_frames)
+                    .run_if(|settings: Res<ShadowSettings>| settings.enabled),
+            );
+    }
+}
+
+/// Drop shadow settings. Offset and blur are in logical pixels, so shadows keep their size on
+/// screen while zooming.
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    /// Offset of the shadow from its frame. Positive y is up.
+    pub offset: Vec2,
+    /// Distance the shadow fades out over beyond the frame bounds.
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offset: Vec2::new(6.0, -6.0),
+            blur: 8.0,
+            color: Color::srgba(0.0, 0.0, 0.0, 0.4),
+        }
+    }
+}
+
+/// Sizes and alphas of the rectangles of a shadow for a frame of `size`, from the innermost.
+/// Where all of them overlap, they add up to `alpha`.
+fn shadow_layers(size: Vec2, blur: f32, alpha: f32) -> Vec<(Vec2, f32)> {
+    if blur <= 0.0 {
+        return vec![(size, alpha)];
+    }
+    let layer_alpha = 1.0 - (1.0 - alpha).powf(1.0 / BLUR_LAYERS as f32);
+    (0..BLUR_LAYERS)
+        .map(|i| {
+            let grow = blur * i as f32 / (BLUR_LAYERS - 1) as f32;
+            (size + Vec2::splat(2.0 * grow), layer_alpha)
+        })
+        .collect()
+}
+
+fn draw_shadows(
+    settings: Res<ShadowSettings>,
+    camera: Single<&Transform, With<MainCamera>>,
+    frames: Query<
+        (&GlobalTransform, &Sprite, &InheritedVisibility),
+        (With<ImageFrame>, Without<culling::Culled>),
+    >,
+    mut painter: ShapePainter,
+) {
+    // World units per logical pixel
+    let scale = camera.scale.x;
+    let offset = settings.offset * scale;
+    let blur = settings.blur * scale;
+
+    for (transform, sprite, visibility) in &frames {
+        let Some(size) = sprite.custom_size else {
+            continue;
+        };
+        if !visibility.get() {
+            continue;
+        }
+
+        let mut transform = transform.compute_transform();
+        transform.translation += offset.extend(-Z_STEP / 2.0);
+        painter.transform = transform;
+        for (layer_size, alpha) in shadow_layers(size, blur, settings.color.alpha()) {
+            // Rounder corners for the outer layers
+            painter.corner_radii = Vec4::splat((layer_size - size).x / 2.0);
+            painter.color = settings.color.with_alpha(alpha);
+            painter.rect(layer_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_layers() {
+        let size = Vec2::new(10.0, 20.0);
+        assert_eq!(shadow_layers(size, 0.0, 0.5), vec![(size, 0.5)]);
+
+        let layers = shadow_layers(size, 3.0, 0.5);
+        assert_eq!(layers.len(), BLUR_LAYERS);
+        assert_eq!(layers.first().unwrap().0, size);
+        assert_eq!(layers.last().unwrap().0, Vec2::new(16.0, 26.0));
+
+        // The inner part has the full alpha after blending all layers
+        let transparency = layers.iter().map(|(_, alpha)| 1.0 - alpha).product::<f32>();
+        assert!((1.0 - transparency - 0.5).abs() < 1e-5);
+    }
+}
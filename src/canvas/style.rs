@@ -0,0 +1,179 @@
+//! Tint, opacity and flip flags of frames, edited in batches with one undo entry.
+
+use bevy::prelude::*;
+
+use super::{
+    ImageFrame,
+    undo::{EditAction, UndoStack},
+};
+
+/// Style of a frame. The alpha of `color` is the opacity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStyle {
+    pub color: Color,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl FrameStyle {
+    pub fn of(sprite: &Sprite) -> Self {
+        Self {
+            color: sprite.color,
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
+        }
+    }
+
+    pub fn apply(&self, sprite: &mut Sprite) {
+        sprite.color = self.color;
+        sprite.flip_x = self.flip_x;
+        sprite.flip_y = self.flip_y;
+    }
+}
+
+/// Input of [`set_style`]. Fields left `None` keep the value of each frame.
+pub struct StyleRequest {
+    pub targets: Vec<Entity>,
+    /// Tint without alpha.
+    pub tint: Option<Srgba>,
+    pub opacity: Option<f32>,
+}
+
+/// Tint and opacity shared by frames, or `None` where they differ.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CommonStyle {
+    /// Tint without alpha.
+    pub tint: Option<Srgba>,
+    pub opacity: Option<f32>,
+}
+
+impl CommonStyle {
+    pub fn of<'a>(sprites: impl IntoIterator<Item = &'a Sprite>) -> Self {
+        let mut colors = sprites.into_iter().map(|sprite| sprite.color.to_srgba());
+        let Some(first) = colors.next() else {
+            return Self::default();
+        };
+        let mut common = Self {
+            tint: Some(first.with_alpha(1.0)),
+            opacity: Some(first.alpha),
+        };
+        for color in colors {
+            if common.tint != Some(color.with_alpha(1.0)) {
+                common.tint = None;
+            }
+            if common.opacity != Some(color.alpha) {
+                common.opacity = None;
+            }
+        }
+        common
+    }
+}
+
+/// One-shot system to set the tint and opacity of frames, recorded as one undo entry. Use
+/// `Commands::run_system_cached_with` to run it.
+pub fn set_style(
+    In(request): In<StyleRequest>,
+    mut sprites: Query<(Entity, &mut Sprite), With<ImageFrame>>,
+    mut undo_stack: ResMut<UndoStack>,
+) {
+    let mut frames = vec![];
+    let mut targets = sprites.iter_many_mut(&request.targets);
+    while let Some((entity, mut sprite)) = targets.fetch_next() {
+        let before = FrameStyle::of(&sprite);
+        // Colors are kept in their space so that setting only the opacity keeps the tint exact
+        let mut color = before.color;
+        if let Some(tint) = request.tint {
+            color = Color::from(tint.with_alpha(color.alpha()));
+        }
+        if let Some(opacity) = request.opacity {
+            color.set_alpha(opacity.clamp(0.0, 1.0));
+        }
+        let after = FrameStyle { color, ..before };
+        if after == before {
+            continue;
+        }
+        after.apply(&mut sprite);
+        frames.push((entity, before, after));
+    }
+
+    if frames.is_empty() {
+        return;
+    }
+    info!("Restyled {} frame(s)", frames.len());
+    undo_stack.push(StyleEdit { frames });
+}
+
+/// Styles applied to frames, with their previous styles.
+pub struct StyleEdit {
+    pub frames: Vec<(Entity, FrameStyle, FrameStyle)>,
+}
+
+impl EditAction for StyleEdit {
+    fn describe(&self) -> String {
+        format!("style {} frame(s)", self.frames.len())
+    }
+
+    fn thumbnail_frame(&self) -> Option<Entity> {
+        self.frames.first().map(|(entity, ..)| *entity)
+    }
+
+    fn undo(&self, world: &mut World) {
+        for (entity, before, _) in &self.frames {
+            if let Some(mut sprite) = world.get_mut::<Sprite>(*entity) {
+                before.apply(&mut sprite);
+            }
+        }
+    }
+
+    fn redo(&self, world: &mut World) {
+        for (entity, _, after) in &self.frames {
+            if let Some(mut sprite) = world.get_mut::<Sprite>(*entity) {
+                after.apply(&mut sprite);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::canvas::undo;
+
+    use super::*;
+
+    #[test]
+    fn test_set_style() {
+        let mut world = World::new();
+        world.init_resource::<UndoStack>();
+        let red = Sprite {
+            color: Color::srgba(1.0, 0.0, 0.0, 0.5),
+            ..default()
+        };
+        let a = world.spawn((ImageFrame(default()), red.clone())).id();
+        let b = world.spawn((ImageFrame(default()), Sprite::default())).id();
+
+        let white = Sprite::default();
+        let common = CommonStyle::of([&red, &white]);
+        assert_eq!(common.tint, None);
+        assert_eq!(common.opacity, None);
+
+        world
+            .run_system_cached_with(
+                set_style,
+                StyleRequest {
+                    targets: vec![a, b],
+                    tint: None,
+                    opacity: Some(0.25),
+                },
+            )
+            .unwrap();
+        let color = |world: &World, entity| world.get::<Sprite>(entity).unwrap().color;
+        // The tint of each frame is kept
+        assert_eq!(color(&world, a), Color::srgba(1.0, 0.0, 0.0, 0.25));
+        assert_eq!(color(&world, b), Color::WHITE.with_alpha(0.25));
+
+        // One undo entry for the batch
+        undo::undo(&mut world);
+        assert_eq!(color(&world, a), red.color);
+        assert_eq!(color(&world, b), Color::WHITE);
+    }
+}
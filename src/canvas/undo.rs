@@ -0,0 +1,190 @@
+//! Linear undo/redo of edits to frames.
+//!
+//! Edits push an [`EditAction`] to [`UndoStack`] after applying their change. Actions must
+//! tolerate entities despawned since they were recorded.
+
+use std::{any::Any, collections::VecDeque};
+
+use bevy::prelude::*;
+
+use crate::key_bindings::{Action, action_just_pressed};
+
+/// Maximum number of actions kept for undo.
+const UNDO_DEPTH: usize = 100;
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoStack>().add_systems(
+            Update,
+            (
+                undo.run_if(action_just_pressed(Action::Undo)),
+                redo.run_if(action_just_pressed(Action::Redo)),
+            ),
+        );
+    }
+}
+
+/// An undoable edit, applied already when pushed to [`UndoStack`].
+pub trait EditAction: Any + Send + Sync {
+    /// Short description of the edit for logs and the history panel.
+    fn describe(&self) -> String;
+
+    /// Frame whose image represents the edit in the history panel.
+    fn thumbnail_frame(&self) -> Option<Entity> {
+        None
+    }
+
+    /// Reverts the edit.
+    fn undo(&self, world: &mut World);
+
+    /// Applies the edit again after [`EditAction::undo`].
+    fn redo(&self, world: &mut World);
+}
+
+#[derive(Resource, Default)]
+pub struct UndoStack {
+    undo: VecDeque<Box<dyn EditAction>>,
+    redo: Vec<Box<dyn EditAction>>,
+}
+
+impl UndoStack {
+    /// Records an applied edit. Clears the redo history.
+    pub fn push(&mut self, action: impl EditAction) {
+        if self.undo.len() == UNDO_DEPTH {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(Box::new(action));
+        self.redo.clear();
+    }
+
+    /// Returns the last edit if it is a `T` and nothing was undone since, so that a burst of
+    /// similar edits can be merged into one entry instead of pushing each.
+    pub fn last_mut<T: EditAction>(&mut self) -> Option<&mut T> {
+        if !self.redo.is_empty() {
+            return None;
+        }
+        let action: &mut dyn Any = self.undo.back_mut()?.as_mut();
+        action.downcast_mut()
+    }
+
+    /// Applied and undone edits, from the oldest to the next to redo.
+    pub fn history(&self) -> impl Iterator<Item = &dyn EditAction> {
+        self.undo
+            .iter()
+            .chain(self.redo.iter().rev())
+            .map(|action| action.as_ref())
+    }
+
+    /// Number of applied edits, which is the position of the current state in
+    /// [`UndoStack::history`].
+    pub fn position(&self) -> usize {
+        self.undo.len()
+    }
+}
+
+pub fn undo(world: &mut World) {
+    let Some(action) = world.resource_mut::<UndoStack>().undo.pop_back() else {
+        return;
+    };
+    info!("Undo {}", action.describe());
+    action.undo(world);
+    world.resource_mut::<UndoStack>().redo.push(action);
+}
+
+pub fn redo(world: &mut World) {
+    let Some(action) = world.resource_mut::<UndoStack>().redo.pop() else {
+        return;
+    };
+    info!("Redo {}", action.describe());
+    action.redo(world);
+    world.resource_mut::<UndoStack>().undo.push_back(action);
+}
+
+/// Undoes or redoes edits until [`UndoStack::position`] is `position`.
+pub fn go_to(world: &mut World, position: usize) {
+    loop {
+        let stack = world.resource::<UndoStack>();
+        let current = stack.position();
+        if current > position {
+            undo(world);
+        } else if current < position && !stack.redo.is_empty() {
+            redo(world);
+        } else {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    struct Increment;
+
+    impl EditAction for Increment {
+        fn describe(&self) -> String {
+            "increment".to_string()
+        }
+
+        fn undo(&self, world: &mut World) {
+            world.resource_mut::<Counter>().0 -= 1;
+        }
+
+        fn redo(&self, world: &mut World) {
+            world.resource_mut::<Counter>().0 += 1;
+        }
+    }
+
+    fn increment(world: &mut World) {
+        world.resource_mut::<Counter>().0 += 1;
+        world.resource_mut::<UndoStack>().push(Increment);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.init_resource::<UndoStack>();
+
+        increment(&mut world);
+        increment(&mut world);
+        undo(&mut world);
+        undo(&mut world);
+        // Nothing left to undo
+        undo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 0);
+
+        redo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+
+        // A new edit drops the redo history
+        increment(&mut world);
+        redo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 2);
+    }
+
+    #[test]
+    fn test_go_to() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.init_resource::<UndoStack>();
+
+        for _ in 0..4 {
+            increment(&mut world);
+        }
+        go_to(&mut world, 1);
+        assert_eq!(world.resource::<Counter>().0, 1);
+        assert_eq!(world.resource::<UndoStack>().history().count(), 4);
+
+        go_to(&mut world, 3);
+        assert_eq!(world.resource::<Counter>().0, 3);
+        // Past the end of the history
+        go_to(&mut world, 10);
+        assert_eq!(world.resource::<UndoStack>().position(), 4);
+    }
+}
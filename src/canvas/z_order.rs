@@ -0,0 +1,209 @@
+//! Stacking order of [`ImageFrame`]s.
+//!
+//! Frames are stacked by `Transform::translation.z`, which is kept evenly spaced
+//! within the `(0, 1)` band.
+
+use bevy::prelude::*;
+
+use super::ImageFrame;
+
+/// Distance between the z values of adjacent frames.
+pub const Z_STEP: f32 = 1.0 / 65536.0;
+
+type FrameZ<'w, 's> =
+    Query<'w, 's, (Entity, &'static mut Transform), (With<ImageFrame>, With<Sprite>)>;
+
+/// Set-up frames from the bottom of the stack to the top.
+fn stacking_order(frames: &FrameZ) -> Vec<Entity> {
+    let mut sorted = frames
+        .iter()
+        .map(|(frame, transform)| (frame, transform.translation.z))
+        .collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+    sorted.into_iter().map(|(frame, _)| frame).collect()
+}
+
+/// Gives frames in `order` evenly spaced z from the bottom.
+fn respace(frames: &mut FrameZ, order: &[Entity]) {
+    for (i, &frame) in order.iter().enumerate() {
+        if let Ok((_, mut transform)) = frames.get_mut(frame) {
+            let z = (i + 1) as f32 * Z_STEP;
+            if transform.translation.z != z {
+                transform.translation.z = z;
+            }
+        }
+    }
+}
+
+/// One-shot system to move `target` frames to `index` in the stack counted from the bottom,
+/// keeping their relative order, then re-space z of all frames. `index` is clamped to the
+/// number of other frames.
+pub fn move_in_stack(In((target, index)): In<(Vec<Entity>, usize)>, mut frames: FrameZ) {
+    let (moved, mut order): (Vec<_>, Vec<_>) = stacking_order(&frames)
+        .into_iter()
+        .partition(|frame| target.contains(frame));
+    let index = index.min(order.len());
+    order.splice(index..index, moved);
+    respace(&mut frames, &order);
+}
+
+/// Moves `target` frames above all other frames, keeping their relative order.
+pub fn bring_to_front(In(target): In<Vec<Entity>>, frames: FrameZ) {
+    move_in_stack(In((target, usize::MAX)), frames);
+}
+
+/// Moves `target` frames below all other frames, keeping their relative order.
+pub fn send_to_back(In(target): In<Vec<Entity>>, frames: FrameZ) {
+    move_in_stack(In((target, 0)), frames);
+}
+
+/// One-shot system to set the stack `index` of `target` frames as typed by the user, counted
+/// from 0 at the bottom. The index is clamped to the stack and converted to z by re-spacing,
+/// so z stays within the `(0, 1)` band.
+pub fn set_z(In((target, index)): In<(Vec<Entity>, i64)>, frames: FrameZ) {
+    let index = usize::try_from(index).unwrap_or(0);
+    move_in_stack(In((target, index)), frames);
+}
+
+/// Index of a frame at `z` in the stack of `frames`, counted from 0 at the bottom.
+pub fn stack_index<'a>(z: f32, frames: impl IntoIterator<Item = &'a Transform>) -> usize {
+    frames
+        .into_iter()
+        .filter(|transform| transform.translation.z < z)
+        .count()
+}
+
+/// Places a newly set-up frame just above all other frames.
+pub fn on_add_frame_sprite(
+    trigger: Trigger<OnAdd, Sprite>,
+    mut frames: Query<(Entity, &mut Transform), (With<ImageFrame>, With<Sprite>)>,
+) {
+    let target = trigger.target();
+    let top = frames
+        .iter()
+        .filter(|(frame, _)| *frame != target)
+        .map(|(_, transform)| transform.translation.z)
+        .fold(0.0, f32::max);
+
+    if let Ok((_, mut transform)) = frames.get_mut(target) {
+        transform.translation.z = top + Z_STEP;
+    }
+}
+
+/// Closes the gap left by a removed frame.
+pub fn on_remove_frame(_trigger: Trigger<OnRemove, ImageFrame>, mut commands: Commands) {
+    commands.run_system_cached(normalize_z);
+}
+
+/// Re-spaces z of all set-up frames by [`Z_STEP`], keeping their current order.
+pub fn normalize_z(mut frames: FrameZ) {
+    let order = stacking_order(&frames);
+    respace(&mut frames, &order);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_frame(world: &mut World) -> Entity {
+        world
+            .spawn((ImageFrame(Handle::default()), Sprite::default()))
+            .id()
+    }
+
+    fn z(world: &World, frame: Entity) -> f32 {
+        world.get::<Transform>(frame).unwrap().translation.z
+    }
+
+    fn assert_on_top(world: &mut World, frame: Entity) {
+        let mut frames = world.query_filtered::<(Entity, &Transform), With<ImageFrame>>();
+        for (other, transform) in frames.iter(world) {
+            if other != frame {
+                assert!(transform.translation.z < z(world, frame));
+            }
+        }
+    }
+
+    #[test]
+    fn test_newest_frame_on_top() {
+        let mut world = World::new();
+        world.add_observer(on_add_frame_sprite);
+        world.add_observer(on_remove_frame);
+
+        let a = spawn_frame(&mut world);
+        let b = spawn_frame(&mut world);
+        let c = spawn_frame(&mut world);
+        assert_on_top(&mut world, c);
+
+        world.despawn(c);
+        world.despawn(a);
+        world.flush();
+        assert_eq!(z(&world, b), Z_STEP);
+
+        let d = spawn_frame(&mut world);
+        assert_on_top(&mut world, d);
+
+        world.despawn(b);
+        world.flush();
+        let e = spawn_frame(&mut world);
+        assert_on_top(&mut world, e);
+        assert_eq!(z(&world, d), Z_STEP);
+        assert_eq!(z(&world, e), 2.0 * Z_STEP);
+    }
+
+    #[test]
+    fn test_send_to_back_below_bottom_frame() {
+        let mut world = World::new();
+        world.add_observer(on_add_frame_sprite);
+
+        let a = spawn_frame(&mut world);
+        let b = spawn_frame(&mut world);
+        let c = spawn_frame(&mut world);
+        world.run_system_cached(normalize_z).unwrap();
+        assert_eq!(z(&world, a), Z_STEP);
+
+        // The old bottom frame ends up above the sent ones, which keep their order
+        world
+            .run_system_cached_with(send_to_back, vec![c, b])
+            .unwrap();
+        assert!(z(&world, b) < z(&world, c));
+        assert!(z(&world, c) < z(&world, a));
+        assert_eq!(z(&world, b), Z_STEP);
+
+        world
+            .run_system_cached_with(bring_to_front, vec![b])
+            .unwrap();
+        assert_on_top(&mut world, b);
+
+        world
+            .run_system_cached_with(move_in_stack, (vec![b], 1))
+            .unwrap();
+        assert_eq!(z(&world, b), 2.0 * Z_STEP);
+    }
+
+    #[test]
+    fn test_set_z() {
+        let mut world = World::new();
+        world.add_observer(on_add_frame_sprite);
+
+        let a = spawn_frame(&mut world);
+        let b = spawn_frame(&mut world);
+        let c = spawn_frame(&mut world);
+        world.run_system_cached(normalize_z).unwrap();
+
+        // Between `a` and `b`, then re-spaced
+        world.run_system_cached_with(set_z, (vec![c], 1)).unwrap();
+        assert_eq!(z(&world, a), Z_STEP);
+        assert_eq!(z(&world, c), 2.0 * Z_STEP);
+        assert_eq!(z(&world, b), 3.0 * Z_STEP);
+        let mut transforms = world.query::<&Transform>();
+        assert_eq!(stack_index(z(&world, c), transforms.iter(&world)), 1);
+
+        // Clamped to the stack
+        world.run_system_cached_with(set_z, (vec![a], 10)).unwrap();
+        assert_on_top(&mut world, a);
+        assert_eq!(z(&world, a), 3.0 * Z_STEP);
+        world.run_system_cached_with(set_z, (vec![a], -1)).unwrap();
+        assert_eq!(z(&world, a), Z_STEP);
+    }
+}
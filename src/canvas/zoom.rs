@@ -0,0 +1,114 @@
+//! Zoom settings and snapping to integer pixel ratios for pixel art.
+//!
+//! At a zoom of N or 1/N, each image pixel covers a whole number of screen pixels (or the
+//! other way around), so nearest-filtered sprites render without shimmer.
+
+use bevy::prelude::*;
+
+use crate::key_bindings::{Action, action_just_pressed};
+
+use super::{MainCamera, camera_tween::CameraTween};
+
+/// Factor of one continuous zoom step.
+const ZOOM_STEP: f32 = 1.1;
+
+pub struct ZoomPlugin;
+
+impl Plugin for ZoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZoomSettings>()
+            .register_type::<ZoomSettings>()
+            .add_systems(
+                Update,
+                (
+                    toggle_pixel_ratio_snap.run_if(action_just_pressed(Action::TogglePixelZoom)),
+                    zoom_to_actual_pixels.run_if(action_just_pressed(Action::ActualPixels)),
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource, Default)]
+pub struct ZoomSettings {
+    /// Zoom in steps of integer pixel ratios (100%, 200%, 50%, ...) instead of continuously.
+    pub pixel_ratio_snap: bool,
+}
+
+impl ZoomSettings {
+    /// Returns the camera scale after one zoom step from `scale`.
+    pub fn step(&self, scale: f32, zoom_in: bool) -> f32 {
+        if self.pixel_ratio_snap {
+            step_pixel_ratio(scale, zoom_in)
+        } else if zoom_in {
+            scale / ZOOM_STEP
+        } else {
+            scale * ZOOM_STEP
+        }
+    }
+}
+
+/// Returns the next camera scale from `scale` whose zoom (the inverse of the scale) is an
+/// integer N or 1/N.
+fn step_pixel_ratio(scale: f32, zoom_in: bool) -> f32 {
+    // Tolerance so that a scale already on a ratio moves to the next one
+    const EPSILON: f32 = 1e-3;
+
+    let zoom = scale.recip();
+    if zoom_in {
+        if zoom >= 1.0 - EPSILON {
+            ((zoom + EPSILON).floor() + 1.0).recip()
+        } else {
+            (scale - EPSILON).ceil() - 1.0
+        }
+    } else if zoom > 1.0 + EPSILON {
+        ((zoom - EPSILON).ceil() - 1.0).recip()
+    } else {
+        (scale + EPSILON).floor() + 1.0
+    }
+}
+
+fn toggle_pixel_ratio_snap(mut settings: ResMut<ZoomSettings>) {
+    settings.pixel_ratio_snap = !settings.pixel_ratio_snap;
+    info!(
+        "Pixel ratio zoom {}",
+        if settings.pixel_ratio_snap {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+}
+
+/// Zooms to 100%, where one image pixel covers one screen pixel.
+fn zoom_to_actual_pixels(
+    mut commands: Commands,
+    main_camera: Single<(Entity, &Transform), With<MainCamera>>,
+) {
+    let (camera_id, transform) = *main_camera;
+    let end = transform.with_scale(Vec3::new(1.0, 1.0, transform.scale.z));
+    commands
+        .entity(camera_id)
+        .insert(CameraTween::new(*transform, end));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_pixel_ratio() {
+        // A scale of 1/N is a zoom of N00%
+        assert_eq!(step_pixel_ratio(1.0, true), 0.5);
+        assert_eq!(step_pixel_ratio(0.5, true), 1.0 / 3.0);
+        assert_eq!(step_pixel_ratio(0.8, true), 0.5);
+        assert_eq!(step_pixel_ratio(2.0, true), 1.0);
+        assert_eq!(step_pixel_ratio(2.5, true), 2.0);
+
+        assert_eq!(step_pixel_ratio(0.5, false), 1.0);
+        assert_eq!(step_pixel_ratio(0.8, false), 1.0);
+        assert_eq!(step_pixel_ratio(1.0, false), 2.0);
+        assert_eq!(step_pixel_ratio(2.0, false), 3.0);
+        assert_eq!(step_pixel_ratio(2.5, false), 3.0);
+    }
+}
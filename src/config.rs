@@ -0,0 +1,84 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{bevyhow, redraw::Redraw};
+
+/// How long a [`ConfigFile`] resource has to stay unchanged before it is written, so that
+/// e.g. dragging a color in the inspector doesn't rewrite the file every frame.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Returns the path of the config file `file_name` in the app's config directory.
+/// The directory may not exist yet.
+pub fn config_path(file_name: &str) -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("neta").join(file_name))
+}
+
+/// Resource saved to a file in the config directory.
+pub trait ConfigFile: Resource + Default {
+    /// File name in the config directory.
+    const FILE_NAME: &'static str;
+
+    fn to_config(&self) -> String;
+
+    fn apply_config(&mut self, config: &str) -> Result;
+
+    /// Loads the resource from its config file, falling back to the defaults.
+    fn load() -> Self {
+        let mut resource = Self::default();
+
+        let Some(path) = config_path(Self::FILE_NAME) else {
+            return resource;
+        };
+        let Ok(config) = std::fs::read_to_string(&path) else {
+            return resource;
+        };
+        if let Err(error) = resource.apply_config(&config) {
+            warn!("Failed to load {}: {error}", path.display());
+        }
+
+        resource
+    }
+
+    fn save(&self) -> Result {
+        let path = config_path(Self::FILE_NAME).ok_or_else(|| bevyhow!("No config directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_config())?;
+        Ok(())
+    }
+}
+
+/// Saves `R` once it has stayed unchanged for [`SAVE_DELAY`], or when the app exits.
+/// Add it to [`Last`] so that the exit is seen in the frame it is requested.
+pub fn save_config<R: ConfigFile>(
+    config: Res<R>,
+    time: Res<Time<Real>>,
+    mut exit: EventReader<AppExit>,
+    mut redraw: ResMut<Redraw>,
+    mut changed_at: Local<Option<Duration>>,
+) {
+    // Loaded from the file already
+    if config.is_changed() && !config.is_added() {
+        *changed_at = Some(time.elapsed());
+    }
+    let Some(at) = *changed_at else {
+        return;
+    };
+
+    if exit.read().count() == 0 && time.elapsed() < at + SAVE_DELAY {
+        // Keep updating so that the save isn't delayed until the next input
+        redraw.request_redraw_once();
+        return;
+    }
+    *changed_at = None;
+    if let Err(error) = config.save() {
+        warn!("Failed to save {}: {error}", R::FILE_NAME);
+    }
+}
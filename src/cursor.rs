@@ -0,0 +1,95 @@
+//! Cursor icon shared by tools and interactions.
+//!
+//! Each interaction sets its icon under a [`CursorSource`] in [`Cursors`] instead of inserting
+//! [`CursorIcon`] on windows, and clears it when done. The icon of the highest priority source
+//! is shown, so e.g. ending a drag goes back to the eyedropper crosshair rather than the
+//! default cursor.
+
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon};
+
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cursors>()
+            .add_systems(PostUpdate, apply_cursor.run_if(resource_changed::<Cursors>));
+    }
+}
+
+/// What a cursor icon is set for, from the lowest priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CursorSource {
+    /// Active tool, such as the eyedropper.
+    Tool,
+    /// Pointer over a handle.
+    HandleHover,
+    /// Marquee, lasso or zoom box selection.
+    Marquee,
+    /// Dragging a frame, a handle or a thumbnail.
+    Drag,
+    /// Panning the canvas.
+    Pan,
+}
+
+/// Cursor icons requested by each [`CursorSource`].
+#[derive(Resource, Default, Debug)]
+pub struct Cursors(BTreeMap<CursorSource, SystemCursorIcon>);
+
+impl Cursors {
+    pub fn set(&mut self, source: CursorSource, icon: SystemCursorIcon) {
+        self.0.insert(source, icon);
+    }
+
+    pub fn clear(&mut self, source: CursorSource) {
+        self.0.remove(&source);
+    }
+
+    /// Icon to show, or `None` for the default cursor.
+    pub fn icon(&self) -> Option<SystemCursorIcon> {
+        self.0.last_key_value().map(|(_, icon)| *icon)
+    }
+}
+
+fn apply_cursor(
+    mut commands: Commands,
+    cursors: Res<Cursors>,
+    window: Query<Entity, With<Window>>,
+) {
+    let icon = cursors.icon();
+    for window in &window {
+        match icon {
+            Some(icon) => {
+                commands.entity(window).insert(CursorIcon::System(icon));
+            }
+            None => {
+                commands.entity(window).remove::<CursorIcon>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_priority() {
+        let mut cursors = Cursors::default();
+        assert_eq!(cursors.icon(), None);
+
+        cursors.set(CursorSource::Tool, SystemCursorIcon::Crosshair);
+        cursors.set(CursorSource::Drag, SystemCursorIcon::Grabbing);
+        cursors.set(CursorSource::HandleHover, SystemCursorIcon::Grab);
+        assert_eq!(cursors.icon(), Some(SystemCursorIcon::Grabbing));
+
+        // Back to the tool rather than the default cursor
+        cursors.clear(CursorSource::Drag);
+        cursors.clear(CursorSource::HandleHover);
+        assert_eq!(cursors.icon(), Some(SystemCursorIcon::Crosshair));
+
+        cursors.clear(CursorSource::Tool);
+        assert_eq!(cursors.icon(), None);
+    }
+}
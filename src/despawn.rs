@@ -0,0 +1,50 @@
+//! Despawning of entities that may already be gone.
+//!
+//! Frames and handles can be despawned from several places in the same frame, e.g. a context
+//! menu removal racing the removal observer of the control handle. [`SafeDespawn`] skips
+//! missing entities instead of warning or panicking.
+
+use bevy::prelude::*;
+
+pub trait SafeDespawn {
+    /// Despawns `entity` and its descendants if it still exists.
+    fn safe_despawn(&mut self, entity: Entity);
+}
+
+impl SafeDespawn for World {
+    fn safe_despawn(&mut self, entity: Entity) {
+        if let Ok(entity) = self.get_entity_mut(entity) {
+            entity.despawn();
+        }
+    }
+}
+
+impl SafeDespawn for Commands<'_, '_> {
+    fn safe_despawn(&mut self, entity: Entity) {
+        // Checked when applied, since the entity may be despawned by an earlier command
+        self.queue(move |world: &mut World| world.safe_despawn(entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_despawn_twice() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn(ChildOf(parent)).id();
+
+        let mut commands = world.commands();
+        commands.safe_despawn(parent);
+        commands.safe_despawn(parent);
+        // Despawned with its parent already
+        commands.safe_despawn(child);
+        world.flush();
+        assert!(world.get_entity(parent).is_err());
+        assert!(world.get_entity(child).is_err());
+
+        world.safe_despawn(parent);
+    }
+}
@@ -0,0 +1,92 @@
+//! Overlay showing FPS, frame time and whether the reactive loop is idle or redrawing.
+//! Only built with the `fps_overlay` feature (enabled by `dev`).
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    window::RequestRedraw,
+};
+
+use crate::{
+    key_bindings::{Action, action_just_pressed},
+    redraw,
+};
+
+pub struct FpsOverlayPlugin;
+
+impl Plugin for FpsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                toggle_overlay.run_if(action_just_pressed(Action::ToggleFpsOverlay)),
+            )
+            // Look at the redraw request of this frame
+            .add_systems(Last, update_overlay.after(redraw::send_redraw_request));
+    }
+}
+
+#[derive(Component)]
+struct FpsOverlay;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Name::new("FpsOverlay"),
+        FpsOverlay,
+        Visibility::Hidden,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            right: Val::Px(5.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        GlobalZIndex(i32::MAX),
+        Pickable::IGNORE,
+    ));
+}
+
+fn toggle_overlay(mut overlay: Single<&mut Visibility, With<FpsOverlay>>) {
+    let visibility = match **overlay {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+    **overlay = visibility;
+}
+
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    mut redraw_requests: EventReader<RequestRedraw>,
+    overlay: Single<(&mut Text, &Visibility), With<FpsOverlay>>,
+) {
+    // Read even while hidden so that old requests are not shown later
+    let redrawing = redraw_requests.read().count() > 0;
+
+    let (mut text, visibility) = overlay.into_inner();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let value = |path| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+
+    // Frames only run on input or redraw requests under `desktop_app()`, so the frame time
+    // while idle is the time since the last event
+    text.0 = format!(
+        "FPS: {:.1}\nFrame time: {:.2} ms\n{}",
+        value(&FrameTimeDiagnosticsPlugin::FPS),
+        value(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        if redrawing { "Redrawing" } else { "Idle" },
+    );
+}
@@ -1,10 +1,12 @@
-use bevy::{input::common_conditions::input_just_released, prelude::*, window::PrimaryWindow};
+use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_inspector_egui::{
     bevy_egui::{EguiContext, EguiContextPass, EguiPlugin},
     bevy_inspector::Filter,
     egui,
 };
 
+use crate::key_bindings::{Action, action_just_pressed};
+
 pub fn plugin(app: &mut App) {
     app.add_plugins(EguiPlugin {
         enable_multipass_for_primary_context: true,
@@ -13,7 +15,7 @@ pub fn plugin(app: &mut App) {
     .add_systems(
         EguiContextPass,
         (
-            add_inspector_ui.run_if(input_just_released(KeyCode::F12)),
+            add_inspector_ui.run_if(action_just_pressed(Action::Inspector)),
             inspector_ui,
         ),
     );
@@ -0,0 +1,578 @@
+use std::fmt;
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, TypeInfo, Typed, VariantInfo},
+};
+
+use crate::{bevyhow, config::config_path, modal::ModalActive};
+
+const CONFIG_FILE: &str = "key_bindings.cfg";
+
+pub struct KeyBindingsPlugin;
+
+impl Plugin for KeyBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        let key_bindings = KeyBindings::load();
+        key_bindings.warn_conflicts();
+        app.insert_resource(key_bindings)
+            .add_event::<InvokeAction>();
+    }
+}
+
+/// An action that can be bound to a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Modifier to add to (or toggle) the selection instead of replacing it.
+    AddToSelection,
+    /// Hold while dragging on the canvas to zoom into the dragged region.
+    ZoomBox,
+    /// Hold while dragging on the canvas to select frames inside a freeform path.
+    Lasso,
+    /// Modifier to snap the rotation of a frame to 15 degree steps.
+    SnapRotation,
+    /// Modifier to keep a rotated frame from matching the angles of other frames.
+    FreeAngle,
+    /// Hold when starting to drag a frame to center it on the cursor.
+    GrabCenter,
+    /// Restore the previous selection.
+    ReselectLast,
+    /// Enable or disable outline rendering for huge boards.
+    ToggleOutlineMode,
+    /// Hide every frame except the selection, or show them again.
+    ToggleIsolateMode,
+    /// Enable or disable snapping dragged frames to the grid.
+    ToggleGridSnap,
+    /// Enable or disable rounding the positions of placed frames to whole pixels.
+    TogglePixelSnap,
+    /// Undo the last edit.
+    Undo,
+    /// Redo the last undone edit.
+    Redo,
+    /// Toggle the eyedropper tool to copy the style of a frame to others.
+    Eyedropper,
+    /// Scale the selected frames up by a step.
+    ScaleUp,
+    /// Scale the selected frames down by a step.
+    ScaleDown,
+    /// Enable or disable flagging frames that overlap others.
+    ToggleOverlapCheck,
+    /// Move the view to the next group of overlapping frames.
+    NextOverlap,
+    /// Switch between continuous zoom and zoom in integer pixel ratios.
+    TogglePixelZoom,
+    /// Zoom to 100%.
+    ActualPixels,
+    /// Name the selected frames from a pattern.
+    RenameFrames,
+    /// Select the frames in the pending adjustable marquee.
+    CommitMarquee,
+    /// Discard the pending adjustable marquee.
+    CancelMarquee,
+    /// Save the layout to its file, asking for one the first time.
+    SaveLayout,
+    /// Search actions by name and run one.
+    CommandPalette,
+    /// Open a new inspector window (`dev` feature only).
+    Inspector,
+    /// Show or hide the hit areas of control handles (`dev` feature only).
+    TogglePickingAreas,
+    /// Show or hide the FPS overlay (`fps_overlay` feature only).
+    ToggleFpsOverlay,
+}
+
+impl Action {
+    pub const ALL: [Action; 28] = [
+        Action::AddToSelection,
+        Action::ZoomBox,
+        Action::Lasso,
+        Action::SnapRotation,
+        Action::FreeAngle,
+        Action::GrabCenter,
+        Action::ReselectLast,
+        Action::ToggleOutlineMode,
+        Action::ToggleIsolateMode,
+        Action::ToggleGridSnap,
+        Action::TogglePixelSnap,
+        Action::Undo,
+        Action::Redo,
+        Action::Eyedropper,
+        Action::ScaleUp,
+        Action::ScaleDown,
+        Action::ToggleOverlapCheck,
+        Action::NextOverlap,
+        Action::TogglePixelZoom,
+        Action::ActualPixels,
+        Action::RenameFrames,
+        Action::CommitMarquee,
+        Action::CancelMarquee,
+        Action::SaveLayout,
+        Action::CommandPalette,
+        Action::Inspector,
+        Action::TogglePickingAreas,
+        Action::ToggleFpsOverlay,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::AddToSelection => "Add to Selection",
+            Action::ZoomBox => "Zoom Box",
+            Action::Lasso => "Lasso",
+            Action::SnapRotation => "Snap Rotation",
+            Action::FreeAngle => "Free Angle",
+            Action::GrabCenter => "Grab Center",
+            Action::ReselectLast => "Reselect Last",
+            Action::ToggleOutlineMode => "Toggle Outline Mode",
+            Action::ToggleIsolateMode => "Toggle Isolate Mode",
+            Action::ToggleGridSnap => "Toggle Grid Snap",
+            Action::TogglePixelSnap => "Toggle Pixel Snap",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Eyedropper => "Eyedropper",
+            Action::ScaleUp => "Scale Up",
+            Action::ScaleDown => "Scale Down",
+            Action::ToggleOverlapCheck => "Toggle Overlap Check",
+            Action::NextOverlap => "Next Overlap",
+            Action::TogglePixelZoom => "Toggle Pixel Zoom",
+            Action::ActualPixels => "Actual Pixels",
+            Action::RenameFrames => "Rename Frames",
+            Action::CommitMarquee => "Commit Marquee",
+            Action::CancelMarquee => "Cancel Marquee",
+            Action::SaveLayout => "Save Layout",
+            Action::CommandPalette => "Command Palette",
+            Action::Inspector => "Inspector",
+            Action::TogglePickingAreas => "Picking Areas",
+            Action::ToggleFpsOverlay => "FPS Overlay",
+        }
+    }
+
+    /// Whether the action is held during another input, such as a modifier for dragging, rather
+    /// than run when pressed. Such actions can't be invoked with [`InvokeAction`].
+    pub fn is_held(&self) -> bool {
+        matches!(
+            self,
+            Action::AddToSelection
+                | Action::ZoomBox
+                | Action::Lasso
+                | Action::SnapRotation
+                | Action::FreeAngle
+                | Action::GrabCenter
+        )
+    }
+
+    fn default_binding(&self) -> KeyBinding {
+        match self {
+            Action::AddToSelection => KeyBinding {
+                ctrl: true,
+                ..default()
+            },
+            Action::ZoomBox => KeyBinding::key(KeyCode::KeyZ),
+            // Alt is taken by `FreeAngle`, which overrides snapping like the usual Alt-drag
+            Action::Lasso => KeyBinding::key(KeyCode::KeyL),
+            Action::SnapRotation => KeyBinding {
+                shift: true,
+                ..default()
+            },
+            Action::FreeAngle => KeyBinding {
+                alt: true,
+                ..default()
+            },
+            // Not a modifier, since any would also hold one of the actions above
+            Action::GrabCenter => KeyBinding::key(KeyCode::KeyX),
+            Action::ReselectLast => KeyBinding {
+                key: Some(KeyCode::KeyA),
+                ctrl: true,
+                shift: true,
+                alt: false,
+            },
+            Action::ToggleOutlineMode => KeyBinding::key(KeyCode::KeyO),
+            Action::ToggleIsolateMode => KeyBinding::key(KeyCode::KeyI),
+            Action::ToggleGridSnap => KeyBinding::key(KeyCode::KeyG),
+            Action::TogglePixelSnap => KeyBinding {
+                key: Some(KeyCode::KeyG),
+                ctrl: false,
+                shift: true,
+                alt: false,
+            },
+            Action::Undo => KeyBinding {
+                key: Some(KeyCode::KeyZ),
+                ctrl: true,
+                shift: false,
+                alt: false,
+            },
+            Action::Redo => KeyBinding {
+                key: Some(KeyCode::KeyZ),
+                ctrl: true,
+                shift: true,
+                alt: false,
+            },
+            Action::Eyedropper => KeyBinding::key(KeyCode::KeyE),
+            Action::ScaleUp => KeyBinding::key(KeyCode::Equal),
+            Action::ScaleDown => KeyBinding::key(KeyCode::Minus),
+            Action::ToggleOverlapCheck => KeyBinding::key(KeyCode::KeyC),
+            Action::NextOverlap => KeyBinding::key(KeyCode::KeyN),
+            Action::TogglePixelZoom => KeyBinding::key(KeyCode::KeyP),
+            Action::ActualPixels => KeyBinding::key(KeyCode::Digit1),
+            Action::RenameFrames => KeyBinding::key(KeyCode::F2),
+            Action::CommitMarquee => KeyBinding::key(KeyCode::Enter),
+            Action::CancelMarquee => KeyBinding::key(KeyCode::Escape),
+            Action::SaveLayout => KeyBinding {
+                key: Some(KeyCode::KeyS),
+                ctrl: true,
+                shift: false,
+                alt: false,
+            },
+            Action::CommandPalette => KeyBinding {
+                key: Some(KeyCode::KeyP),
+                ctrl: true,
+                shift: false,
+                alt: false,
+            },
+            Action::Inspector => KeyBinding::key(KeyCode::F12),
+            Action::TogglePickingAreas => KeyBinding::key(KeyCode::F4),
+            Action::ToggleFpsOverlay => KeyBinding::key(KeyCode::F3),
+        }
+    }
+}
+
+/// A key with modifiers. A binding without `key` is held by its modifiers alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyBinding {
+    pub key: Option<KeyCode>,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn key(key: KeyCode) -> Self {
+        Self {
+            key: Some(key),
+            ..default()
+        }
+    }
+
+    pub fn is_unbound(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns `true` if the key and at least the required modifiers are held.
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        if self.is_unbound() {
+            return false;
+        }
+        let (ctrl, shift, alt) = modifiers(input);
+        self.key.is_none_or(|key| input.pressed(key))
+            && (!self.ctrl || ctrl)
+            && (!self.shift || shift)
+            && (!self.alt || alt)
+    }
+
+    /// Returns `true` if the key has just been pressed with exactly the bound modifiers.
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        let Some(key) = self.key else {
+            return false;
+        };
+        input.just_pressed(key) && modifiers(input) == (self.ctrl, self.shift, self.alt)
+    }
+
+    fn parse(s: &str) -> Result<Self> {
+        let mut binding = KeyBinding::default();
+        for part in s.split('+').map(str::trim).filter(|part| !part.is_empty()) {
+            match part {
+                "Ctrl" => binding.ctrl = true,
+                "Shift" => binding.shift = true,
+                "Alt" => binding.alt = true,
+                "-" => {}
+                key => binding.key = Some(parse_key_code(key)?),
+            }
+        }
+        Ok(binding)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_unbound() {
+            return write!(f, "-");
+        }
+
+        let mut parts = vec![];
+        if self.ctrl {
+            parts.push("Ctrl".to_string());
+        }
+        if self.shift {
+            parts.push("Shift".to_string());
+        }
+        if self.alt {
+            parts.push("Alt".to_string());
+        }
+        if let Some(key) = self.key {
+            parts.push(format!("{key:?}"));
+        }
+        write!(f, "{}", parts.join("+"))
+    }
+}
+
+/// Parses a unit variant name of [`KeyCode`] (e.g. `KeyA`).
+fn parse_key_code(name: &str) -> Result<KeyCode> {
+    let TypeInfo::Enum(enum_info) = KeyCode::type_info() else {
+        unreachable!();
+    };
+    // `from_reflect` panics on unknown variants
+    if !matches!(enum_info.variant(name), Some(VariantInfo::Unit(_))) {
+        return Err(bevyhow!("Unknown key: {name}"));
+    }
+
+    KeyCode::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+        .ok_or_else(|| bevyhow!("Unknown key: {name}"))
+}
+
+/// Returns whether (Ctrl, Shift, Alt) are held.
+pub fn modifiers(input: &ButtonInput<KeyCode>) -> (bool, bool, bool) {
+    (
+        input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+        input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+        input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+    )
+}
+
+pub fn is_modifier(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::ControlLeft
+            | KeyCode::ControlRight
+            | KeyCode::ShiftLeft
+            | KeyCode::ShiftRight
+            | KeyCode::AltLeft
+            | KeyCode::AltRight
+            | KeyCode::SuperLeft
+            | KeyCode::SuperRight
+    )
+}
+
+/// Key bindings of all [`Action`]s. Input systems should query this instead of hardcoding keys.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct KeyBindings(HashMap<Action, KeyBinding>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        )
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> KeyBinding {
+        self.0.get(&action).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, action: Action, binding: KeyBinding) {
+        self.0.insert(action, binding);
+    }
+
+    pub fn pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        self.get(action).pressed(input)
+    }
+
+    pub fn just_pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        self.get(action).just_pressed(input)
+    }
+
+    /// Returns pairs of actions bound to the same keys.
+    pub fn conflicts(&self) -> Vec<(Action, Action)> {
+        let mut conflicts = vec![];
+        for (i, a) in Action::ALL.iter().enumerate() {
+            for b in &Action::ALL[i + 1..] {
+                let binding = self.get(*a);
+                if !binding.is_unbound() && binding == self.get(*b) {
+                    conflicts.push((*a, *b));
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn warn_conflicts(&self) {
+        for (a, b) in self.conflicts() {
+            warn!(
+                "Conflicting key bindings: {:?} and {:?} are both bound to {}",
+                a,
+                b,
+                self.get(a)
+            );
+        }
+    }
+
+    /// Loads key bindings from the config file, falling back to the defaults.
+    pub fn load() -> Self {
+        let mut key_bindings = Self::default();
+
+        let Some(path) = config_path(CONFIG_FILE) else {
+            return key_bindings;
+        };
+        let Ok(config) = std::fs::read_to_string(&path) else {
+            return key_bindings;
+        };
+        if let Err(error) = key_bindings.apply_config(&config) {
+            warn!("Failed to load {}: {error}", path.display());
+        }
+
+        key_bindings
+    }
+
+    pub fn save(&self) -> Result {
+        let path = config_path(CONFIG_FILE).ok_or_else(|| bevyhow!("No config directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_config())?;
+        Ok(())
+    }
+
+    fn to_config(&self) -> String {
+        Action::ALL
+            .iter()
+            .map(|action| format!("{action:?} = {}\n", self.get(*action)))
+            .collect()
+    }
+
+    fn apply_config(&mut self, config: &str) -> Result {
+        for line in config.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, binding) = line
+                .split_once('=')
+                .ok_or_else(|| bevyhow!("Invalid line: {line}"))?;
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| format!("{action:?}") == name.trim())
+                .ok_or_else(|| bevyhow!("Unknown action: {}", name.trim()))?;
+            self.set(action, KeyBinding::parse(binding)?);
+        }
+        Ok(())
+    }
+}
+
+/// Runs an action as if its key had just been pressed, e.g. from the command palette.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct InvokeAction(pub Action);
+
+/// Run condition that is `true` when the key bound to `action` has just been pressed or the
+/// action was invoked with [`InvokeAction`]. Keys and invocations are both ignored while a modal
+/// dialog is open, so a dialog that invokes an action must close first.
+pub fn action_just_pressed(
+    action: Action,
+) -> impl FnMut(
+    Res<KeyBindings>,
+    Res<ButtonInput<KeyCode>>,
+    Option<Res<ModalActive>>,
+    EventReader<InvokeAction>,
+) -> bool
++ Clone {
+    move |key_bindings, input, modal, mut invoked| {
+        if modal.is_some() {
+            invoked.clear();
+            return false;
+        }
+        // Read all events so that none is left for the next frame
+        let invoked = invoked.read().filter(|event| event.0 == action).count() > 0;
+        invoked || key_bindings.just_pressed(action, &input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.set(
+            Action::ZoomBox,
+            KeyBinding {
+                key: Some(KeyCode::KeyX),
+                shift: true,
+                ..default()
+            },
+        );
+
+        let config = key_bindings.to_config();
+        assert!(config.contains("ZoomBox = Shift+KeyX"));
+        assert!(config.contains("AddToSelection = Ctrl"));
+
+        let mut loaded = KeyBindings::default();
+        loaded.apply_config(&config).unwrap();
+        assert_eq!(loaded, key_bindings);
+
+        assert!(loaded.apply_config("ZoomBox = NotAKey").is_err());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut key_bindings = KeyBindings::default();
+        assert!(key_bindings.conflicts().is_empty());
+
+        key_bindings.set(Action::Inspector, KeyBinding::key(KeyCode::KeyZ));
+        assert_eq!(
+            key_bindings.conflicts(),
+            vec![(Action::ZoomBox, Action::Inspector)]
+        );
+    }
+
+    #[test]
+    fn test_held_actions_independent() {
+        let key_bindings = KeyBindings::default();
+        let held = Action::ALL.into_iter().filter(Action::is_held);
+        for action in held.clone() {
+            let binding = key_bindings.get(action);
+            let mut input = ButtonInput::<KeyCode>::default();
+            for (required, key) in [
+                (binding.ctrl, KeyCode::ControlLeft),
+                (binding.shift, KeyCode::ShiftLeft),
+                (binding.alt, KeyCode::AltLeft),
+            ] {
+                if required {
+                    input.press(key);
+                }
+            }
+            if let Some(key) = binding.key {
+                input.press(key);
+            }
+
+            for other in held.clone() {
+                assert_eq!(
+                    key_bindings.pressed(other, &input),
+                    other == action,
+                    "{other:?} while holding {action:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invoke_action() {
+        let mut world = World::new();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<Events<InvokeAction>>();
+        let mut condition = IntoSystem::into_system(action_just_pressed(Action::Undo));
+        condition.initialize(&mut world);
+
+        world.send_event(InvokeAction(Action::Redo));
+        assert!(!condition.run((), &mut world));
+        world.send_event(InvokeAction(Action::Undo));
+        assert!(condition.run((), &mut world));
+        assert!(!condition.run((), &mut world));
+
+        // Dropped rather than run once the dialog closes
+        world.insert_resource(ModalActive::default());
+        world.send_event(InvokeAction(Action::Undo));
+        assert!(!condition.run((), &mut world));
+        world.remove_resource::<ModalActive>();
+        assert!(!condition.run((), &mut world));
+    }
+}
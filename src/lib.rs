@@ -0,0 +1,3 @@
+//! Parts of neta that don't depend on the app, for reuse by other tools.
+
+pub mod packing;
@@ -7,13 +7,23 @@ use bevy::{
     winit::WinitSettings,
 };
 
+use neta::packing;
+
+mod browse;
 mod canvas;
+mod config;
+mod cursor;
 mod debug_gizmo;
+mod despawn;
 mod error;
+#[cfg(feature = "fps_overlay")]
+mod fps_overlay;
 #[cfg(feature = "dev")]
 mod inspector;
+mod key_bindings;
+mod modal;
 mod observe_component;
-mod packing;
+mod redraw;
 mod sprite_picking;
 mod ui;
 mod viewport_delta;
@@ -28,6 +38,8 @@ fn main() {
                         present_mode: PresentMode::AutoNoVsync,
                         ..default()
                     }),
+                    // Closing is handled by `ui` to ask for unsaved changes
+                    close_when_requested: false,
                     ..default()
                 })
                 .set(AssetPlugin {
@@ -37,7 +49,11 @@ fn main() {
                 .disable::<PipelinedRenderingPlugin>(),
         )
         // replace with fixed version (https://github.com/bevyengine/bevy/pull/18069)
-        .add_plugins(sprite_picking::SpritePickingPlugin);
+        .add_plugins(sprite_picking::SpritePickingPlugin)
+        .add_plugins(key_bindings::KeyBindingsPlugin)
+        .add_plugins(redraw::RedrawPlugin)
+        .add_plugins(cursor::CursorPlugin)
+        .add_plugins(modal::ModalPlugin);
 
     #[cfg(feature = "dev")]
     app.add_plugins(DebugPickingPlugin)
@@ -45,7 +61,10 @@ fn main() {
         .add_plugins(inspector::plugin)
         .add_plugins(debug_gizmo::DebugGizmoPlugin);
 
-    app.add_plugins((canvas::CanvasPlugin, ui::UiPlugin));
+    #[cfg(feature = "fps_overlay")]
+    app.add_plugins(fps_overlay::FpsOverlayPlugin);
+
+    app.add_plugins((canvas::CanvasPlugin, ui::UiPlugin, browse::BrowsePlugin));
 
     app.run();
 }
@@ -0,0 +1,128 @@
+//! Blocking of canvas input while a modal dialog is open.
+//!
+//! In-app modals open and close [`ModalActive`] with their own [`ModalSource`], so that closing
+//! one of several open modals doesn't unblock input. Native dialogs such as `rfd::FileDialog`
+//! block the app, and the input they receive is delivered once they close, so
+//! [`block_native_dialog`] keeps input blocked for one more frame.
+
+use bevy::{diagnostic::FrameCount, platform::collections::HashSet, prelude::*};
+
+use crate::redraw::Redraw;
+
+pub struct ModalPlugin;
+
+impl Plugin for ModalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, release_modal.run_if(resource_exists::<ModalActive>));
+    }
+}
+
+/// Present while a modal dialog is open. Picking backends, canvas click and drag observers and
+/// key bindings ignore input meanwhile. The default value blocks until removed.
+#[derive(Resource, Debug, Default)]
+pub struct ModalActive {
+    /// In-app modals that are open.
+    sources: HashSet<ModalSource>,
+    /// Frame after which a blocking dialog no longer blocks input.
+    release_after: Option<u32>,
+}
+
+/// In-app modal holding [`ModalActive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModalSource {
+    Dialog,
+    CommandPalette,
+    ExitPrompt,
+}
+
+/// Blocks input until [`close_modal`] is applied with the same `source`.
+pub fn open_modal(source: ModalSource) -> impl Command {
+    move |world: &mut World| {
+        world
+            .get_resource_or_init::<ModalActive>()
+            .sources
+            .insert(source);
+    }
+}
+
+/// Stops blocking input for `source`. Input stays blocked while other modals are open.
+pub fn close_modal(source: ModalSource) -> impl Command {
+    move |world: &mut World| {
+        let Some(mut modal) = world.get_resource_mut::<ModalActive>() else {
+            return;
+        };
+        modal.sources.remove(&source);
+        if modal.sources.is_empty() && modal.release_after.is_none() {
+            world.remove_resource::<ModalActive>();
+        }
+    }
+}
+
+/// Blocks input for a blocking dialog that was open during `frame`. Input queued while it was
+/// open is delivered in the next frame, so it is released after that frame.
+pub fn block_native_dialog(frame: &FrameCount) -> impl Command {
+    let release_after = frame.0.wrapping_add(1);
+    move |world: &mut World| {
+        world.get_resource_or_init::<ModalActive>().release_after = Some(release_after);
+    }
+}
+
+fn release_modal(
+    mut commands: Commands,
+    mut modal: ResMut<ModalActive>,
+    frame: Res<FrameCount>,
+    mut redraw: ResMut<Redraw>,
+) {
+    let Some(release_after) = modal.release_after else {
+        return;
+    };
+    if frame.0 <= release_after {
+        // Run the frame receiving the queued input even if nothing else happens
+        redraw.request_redraw_once();
+    } else if modal.sources.is_empty() {
+        commands.remove_resource::<ModalActive>();
+    } else {
+        modal.release_after = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_modals() {
+        let mut world = World::new();
+        world.init_resource::<FrameCount>();
+        world.init_resource::<Redraw>();
+
+        open_modal(ModalSource::Dialog).apply(&mut world);
+        open_modal(ModalSource::CommandPalette).apply(&mut world);
+        // Closing one keeps input blocked for the other
+        close_modal(ModalSource::Dialog).apply(&mut world);
+        assert!(world.contains_resource::<ModalActive>());
+        close_modal(ModalSource::CommandPalette).apply(&mut world);
+        assert!(!world.contains_resource::<ModalActive>());
+
+        // A native dialog opened from a modal is released after the next frame, while the
+        // modal still blocks
+        open_modal(ModalSource::ExitPrompt).apply(&mut world);
+        block_native_dialog(world.resource::<FrameCount>()).apply(&mut world);
+        close_modal(ModalSource::ExitPrompt).apply(&mut world);
+        for frame in 0..3 {
+            assert!(world.contains_resource::<ModalActive>(), "frame {frame}");
+            world.run_system_cached(release_modal).unwrap();
+            world.resource_mut::<FrameCount>().0 += 1;
+        }
+        assert!(!world.contains_resource::<ModalActive>());
+
+        // A modal still open when a native dialog is released keeps blocking
+        open_modal(ModalSource::Dialog).apply(&mut world);
+        block_native_dialog(world.resource::<FrameCount>()).apply(&mut world);
+        world.resource_mut::<FrameCount>().0 += 2;
+        world.run_system_cached(release_modal).unwrap();
+        assert!(world.contains_resource::<ModalActive>());
+        close_modal(ModalSource::Dialog).apply(&mut world);
+        assert!(!world.contains_resource::<ModalActive>());
+    }
+}
@@ -1,13 +1,24 @@
+//! Placement of convex polygons next to each other without overlapping, used to organize frames
+//! and to find free spots for new ones.
+//!
+//! Shapes are [`EdgeVectors`] placed at a [`ShapePosition`]. [`fill`] moves a shape to the
+//! nearest position around the placed shapes using their no-fit polygons, the
+//! [`minkowski_sum`]s of each placed shape and the shape to place.
+//!
+//! All polygons are expected to be convex and in counter-clockwise order.
+
 use bevy::{
     math::{Mat2, Vec2},
     prelude::Deref,
 };
 
-/// A polygon represented by its edge vectors (CCW order).
+/// A convex polygon represented by its edge vectors in counter-clockwise order. The edges of a
+/// closed polygon sum to zero.
 #[derive(Deref, Clone, Debug)]
 pub struct EdgeVectors(Vec<Vec2>);
 
 impl EdgeVectors {
+    /// Rectangle of `size` rotated counter-clockwise by `rotation` radians.
     pub fn with_rect_size_rotation(size: Vec2, rotation: f32) -> Self {
         let rotation_matrix = Mat2::from_angle(rotation);
 
@@ -22,7 +33,8 @@ impl EdgeVectors {
         EdgeVectors(points)
     }
 
-    /// Construct from a list of vertices.
+    /// Construct from a list of at least three vertices of a convex polygon in counter-clockwise
+    /// order.
     pub fn from_vertices(vertices: &[Vec2]) -> Self {
         assert!(vertices.len() >= 3);
 
@@ -56,8 +68,13 @@ impl EdgeVectors {
     }
 }
 
-/// Compute the Minkowski sum of two polygons.
-fn minkowski_sum(a: &EdgeVectors, b: &EdgeVectors) -> EdgeVectors {
+/// Compute the Minkowski sum of two convex polygons in counter-clockwise order. The result is
+/// convex and counter-clockwise too, starting from its bottom-left most vertex.
+///
+/// The sum of a placed shape and the point-mirrored shape to place is their no-fit polygon.
+/// [`fill`] sums the shapes as they are, so it expects centrally symmetric shapes such as
+/// rectangles.
+pub fn minkowski_sum(a: &EdgeVectors, b: &EdgeVectors) -> EdgeVectors {
     // https://cp-algorithms.com/geometry/minkowski.html
 
     // Get the index of bottom-left most point
@@ -107,6 +124,7 @@ fn minkowski_sum(a: &EdgeVectors, b: &EdgeVectors) -> EdgeVectors {
     EdgeVectors::from_vertices(&result)
 }
 
+/// A polygon placed with its [`calculate_centroid`] at `translation`.
 #[derive(Clone, Debug)]
 pub struct ShapePosition {
     pub translation: Vec2,
@@ -114,6 +132,7 @@ pub struct ShapePosition {
 }
 
 impl ShapePosition {
+    /// Vertices of the placed polygon in counter-clockwise order.
     pub fn vertices(&self) -> Vec<Vec2> {
         // local vertices
         let mut vertices = self.edges.local_vertices().collect::<Vec<_>>();
@@ -146,47 +165,45 @@ impl ShapePosition {
         self.translation -= calculate_centroid(&new_vertices) - calculate_centroid(&vertices);
     }
 
-    fn is_overlapping(&self, other: &ShapePosition) -> bool {
-        // Check overlap using the Separating Axis Theorem (SAT)
-
-        let normals = (self.edges.0.iter().map(|v| v.perp()))
-            .chain(other.edges.0.iter().map(|v| v.perp()))
-            .collect::<Vec<_>>();
-
-        for normal in normals {
-            let mut min_a = f32::MAX;
-            let mut max_a = f32::MIN;
-
-            for vertex in self.vertices() {
-                let projection = vertex.dot(normal);
-                min_a = min_a.min(projection);
-                max_a = max_a.max(projection);
-            }
-
-            let mut min_b = f32::MAX;
-            let mut max_b = f32::MIN;
-
-            for vertex in other.vertices() {
-                let projection = vertex.dot(normal);
-                min_b = min_b.min(projection);
-                max_b = max_b.max(projection);
-            }
+    /// Whether two convex shapes overlap, by the separating axis theorem. Shapes touching at an
+    /// edge count as overlapping.
+    pub fn is_overlapping(&self, other: &ShapePosition) -> bool {
+        self.separation(other) <= 0.0
+    }
 
-            if max_a < min_b || max_b < min_a {
-                return false;
-            }
-        }
+    /// Largest gap between the projections of two convex shapes onto the axes of the separating
+    /// axis theorem. Positive if they are apart, zero if they touch, and minus the depth of the
+    /// overlap if they overlap.
+    pub fn separation(&self, other: &ShapePosition) -> f32 {
+        let normals = (self.edges.0.iter().map(|v| v.perp().normalize_or_zero()))
+            .chain(other.edges.0.iter().map(|v| v.perp().normalize_or_zero()));
+        let vertices_a = self.vertices();
+        let vertices_b = other.vertices();
+        let project = |vertices: &[Vec2], normal: Vec2| {
+            vertices
+                .iter()
+                .map(|vertex| vertex.dot(normal))
+                .fold((f32::MAX, f32::MIN), |(min, max), p| (min.min(p), max.max(p)))
+        };
 
-        true
+        normals
+            .map(|normal| {
+                let (min_a, max_a) = project(&vertices_a, normal);
+                let (min_b, max_b) = project(&vertices_b, normal);
+                (min_b - max_a).max(min_a - max_b)
+            })
+            .fold(f32::MIN, f32::max)
     }
 }
 
+/// Returns `shape_to_place` moved to the nearest position not overlapping `placed_shapes` with a
+/// gap of `offset`, or `None` if there is no candidate position (e.g. nothing is placed).
 pub fn fill<'a>(
     placed_shapes: impl IntoIterator<Item = &'a ShapePosition> + Clone,
     shape_to_place: &ShapePosition,
     offset: f32,
     div: Option<u32>,
-) -> ShapePosition {
+) -> Option<ShapePosition> {
     let mut candidates = vec![];
 
     for placed in placed_shapes.clone() {
@@ -250,10 +267,12 @@ pub fn fill<'a>(
             .expect("NaN")
     });
 
-    candidates.swap_remove(0)
+    (!candidates.is_empty()).then(|| candidates.swap_remove(0))
 }
 
-fn calculate_centroid(vertices: &[Vec2]) -> Vec2 {
+/// Average of `vertices`, which must not be empty. This is the center of a rectangle or another
+/// centrally symmetric polygon, but not the area centroid of polygons in general.
+pub fn calculate_centroid(vertices: &[Vec2]) -> Vec2 {
     let mut centroid = Vec2::ZERO;
     for vertex in vertices {
         centroid += *vertex;
@@ -285,6 +304,17 @@ mod tests {
         assert!(a.is_overlapping(&b));
         assert!(!a.is_overlapping(&c));
         assert!(b.is_overlapping(&c));
+
+        assert_eq!(a.separation(&b), -2.0);
+        assert_eq!(a.separation(&c), 1.5);
+
+        // Touching at an edge
+        let d = ShapePosition {
+            translation: Vec2::new(4.0, 0.0),
+            edges: EdgeVectors::with_rect_size_rotation(Vec2::new(4.0, 4.0), 0.0),
+        };
+        assert!(a.is_overlapping(&d));
+        assert!(a.separation(&d).abs() < 1e-5);
     }
 
     #[test]
@@ -328,7 +358,9 @@ mod tests {
             edges: EdgeVectors::with_rect_size_rotation(Vec2::new(2.0, 2.0), 0.0),
         };
 
-        let result = fill(&placed_shapes, &shape_to_place, 0.1, Some(2));
+        assert!(fill(std::iter::empty(), &shape_to_place, 0.1, Some(2)).is_none());
+
+        let result = fill(&placed_shapes, &shape_to_place, 0.1, Some(2)).unwrap();
 
         // Ensure the result is not overlapping with the placed shape
         for placed in &placed_shapes {
@@ -353,7 +385,7 @@ mod tests {
             edges: EdgeVectors::with_rect_size_rotation(Vec2::new(10.0, 10.0), 0.0),
         };
 
-        let result2 = fill(&placed_shapes, &shape_to_place2, 0.1, Some(2));
+        let result2 = fill(&placed_shapes, &shape_to_place2, 0.1, Some(2)).unwrap();
 
         for placed in &placed_shapes {
             assert!(!result2.is_overlapping(placed));
@@ -0,0 +1,71 @@
+//! Coalesced redraw requests for the reactive `WinitSettings::desktop_app()` loop.
+//!
+//! Systems call [`Redraw::request_redraw_once`] instead of sending [`RequestRedraw`] directly.
+//! Requests made during a frame are merged into a single [`RequestRedraw`] sent in [`Last`],
+//! so the app goes back to idle as soon as nothing asks for another frame.
+//!
+//! Animations that legitimately need continuous redraws (e.g. camera tweens) call it every
+//! frame while they run. That still yields exactly one redraw per frame, and redrawing stops
+//! on the frame after they finish.
+
+use bevy::{prelude::*, window::RequestRedraw};
+
+pub struct RedrawPlugin;
+
+impl Plugin for RedrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Redraw>()
+            .add_systems(Last, send_redraw_request);
+    }
+}
+
+/// Flag for a redraw requested in the current frame.
+#[derive(Resource, Default)]
+pub struct Redraw {
+    requested: bool,
+}
+
+impl Redraw {
+    /// Requests another frame. Multiple requests in a frame result in a single redraw.
+    pub fn request_redraw_once(&mut self) {
+        self.requested = true;
+    }
+}
+
+pub fn send_redraw_request(mut redraw: ResMut<Redraw>, mut writer: EventWriter<RequestRedraw>) {
+    // Avoid triggering change detection on idle frames
+    if redraw.requested {
+        redraw.requested = false;
+        writer.write(RequestRedraw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_redraw_once() {
+        let mut app = App::new();
+        app.add_event::<RequestRedraw>().add_plugins(RedrawPlugin);
+
+        let count = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Events<RequestRedraw>>()
+                .drain()
+                .count()
+        };
+
+        app.world_mut()
+            .resource_mut::<Redraw>()
+            .request_redraw_once();
+        app.world_mut()
+            .resource_mut::<Redraw>()
+            .request_redraw_once();
+        app.update();
+        assert_eq!(count(&mut app), 1);
+
+        app.update();
+        assert_eq!(count(&mut app), 0);
+    }
+}
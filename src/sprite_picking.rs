@@ -21,6 +21,8 @@ use bevy::sprite::Sprite;
 use bevy::transform::prelude::*;
 use bevy::window::PrimaryWindow;
 
+use crate::modal::ModalActive;
+
 /// An optional component that marks cameras that should be used in the [`SpritePickingPlugin`].
 ///
 /// Only needed if [`SpritePickingSettings::require_markers`] is set to `true`, and ignored
@@ -29,6 +31,11 @@ use bevy::window::PrimaryWindow;
 #[reflect(Debug, Default, Component, Clone)]
 pub struct SpritePickingCamera;
 
+/// A marker component to keep a sprite pickable while it is hidden, e.g. when it is drawn by other
+/// means.
+#[derive(Debug, Clone, Default, Component)]
+pub struct PickHidden;
+
 /// How should the [`SpritePickingPlugin`] handle picking and how should it handle transparent pixels
 #[derive(Debug, Clone, Copy, Reflect)]
 #[reflect(Debug, Clone)]
@@ -76,7 +83,12 @@ impl Plugin for SpritePickingPlugin {
             .register_type::<SpritePickingCamera>()
             .register_type::<SpritePickingMode>()
             .register_type::<SpritePickingSettings>()
-            .add_systems(PreUpdate, sprite_picking.in_set(PickSet::Backend));
+            .add_systems(
+                PreUpdate,
+                sprite_picking
+                    .in_set(PickSet::Backend)
+                    .run_if(not(resource_exists::<ModalActive>)),
+            );
     }
 }
 
@@ -100,19 +112,22 @@ fn sprite_picking(
         &GlobalTransform,
         &Pickable,
         &ViewVisibility,
+        Has<PickHidden>,
         Option<&RenderLayers>,
     )>,
     mut output: EventWriter<PointerHits>,
 ) {
     let mut sorted_sprites: Vec<_> = sprite_query
         .iter()
-        .filter_map(|(entity, sprite, transform, pickable, vis, rl)| {
-            if !transform.affine().is_nan() && vis.get() {
-                Some((entity, sprite, transform, pickable, rl))
-            } else {
-                None
-            }
-        })
+        .filter_map(
+            |(entity, sprite, transform, pickable, vis, pick_hidden, rl)| {
+                if !transform.affine().is_nan() && (vis.get() || pick_hidden) {
+                    Some((entity, sprite, transform, pickable, rl))
+                } else {
+                    None
+                }
+            },
+        )
         .collect();
 
     // radsort is a stable radix sort that performed better than `slice::sort_by_key`
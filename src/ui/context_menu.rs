@@ -0,0 +1,958 @@
+//! Context menu opened by right-clicking a frame or the canvas.
+
+use bevy::{
+    diagnostic::FrameCount, platform::collections::HashMap, prelude::*, window::PrimaryWindow,
+};
+
+use crate::{
+    browse,
+    canvas::{
+        Hovered, ImageFrame, Selected, SelectionOrder, arrange, export, grid_snap, import, layout,
+        organize_canvas, pan::Panning, pin, sampling, selection_history, style, z_order,
+    },
+    despawn::SafeDespawn,
+    key_bindings::Action,
+    modal,
+    observe_component::Observe,
+    redraw::Redraw,
+};
+
+use super::{
+    dialog::{self, open_dialog},
+    key_bindings_panel::show_key_bindings_panel,
+    widget::{PanelBackground, button},
+};
+
+pub struct ContextMenuPlugin;
+
+impl Plugin for ContextMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ContextMenuSettings>()
+            .register_type::<ContextMenuSettings>()
+            .add_systems(Update, (place_context_menu, place_submenus))
+            .add_systems(
+                Update,
+                update_auto_save_label.run_if(resource_changed::<layout::AutoSave>),
+            )
+            .add_systems(
+                Update,
+                update_pixel_snap_label.run_if(resource_changed::<grid_snap::PixelSnap>),
+            )
+            .add_observer(on_click);
+    }
+}
+
+#[derive(Component, Default)]
+struct ContextMenu {
+    target_frames: Vec<Entity>,
+    /// Cursor position the menu was opened at.
+    anchor: Vec2,
+    /// Submenu shown next to its item.
+    open_submenu: Option<Submenu>,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ContextMenuSettings {
+    pub anchor: MenuAnchor,
+}
+
+impl Default for ContextMenuSettings {
+    fn default() -> Self {
+        Self {
+            anchor: MenuAnchor::NearestCorner,
+        }
+    }
+}
+
+/// How a menu is placed relative to the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum MenuAnchor {
+    /// Top-left corner at the cursor.
+    TopLeft,
+    /// The corner nearest to the cursor, expanding away from window edges.
+    NearestCorner,
+}
+
+/// Command of a context menu item. The command palette lists the ones without an [`Action`]
+/// as well, so both stay in sync with this enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MenuCommand {
+    AddImages,
+    Browse,
+    KeyBindings,
+    Remove,
+    ToFront,
+    ToBack,
+    SetZ,
+    Reselect,
+    Style,
+    AutoSave,
+    AddFolder,
+    SetSize,
+    Size50,
+    Size100,
+    Size200,
+    Pin,
+    ExportFrames,
+    PixelSnap,
+    Organize,
+    StackRow,
+    StackColumn,
+    Grid,
+    UniformGrid,
+    UniformGridTop,
+    MirrorHorizontal,
+    MirrorVertical,
+    SampleMode,
+    Rename,
+    Rotate,
+}
+
+/// Submenu grouping [`MenuCommand`]s, opened by hovering or clicking its item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Submenu {
+    Order,
+    Size,
+    Arrange,
+}
+
+impl Submenu {
+    fn label(&self) -> &'static str {
+        match self {
+            Submenu::Order => "Order >",
+            Submenu::Size => "Size >",
+            Submenu::Arrange => "Arrange >",
+        }
+    }
+}
+
+/// Where a [`MenuCommand`] is shown in the context menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MenuScope {
+    /// Only on the canvas background.
+    Canvas,
+    /// Only on a frame. The command palette runs it on the selection only.
+    Frame,
+    /// On both, applying to all frames on the canvas.
+    Both,
+}
+
+impl MenuCommand {
+    /// Commands in the order of the context menu.
+    pub(super) const ALL: [MenuCommand; 29] = [
+        MenuCommand::AddImages,
+        MenuCommand::Browse,
+        MenuCommand::KeyBindings,
+        MenuCommand::Remove,
+        MenuCommand::ToFront,
+        MenuCommand::ToBack,
+        MenuCommand::SetZ,
+        MenuCommand::Reselect,
+        MenuCommand::Style,
+        MenuCommand::AutoSave,
+        MenuCommand::AddFolder,
+        MenuCommand::SetSize,
+        MenuCommand::Size50,
+        MenuCommand::Size100,
+        MenuCommand::Size200,
+        MenuCommand::Pin,
+        MenuCommand::ExportFrames,
+        MenuCommand::PixelSnap,
+        MenuCommand::Organize,
+        MenuCommand::StackRow,
+        MenuCommand::StackColumn,
+        MenuCommand::Grid,
+        MenuCommand::UniformGrid,
+        MenuCommand::UniformGridTop,
+        MenuCommand::MirrorHorizontal,
+        MenuCommand::MirrorVertical,
+        MenuCommand::SampleMode,
+        MenuCommand::Rename,
+        MenuCommand::Rotate,
+    ];
+
+    pub(super) fn label(&self) -> &'static str {
+        match self {
+            MenuCommand::AddImages => "Add Images",
+            MenuCommand::Browse => "Browse",
+            MenuCommand::KeyBindings => "Key Bindings",
+            MenuCommand::Remove => "Remove",
+            MenuCommand::ToFront => "To Front",
+            MenuCommand::ToBack => "To Back",
+            MenuCommand::SetZ => "Set Z",
+            MenuCommand::Reselect => "Reselect",
+            MenuCommand::Style => "Style",
+            MenuCommand::AutoSave => "Auto-Save",
+            MenuCommand::AddFolder => "Add Folder",
+            MenuCommand::SetSize => "Set Size",
+            MenuCommand::Size50 => "Size 50%",
+            MenuCommand::Size100 => "Size 100%",
+            MenuCommand::Size200 => "Size 200%",
+            MenuCommand::Pin => "Pin/Unpin",
+            MenuCommand::ExportFrames => "Export Frames",
+            MenuCommand::PixelSnap => "Pixel Snap",
+            MenuCommand::Organize => "Organize",
+            MenuCommand::StackRow => "Stack Row",
+            MenuCommand::StackColumn => "Stack Column",
+            MenuCommand::Grid => "Grid",
+            MenuCommand::UniformGrid => "Uniform Grid",
+            MenuCommand::UniformGridTop => "Uniform Top",
+            MenuCommand::MirrorHorizontal => "Mirror H",
+            MenuCommand::MirrorVertical => "Mirror V",
+            MenuCommand::SampleMode => "Pixel/Smooth",
+            MenuCommand::Rename => "Rename",
+            MenuCommand::Rotate => "Rotate",
+        }
+    }
+
+    pub(super) fn scope(&self) -> MenuScope {
+        match self {
+            MenuCommand::AddImages
+            | MenuCommand::Browse
+            | MenuCommand::KeyBindings
+            | MenuCommand::AutoSave
+            | MenuCommand::AddFolder
+            | MenuCommand::PixelSnap => MenuScope::Canvas,
+            MenuCommand::Remove
+            | MenuCommand::ToFront
+            | MenuCommand::ToBack
+            | MenuCommand::SetZ
+            | MenuCommand::Style
+            | MenuCommand::Size50
+            | MenuCommand::Size100
+            | MenuCommand::Size200
+            | MenuCommand::Pin
+            | MenuCommand::SampleMode
+            | MenuCommand::Rename
+            | MenuCommand::Rotate => MenuScope::Frame,
+            MenuCommand::Reselect
+            | MenuCommand::SetSize
+            | MenuCommand::ExportFrames
+            | MenuCommand::Organize
+            | MenuCommand::StackRow
+            | MenuCommand::StackColumn
+            | MenuCommand::Grid
+            | MenuCommand::UniformGrid
+            | MenuCommand::UniformGridTop
+            | MenuCommand::MirrorHorizontal
+            | MenuCommand::MirrorVertical => MenuScope::Both,
+        }
+    }
+
+    /// Submenu listing the command, or `None` for an item of the context menu itself.
+    pub(super) fn submenu(&self) -> Option<Submenu> {
+        match self {
+            MenuCommand::ToFront | MenuCommand::ToBack | MenuCommand::SetZ => Some(Submenu::Order),
+            MenuCommand::SetSize
+            | MenuCommand::Size50
+            | MenuCommand::Size100
+            | MenuCommand::Size200 => Some(Submenu::Size),
+            MenuCommand::Organize
+            | MenuCommand::StackRow
+            | MenuCommand::StackColumn
+            | MenuCommand::Grid
+            | MenuCommand::UniformGrid
+            | MenuCommand::UniformGridTop
+            | MenuCommand::MirrorHorizontal
+            | MenuCommand::MirrorVertical => Some(Submenu::Arrange),
+            _ => None,
+        }
+    }
+
+    /// The [`Action`] doing the same, listed in the command palette instead of this command.
+    pub(super) fn action(&self) -> Option<Action> {
+        match self {
+            MenuCommand::Reselect => Some(Action::ReselectLast),
+            MenuCommand::PixelSnap => Some(Action::TogglePixelSnap),
+            MenuCommand::Rename => Some(Action::RenameFrames),
+            _ => None,
+        }
+    }
+
+    /// Whether the item is shown in the context menu opened on the canvas or on a frame.
+    fn is_shown(&self, on_canvas: bool) -> bool {
+        match self.scope() {
+            MenuScope::Canvas => on_canvas,
+            MenuScope::Frame => !on_canvas,
+            MenuScope::Both => true,
+        }
+    }
+}
+
+/// Context menu item running a [`MenuCommand`].
+#[derive(Component)]
+struct MenuItem(MenuCommand);
+
+/// Context menu item opening a [`Submenu`].
+#[derive(Component)]
+struct SubmenuItem(Submenu);
+
+/// Panel listing the items of a [`Submenu`], shown next to its [`SubmenuItem`].
+#[derive(Component)]
+struct SubmenuPanel(Submenu);
+
+pub fn setup_context_menu(world: &mut World) {
+    let menu_background_node = world.resource::<PanelBackground>().0.clone();
+
+    let menu = world
+        .spawn((
+            Name::new("ContextMenu"),
+            ContextMenu::default(),
+            Visibility::Hidden,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            menu_background_node.clone(),
+        ))
+        .id();
+
+    let mut submenus = HashMap::new();
+    for command in MenuCommand::ALL {
+        let parent = match command.submenu() {
+            Some(submenu) => *submenus.entry(submenu).or_insert_with(|| {
+                let item = button(world, submenu.label());
+                world.spawn((
+                    ChildOf(menu),
+                    SubmenuItem(submenu),
+                    item,
+                    Observe::new(on_menu_item_over),
+                    Observe::new(on_submenu_item_clicked),
+                ));
+                // Outside the menu, so that it is placed in window coordinates like the menu
+                world
+                    .spawn((
+                        Name::new("ContextSubmenu"),
+                        SubmenuPanel(submenu),
+                        Visibility::Hidden,
+                        Node {
+                            position_type: PositionType::Absolute,
+                            padding: UiRect::axes(Val::Px(10.0), Val::Px(10.0)),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        menu_background_node.clone(),
+                    ))
+                    .id()
+            }),
+            None => menu,
+        };
+        let label = match command {
+            MenuCommand::AutoSave => auto_save_label(world.resource::<layout::AutoSave>().enabled),
+            MenuCommand::PixelSnap => {
+                pixel_snap_label(world.resource::<grid_snap::PixelSnap>().enabled)
+            }
+            command => command.label(),
+        };
+        let item = button(world, label);
+        let mut item = world.spawn((
+            ChildOf(parent),
+            MenuItem(command),
+            item,
+            Observe::new(on_menu_item_clicked),
+        ));
+        if parent == menu {
+            item.insert(Observe::new(on_menu_item_over));
+        }
+        match command {
+            MenuCommand::AutoSave => {
+                item.insert(AutoSaveButton);
+            }
+            MenuCommand::PixelSnap => {
+                item.insert(PixelSnapButton);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Opens the submenu of a hovered [`SubmenuItem`], or closes it when hovering another item.
+fn on_menu_item_over(
+    trigger: Trigger<Pointer<Over>>,
+    submenu_items: Query<&SubmenuItem>,
+    mut context_menu: Single<&mut ContextMenu>,
+) {
+    let submenu = submenu_items.get(trigger.target()).ok().map(|item| item.0);
+    if context_menu.open_submenu != submenu {
+        context_menu.open_submenu = submenu;
+    }
+}
+
+/// Opens the submenu of a [`SubmenuItem`] for pointers that don't hover, such as touches.
+fn on_submenu_item_clicked(
+    mut trigger: Trigger<Pointer<Click>>,
+    submenu_items: Query<&SubmenuItem>,
+    mut context_menu: Single<&mut ContextMenu>,
+) {
+    trigger.propagate(false);
+
+    if let Ok(item) = submenu_items.get(trigger.target()) {
+        context_menu.open_submenu = Some(item.0);
+    }
+}
+
+fn on_menu_item_clicked(
+    mut trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    items: Query<&MenuItem>,
+    context_menu: Single<&ContextMenu>,
+) {
+    trigger.propagate(false);
+
+    let Ok(MenuItem(command)) = items.get(trigger.target()) else {
+        return;
+    };
+    commands.run_system_cached_with(
+        run_menu_command,
+        (*command, context_menu.target_frames.clone()),
+    );
+}
+
+/// One-shot system to run `command` on the `targets` frames. Use
+/// `Commands::run_system_cached_with` to run it.
+pub(super) fn run_menu_command(
+    In((command, targets)): In<(MenuCommand, Vec<Entity>)>,
+    mut commands: Commands,
+    frame: Res<FrameCount>,
+    frames: Query<(&Transform, &Sprite), With<ImageFrame>>,
+    mut auto_save: ResMut<layout::AutoSave>,
+) {
+    match command {
+        MenuCommand::AddImages => pick_images_to_import(&mut commands, &frame),
+        MenuCommand::Browse => commands.run_system_cached(browse::browse_folder),
+        MenuCommand::KeyBindings => commands.run_system_cached(show_key_bindings_panel),
+        MenuCommand::Remove => {
+            for target in targets {
+                commands.safe_despawn(target);
+            }
+        }
+        MenuCommand::ToFront => commands.run_system_cached_with(z_order::bring_to_front, targets),
+        MenuCommand::ToBack => commands.run_system_cached_with(z_order::send_to_back, targets),
+        MenuCommand::SetZ => {
+            let Some((transform, _)) = targets.first().and_then(|&first| frames.get(first).ok())
+            else {
+                return;
+            };
+            let transforms = frames.iter().map(|(transform, _)| transform);
+            let index = z_order::stack_index(transform.translation.z, transforms);
+            let top = frames.iter().len().saturating_sub(1);
+            open_dialog(&mut commands, dialog::z_dialog(targets, index, top));
+        }
+        MenuCommand::Reselect => commands.run_system_cached(selection_history::reselect_last),
+        MenuCommand::Style => {
+            if targets.is_empty() {
+                return;
+            }
+            // Values shared by all targets, or empty where they differ
+            let sprites = frames.iter_many(&targets).map(|(_, sprite)| sprite);
+            let common = style::CommonStyle::of(sprites);
+            open_dialog(&mut commands, dialog::style_dialog(targets, common));
+        }
+        MenuCommand::AutoSave => {
+            auto_save.enabled = !auto_save.enabled;
+            info!(
+                "Auto-save {}",
+                if auto_save.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+        }
+        MenuCommand::AddFolder => pick_folder_to_import(&mut commands, &frame),
+        MenuCommand::SetSize => commands.run_system_cached_with(arrange::set_size, targets),
+        MenuCommand::Size50 => {
+            commands.run_system_cached_with(arrange::actual_size, (targets, 0.5))
+        }
+        MenuCommand::Size100 => {
+            commands.run_system_cached_with(arrange::actual_size, (targets, 1.0))
+        }
+        MenuCommand::Size200 => {
+            commands.run_system_cached_with(arrange::actual_size, (targets, 2.0))
+        }
+        MenuCommand::Pin => commands.run_system_cached_with(pin::toggle_pin, targets),
+        MenuCommand::ExportFrames => pick_folder_to_export(&mut commands, &frame, targets),
+        MenuCommand::PixelSnap => commands.run_system_cached(grid_snap::toggle_pixel_snap),
+        MenuCommand::Organize => commands.run_system_cached_with(organize_canvas, targets),
+        MenuCommand::StackRow => {
+            commands.run_system_cached_with(arrange::stack_horizontal, targets)
+        }
+        MenuCommand::StackColumn => {
+            commands.run_system_cached_with(arrange::stack_vertical, targets)
+        }
+        MenuCommand::Grid => commands.run_system_cached_with(arrange::arrange_grid, targets),
+        MenuCommand::UniformGrid => commands.run_system_cached_with(
+            arrange::arrange_uniform_grid,
+            (targets, arrange::CellAlign::Center),
+        ),
+        MenuCommand::UniformGridTop => commands.run_system_cached_with(
+            arrange::arrange_uniform_grid,
+            (targets, arrange::CellAlign::Top),
+        ),
+        MenuCommand::MirrorHorizontal => {
+            commands.run_system_cached_with(arrange::mirror_horizontal, targets)
+        }
+        MenuCommand::MirrorVertical => {
+            commands.run_system_cached_with(arrange::mirror_vertical, targets)
+        }
+        MenuCommand::SampleMode => {
+            commands.run_system_cached_with(sampling::toggle_sample_mode, targets)
+        }
+        MenuCommand::Rename => {
+            if !targets.is_empty() {
+                open_dialog(&mut commands, dialog::rename_dialog(targets));
+            }
+        }
+        MenuCommand::Rotate => {
+            let Some((transform, _)) = targets.first().and_then(|&first| frames.get(first).ok())
+            else {
+                return;
+            };
+            // Start from the angle of the first frame, in the range typed angles are normalized
+            // to
+            let angle = arrange::normalize_degrees(
+                transform.rotation.to_euler(EulerRot::XYZ).2.to_degrees(),
+            );
+            open_dialog(&mut commands, dialog::rotate_dialog(targets, angle));
+        }
+    }
+}
+
+/// Asks for image files and imports them.
+fn pick_images_to_import(commands: &mut Commands, frame: &FrameCount) {
+    let files = rfd::FileDialog::new().pick_files();
+    commands.queue(modal::block_native_dialog(frame));
+    info!(?files);
+    if let Some(files) = files {
+        import::import_files(commands, files);
+    }
+}
+
+/// Asks for a folder and imports the images in it.
+fn pick_folder_to_import(commands: &mut Commands, frame: &FrameCount) {
+    let dir = rfd::FileDialog::new().pick_folder();
+    commands.queue(modal::block_native_dialog(frame));
+    let Some(dir) = dir else {
+        return;
+    };
+    match browse::image_files(&dir) {
+        Ok(files) => import::import_files(commands, files),
+        Err(error) => warn!("Failed to read {}: {error}", dir.display()),
+    }
+}
+
+/// Context menu item to toggle [`layout::AutoSave`], labeled with its state.
+#[derive(Component)]
+struct AutoSaveButton;
+
+fn auto_save_label(enabled: bool) -> &'static str {
+    if enabled {
+        "Auto-Save: On"
+    } else {
+        "Auto-Save: Off"
+    }
+}
+
+fn update_auto_save_label(
+    auto_save: Res<layout::AutoSave>,
+    button: Single<&Children, With<AutoSaveButton>>,
+    mut text: Query<&mut Text>,
+) {
+    let mut iter = text.iter_many_mut(*button);
+    while let Some(mut text) = iter.fetch_next() {
+        text.0 = auto_save_label(auto_save.enabled).to_string();
+    }
+}
+
+/// Context menu item to toggle [`grid_snap::PixelSnap`], labeled with its state.
+#[derive(Component)]
+struct PixelSnapButton;
+
+fn pixel_snap_label(enabled: bool) -> &'static str {
+    if enabled {
+        "Pixel Snap: On"
+    } else {
+        "Pixel Snap: Off"
+    }
+}
+
+fn update_pixel_snap_label(
+    pixel_snap: Res<grid_snap::PixelSnap>,
+    button: Single<&Children, With<PixelSnapButton>>,
+    mut text: Query<&mut Text>,
+) {
+    let mut iter = text.iter_many_mut(*button);
+    while let Some(mut text) = iter.fetch_next() {
+        text.0 = pixel_snap_label(pixel_snap.enabled).to_string();
+    }
+}
+
+/// Asks for a folder and exports the `target` frames into it.
+fn pick_folder_to_export(commands: &mut Commands, frame: &FrameCount, target: Vec<Entity>) {
+    let dir = rfd::FileDialog::new().pick_folder();
+    commands.queue(modal::block_native_dialog(frame));
+    if let Some(dir) = dir {
+        commands.run_system_cached_with(export::export_frames, (target, dir));
+    }
+}
+
+fn update_context_menu_state(
+    mut items: Query<(&MenuItem, &mut Node)>,
+    mut submenu_items: Query<(&SubmenuItem, &mut Node), Without<MenuItem>>,
+    target: Query<(Entity, Option<&SelectionOrder>), Or<(With<Hovered>, With<Selected>)>>,
+    frames: Query<Entity, With<ImageFrame>>,
+    mut context_menu: Single<&mut ContextMenu>,
+) {
+    let on_canvas = target.is_empty();
+    let display = |shown: bool| {
+        if shown {
+            Display::default()
+        } else {
+            Display::None
+        }
+    };
+
+    for (MenuItem(command), mut node) in &mut items {
+        node.display = display(command.is_shown(on_canvas));
+    }
+    // A submenu is shown if any of its items is
+    for (SubmenuItem(submenu), mut node) in &mut submenu_items {
+        node.display = display(
+            MenuCommand::ALL
+                .iter()
+                .any(|command| command.submenu() == Some(*submenu) && command.is_shown(on_canvas)),
+        );
+    }
+    context_menu.open_submenu = None;
+
+    if target.is_empty() {
+        context_menu.target_frames = frames.iter().collect();
+    } else {
+        // In the order of selection, so that actions can follow the click order
+        let mut target = target.iter().collect::<Vec<_>>();
+        target.sort_by_key(|&(_, order)| order.copied());
+        context_menu.target_frames = target.into_iter().map(|(entity, _)| entity).collect();
+    }
+}
+
+fn on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut context_menu: Query<(&mut ContextMenu, &mut Node, &mut Visibility)>,
+    panning: Res<Panning>,
+    submenu_items: Query<(), With<SubmenuItem>>,
+    parents: Query<&ChildOf>,
+) {
+    let Ok((mut context_menu, mut node, mut visibility)) = context_menu.single_mut() else {
+        return;
+    };
+    // Releasing a right-drag pan doesn't open the menu
+    if panning.is_panning(trigger.pointer_id) {
+        return;
+    }
+    // Opening a submenu keeps the menu open
+    let target = trigger.target();
+    if std::iter::once(target)
+        .chain(parents.iter_ancestors(target))
+        .any(|entity| submenu_items.contains(entity))
+    {
+        return;
+    }
+
+    if trigger.button != PointerButton::Secondary {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+
+    // Secondary button clicked
+
+    commands.run_system_cached(update_context_menu_state);
+
+    let position = trigger.pointer_location.position;
+    context_menu.anchor = position;
+    node.left = Val::Px(position.x);
+    node.top = Val::Px(position.y);
+
+    visibility.set_if_neq(Visibility::Inherited);
+}
+
+/// Moves the open context menu according to [`ContextMenuSettings::anchor`]. Uses the size
+/// measured by the last layout, so the menu settles on the frame after its items change.
+fn place_context_menu(
+    settings: Res<ContextMenuSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    context_menu: Single<(&ContextMenu, &mut Node, &ComputedNode, &Visibility)>,
+    mut redraw: ResMut<Redraw>,
+) {
+    let (context_menu, mut node, computed_node, visibility) = context_menu.into_inner();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let position = match settings.anchor {
+        MenuAnchor::TopLeft => context_menu.anchor,
+        MenuAnchor::NearestCorner => menu_position(
+            context_menu.anchor,
+            computed_node.size() * computed_node.inverse_scale_factor(),
+            window.size(),
+        ),
+    };
+
+    let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+    if node.left != left || node.top != top {
+        node.left = left;
+        node.top = top;
+        // Lay out again at the new position
+        redraw.request_redraw_once();
+    }
+}
+
+/// Shows the open submenu next to its item, on the side with room in the window like
+/// [`menu_position`], and hides the others.
+fn place_submenus(
+    window: Single<&Window, With<PrimaryWindow>>,
+    context_menu: Single<(&ContextMenu, &Visibility), Without<SubmenuPanel>>,
+    submenu_items: Query<(&SubmenuItem, &ComputedNode, &GlobalTransform)>,
+    mut panels: Query<(&SubmenuPanel, &mut Node, &ComputedNode, &mut Visibility)>,
+    mut redraw: ResMut<Redraw>,
+) {
+    let (context_menu, menu_visibility) = *context_menu;
+    for (SubmenuPanel(submenu), mut node, computed_node, mut visibility) in &mut panels {
+        let item = submenu_items
+            .iter()
+            .find(|(item, ..)| item.0 == *submenu)
+            .filter(|_| {
+                *menu_visibility != Visibility::Hidden
+                    && context_menu.open_submenu == Some(*submenu)
+            });
+        let Some((_, item_node, item_transform)) = item else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        // Computed sizes and UI transforms are in physical pixels
+        let scale = item_node.inverse_scale_factor();
+        let item_rect = Rect::from_center_size(
+            item_transform.translation().xy() * scale,
+            item_node.size() * scale,
+        );
+        let position = submenu_position(
+            item_rect,
+            computed_node.size() * computed_node.inverse_scale_factor(),
+            window.size(),
+        );
+
+        let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+        if node.left != left || node.top != top {
+            node.left = left;
+            node.top = top;
+            redraw.request_redraw_once();
+        }
+    }
+}
+
+/// Returns the top-left position of a menu of `size` opened at `cursor`, placing its corner
+/// nearest to the cursor there so that it expands towards the side with room in the window.
+/// A menu fitting on neither side is kept inside the window.
+fn menu_position(cursor: Vec2, size: Vec2, window_size: Vec2) -> Vec2 {
+    place_menu(cursor, cursor, size, window_size)
+}
+
+/// Returns the top-left position of a submenu of `size` opened from the item at `item`. It
+/// expands right from the right edge of the item and down from its top, or left from the left
+/// edge and up from the bottom where the window has no room, like [`menu_position`].
+fn submenu_position(item: Rect, size: Vec2, window_size: Vec2) -> Vec2 {
+    place_menu(
+        Vec2::new(item.max.x, item.min.y),
+        Vec2::new(item.min.x, item.max.y),
+        size,
+        window_size,
+    )
+}
+
+/// Returns the top-left position of a menu of `size` starting at `start` on each axis, or
+/// ending at `end` where it would leave the window. A menu fitting on neither side is kept
+/// inside the window.
+fn place_menu(start: Vec2, end: Vec2, size: Vec2, window_size: Vec2) -> Vec2 {
+    let axis = |start: f32, end: f32, size: f32, window_size: f32| {
+        if start + size <= window_size {
+            start
+        } else if end >= size {
+            end - size
+        } else {
+            (window_size - size).max(0.0)
+        }
+    };
+    Vec2::new(
+        axis(start.x, end.x, size.x, window_size.x),
+        axis(start.y, end.y, size.y, window_size.y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        picking::{
+            backend::HitData,
+            pointer::{Location, PointerId},
+        },
+        platform::collections::HashSet,
+        render::camera::NormalizedRenderTarget,
+        window::WindowRef,
+    };
+
+    use super::*;
+
+    fn click(world: &mut World, target: Entity) {
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Entity(target).normalize(None).unwrap(),
+            ),
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(target, 0.0, None, None),
+            duration: Duration::ZERO,
+        };
+        world.trigger_targets(
+            Pointer::new(PointerId::Mouse, location, target, click),
+            target,
+        );
+        world.flush();
+    }
+
+    #[test]
+    fn test_menu_items_run_commands() {
+        let labels = MenuCommand::ALL
+            .iter()
+            .map(MenuCommand::label)
+            .collect::<HashSet<_>>();
+        assert_eq!(labels.len(), MenuCommand::ALL.len());
+
+        let mut world = World::new();
+        world.init_resource::<FrameCount>();
+        world.init_resource::<layout::AutoSave>();
+        let frames = [(); 2].map(|_| world.spawn(ImageFrame(default())).id());
+        world.spawn(ContextMenu {
+            target_frames: vec![frames[0]],
+            ..default()
+        });
+        let mut item = |command| {
+            world
+                .spawn((MenuItem(command), Observe::new(on_menu_item_clicked)))
+                .id()
+        };
+        let (remove, auto_save) = (item(MenuCommand::Remove), item(MenuCommand::AutoSave));
+        world.flush();
+
+        // Only the frames the menu was opened on
+        click(&mut world, remove);
+        assert!(world.get_entity(frames[0]).is_err());
+        assert!(world.get_entity(frames[1]).is_ok());
+
+        let enabled = world.resource::<layout::AutoSave>().enabled;
+        click(&mut world, auto_save);
+        assert_eq!(world.resource::<layout::AutoSave>().enabled, !enabled);
+    }
+
+    #[test]
+    fn test_uniform_grid_command() {
+        let mut world = World::new();
+        world.init_resource::<FrameCount>();
+        world.init_resource::<layout::AutoSave>();
+        world.init_resource::<arrange::ArrangeSettings>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<crate::canvas::undo::UndoStack>();
+        let frame = |world: &mut World, size: Vec2, x: f32| {
+            world
+                .spawn((
+                    ImageFrame(default()),
+                    Sprite {
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    Transform::from_xyz(x, 0.0, 0.0),
+                ))
+                .id()
+        };
+        let frames = vec![
+            frame(&mut world, Vec2::new(20.0, 10.0), 0.0),
+            frame(&mut world, Vec2::new(10.0, 30.0), 100.0),
+        ];
+
+        world
+            .run_system_cached_with(
+                run_menu_command,
+                (MenuCommand::UniformGridTop, frames.clone()),
+            )
+            .unwrap();
+
+        // Fitted into cells as large as the largest frame, keeping the aspect ratio
+        let size = |entity| world.get::<Sprite>(entity).unwrap().custom_size.unwrap();
+        assert_eq!(size(frames[0]), Vec2::new(30.0, 15.0));
+        assert_eq!(size(frames[1]), Vec2::new(10.0, 30.0));
+        // Aligned with the tops of their cells in the same row
+        let top =
+            |entity| world.get::<Transform>(entity).unwrap().translation.y + size(entity).y / 2.0;
+        assert_eq!(top(frames[0]), top(frames[1]));
+    }
+
+    #[test]
+    fn test_submenu_position() {
+        let size = Vec2::new(100.0, 200.0);
+        let window_size = Vec2::new(800.0, 600.0);
+        let item = |x: f32, y: f32| Rect::new(x, y, x + 150.0, y + 35.0);
+
+        // Right of the item, top-aligned
+        assert_eq!(
+            submenu_position(item(10.0, 20.0), size, window_size),
+            Vec2::new(160.0, 20.0)
+        );
+        // Left of an item near the right edge
+        assert_eq!(
+            submenu_position(item(600.0, 20.0), size, window_size),
+            Vec2::new(500.0, 20.0)
+        );
+        // Bottom-aligned with an item near the bottom edge
+        assert_eq!(
+            submenu_position(item(10.0, 500.0), size, window_size),
+            Vec2::new(160.0, 335.0)
+        );
+        // Kept inside a window too small for either side
+        assert_eq!(
+            submenu_position(item(10.0, 100.0), size, Vec2::new(800.0, 250.0)),
+            Vec2::new(160.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn test_menu_position() {
+        let size = Vec2::new(100.0, 200.0);
+        let window_size = Vec2::new(800.0, 600.0);
+
+        // Expands right and down when there is room
+        assert_eq!(
+            menu_position(Vec2::new(10.0, 20.0), size, window_size),
+            Vec2::new(10.0, 20.0)
+        );
+        // Expands left and up near the bottom-right edges
+        assert_eq!(
+            menu_position(Vec2::new(750.0, 500.0), size, window_size),
+            Vec2::new(650.0, 300.0)
+        );
+        // Kept inside a window too small for either side
+        assert_eq!(
+            menu_position(Vec2::new(50.0, 150.0), size, Vec2::new(800.0, 250.0)),
+            Vec2::new(50.0, 50.0)
+        );
+    }
+}
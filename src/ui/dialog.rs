@@ -0,0 +1,472 @@
+//! Modal dialogs of text fields, such as renaming or rotating frames.
+//!
+//! A [`TextDialog`] lists its fields and what to do with the typed values. Opening one
+//! spawns its panel in the dialog layer and blocks key bindings until it is applied or
+//! cancelled.
+
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+
+use crate::{
+    bevyhow,
+    canvas::{
+        Selected, SelectionOrder, arrange,
+        naming::{self, RenameRequest},
+        style::{self, StyleRequest},
+        z_order,
+    },
+    despawn::SafeDespawn,
+    key_bindings::{Action, action_just_pressed},
+    modal::{ModalSource, close_modal, open_modal},
+    observe_component::Observe,
+};
+
+use super::widget::{button, edit_text, field_text, spawn_dialog_panel, text_field};
+
+pub struct DialogPlugin;
+
+impl Plugin for DialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_rename_dialog_for_selection.run_if(action_just_pressed(Action::RenameFrames)),
+                listen_dialog_input,
+                update_dialog_fields.run_if(resource_exists_and_changed::<TextDialog>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Field of a [`TextDialog`].
+pub struct DialogField {
+    pub label: &'static str,
+    /// Text as typed.
+    pub value: String,
+    /// Characters that can be typed into the field.
+    pub accepts: fn(char) -> bool,
+    /// Shown while the field is empty and has not been edited, such as for values that
+    /// differ between frames.
+    pub placeholder: &'static str,
+    /// Whether a key has edited the field. A field cleared by the user shows no placeholder.
+    pub edited: bool,
+}
+
+impl DialogField {
+    pub fn new(label: &'static str, value: impl Into<String>, accepts: fn(char) -> bool) -> Self {
+        Self {
+            label,
+            value: value.into(),
+            accepts,
+            placeholder: "",
+            edited: false,
+        }
+    }
+
+    pub fn with_placeholder(mut self, placeholder: &'static str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Applies a key press to the value.
+    fn edit(&mut self, key: &Key) {
+        if edit_text(&mut self.value, key, self.accepts) {
+            self.edited = true;
+        }
+    }
+
+    /// Text shown in the field box.
+    fn text(&self, focused: bool) -> String {
+        let placeholder = if self.edited { "" } else { self.placeholder };
+        field_text(&self.value, focused, placeholder)
+    }
+}
+
+/// Applies the values of the fields of a [`TextDialog`] in order. An error keeps the dialog
+/// open so that the value can be corrected.
+type ApplyDialog = Box<dyn Fn(&mut Commands, &[&str]) -> Result + Send + Sync>;
+
+/// Dialog of text fields. Present while the dialog is open.
+#[derive(Resource)]
+pub struct TextDialog {
+    name: &'static str,
+    fields: Vec<DialogField>,
+    /// Note below the fields.
+    hint: Option<String>,
+    /// Index of the field receiving typed text.
+    focus: usize,
+    apply: ApplyDialog,
+}
+
+impl TextDialog {
+    pub fn new(
+        name: &'static str,
+        fields: Vec<DialogField>,
+        apply: impl Fn(&mut Commands, &[&str]) -> Result + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name,
+            fields,
+            hint: None,
+            focus: 0,
+            apply: Box::new(apply),
+        }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    fn apply(&self, commands: &mut Commands) {
+        let values = self
+            .fields
+            .iter()
+            .map(|field| field.value.as_str())
+            .collect::<Vec<_>>();
+        match (self.apply)(commands, &values) {
+            Ok(()) => close_dialog(commands),
+            Err(error) => warn!("{error}"),
+        }
+    }
+}
+
+/// Panel showing the [`TextDialog`].
+#[derive(Component)]
+struct DialogPanel;
+
+/// Box of the field at this index of [`TextDialog::fields`]. Click to focus.
+#[derive(Component)]
+struct DialogFieldBox(usize);
+
+/// Opens `dialog`, replacing any open one.
+pub fn open_dialog(commands: &mut Commands, dialog: TextDialog) {
+    commands.queue(move |world: &mut World| {
+        despawn_panel(world);
+
+        let panel = spawn_dialog_panel(world, dialog.name, DialogPanel, true);
+        for (index, field) in dialog.fields.iter().enumerate() {
+            world.spawn((
+                ChildOf(panel),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    (
+                        Text::new(field.label),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        Node {
+                            width: Val::Px(80.0),
+                            ..default()
+                        },
+                    ),
+                    (
+                        DialogFieldBox(index),
+                        text_field(200.0),
+                        Observe::new(on_dialog_field_clicked),
+                    )
+                ],
+            ));
+        }
+
+        if let Some(hint) = &dialog.hint {
+            world.spawn((
+                ChildOf(panel),
+                Text::new(hint),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+        }
+
+        world.spawn((
+            ChildOf(panel),
+            Node {
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            children![
+                (
+                    button(world, "Apply"),
+                    Observe::new(
+                        |mut trigger: Trigger<Pointer<Click>>,
+                         mut commands: Commands,
+                         dialog: Option<Res<TextDialog>>| {
+                            trigger.propagate(false);
+                            if let Some(dialog) = dialog {
+                                dialog.apply(&mut commands);
+                            }
+                        },
+                    ),
+                ),
+                (
+                    button(world, "Cancel"),
+                    Observe::new(
+                        |mut trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                            trigger.propagate(false);
+                            close_dialog(&mut commands);
+                        },
+                    ),
+                )
+            ],
+        ));
+
+        world.insert_resource(dialog);
+        // Typed text must not trigger key bindings or edit the canvas
+        open_modal(ModalSource::Dialog).apply(world);
+    });
+}
+
+fn close_dialog(commands: &mut Commands) {
+    commands.remove_resource::<TextDialog>();
+    commands.queue(close_modal(ModalSource::Dialog));
+    commands.queue(despawn_panel);
+}
+
+fn despawn_panel(world: &mut World) {
+    let panels = world
+        .query_filtered::<Entity, With<DialogPanel>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for panel in panels {
+        world.safe_despawn(panel);
+    }
+}
+
+fn on_dialog_field_clicked(
+    mut trigger: Trigger<Pointer<Click>>,
+    fields: Query<&DialogFieldBox>,
+    mut dialog: Option<ResMut<TextDialog>>,
+) {
+    trigger.propagate(false);
+
+    if let (Ok(field), Some(dialog)) = (fields.get(trigger.target()), dialog.as_mut()) {
+        dialog.focus = field.0;
+    }
+}
+
+/// Types into the focused field of the [`TextDialog`]. Tab moves to the next field, Enter
+/// applies and Escape cancels.
+fn listen_dialog_input(
+    mut commands: Commands,
+    mut events: EventReader<KeyboardInput>,
+    dialog: Option<ResMut<TextDialog>>,
+) {
+    let Some(mut dialog) = dialog else {
+        events.clear();
+        return;
+    };
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                dialog.apply(&mut commands);
+                return;
+            }
+            Key::Escape => {
+                close_dialog(&mut commands);
+                return;
+            }
+            Key::Tab => {
+                dialog.focus = (dialog.focus + 1) % dialog.fields.len();
+            }
+            key => {
+                let focus = dialog.focus;
+                dialog.fields[focus].edit(key);
+            }
+        }
+    }
+}
+
+fn update_dialog_fields(
+    dialog: Res<TextDialog>,
+    fields: Query<(&DialogFieldBox, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (field_box, children) in &fields {
+        let Some(field) = dialog.fields.get(field_box.0) else {
+            continue;
+        };
+        let label = field.text(field_box.0 == dialog.focus);
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0.clone_from(&label);
+        }
+    }
+}
+
+/// Dialog to name `targets` from a pattern, numbered in the order given.
+pub fn rename_dialog(targets: Vec<Entity>) -> TextDialog {
+    TextDialog::new(
+        "RenameDialog",
+        vec![
+            DialogField::new(
+                "Name",
+                format!("frame_{}", naming::INDEX_PLACEHOLDER),
+                |_| true,
+            ),
+            DialogField::new("Start", "1", |c| c.is_ascii_digit()),
+        ],
+        move |commands, values| {
+            let [pattern, start] = values else {
+                return Err(bevyhow!("Expected a pattern and a start number"));
+            };
+            let Ok(start) = start.parse() else {
+                return Err(bevyhow!("Invalid start number: {start}"));
+            };
+            commands.run_system_cached_with(
+                naming::rename_frames,
+                RenameRequest {
+                    targets: targets.clone(),
+                    pattern: pattern.to_string(),
+                    start,
+                },
+            );
+            Ok(())
+        },
+    )
+    .with_hint(format!(
+        "{} is replaced by the number",
+        naming::INDEX_PLACEHOLDER
+    ))
+}
+
+fn open_rename_dialog_for_selection(
+    mut commands: Commands,
+    selected: Query<(Entity, Option<&SelectionOrder>), With<Selected>>,
+) {
+    let mut selected = selected.iter().collect::<Vec<_>>();
+    if selected.is_empty() {
+        return;
+    }
+    selected.sort_by_key(|&(_, order)| order.copied());
+    let targets = selected.into_iter().map(|(entity, _)| entity).collect();
+    open_dialog(&mut commands, rename_dialog(targets));
+}
+
+/// Dialog to set the tint and opacity of `targets`, starting from their `common` values.
+/// Fields left empty keep the value of each frame.
+pub fn style_dialog(targets: Vec<Entity>, common: style::CommonStyle) -> TextDialog {
+    let tint = common.tint.map(|tint| tint.to_hex()).unwrap_or_default();
+    let opacity = common
+        .opacity
+        .map(|opacity| format!("{}", (opacity * 100.0).round()))
+        .unwrap_or_default();
+
+    TextDialog::new(
+        "StyleDialog",
+        vec![
+            DialogField::new("Tint", tint, |c| c.is_ascii_hexdigit() || c == '#')
+                .with_placeholder("(mixed)"),
+            DialogField::new("Opacity", opacity, |c| c.is_ascii_digit() || c == '.')
+                .with_placeholder("(mixed)"),
+        ],
+        move |commands, values| {
+            let [tint, opacity] = values else {
+                return Err(bevyhow!("Expected a tint and an opacity"));
+            };
+            let tint = match *tint {
+                "" => None,
+                hex => match Srgba::hex(hex) {
+                    Ok(tint) => Some(tint),
+                    Err(error) => return Err(bevyhow!("Invalid tint {hex}: {error}")),
+                },
+            };
+            let opacity = match *opacity {
+                "" => None,
+                percent => match percent.parse::<f32>() {
+                    Ok(percent) => Some(percent / 100.0),
+                    Err(_) => return Err(bevyhow!("Invalid opacity: {percent}")),
+                },
+            };
+            commands.run_system_cached_with(
+                style::set_style,
+                StyleRequest {
+                    targets: targets.clone(),
+                    tint,
+                    opacity,
+                },
+            );
+            Ok(())
+        },
+    )
+    .with_hint("Tint as #RRGGBB, opacity in %")
+}
+
+/// Dialog to type the rotation of `targets` in degrees, starting from `angle`.
+pub fn rotate_dialog(targets: Vec<Entity>, angle: f32) -> TextDialog {
+    TextDialog::new(
+        "RotateDialog",
+        vec![DialogField::new(
+            "Angle",
+            format!("{}", (angle * 10.0).round() / 10.0),
+            |c| c.is_ascii_digit() || matches!(c, '-' | '.'),
+        )],
+        move |commands, values| {
+            let [angle] = values else {
+                return Err(bevyhow!("Expected an angle"));
+            };
+            let Ok(angle) = angle.parse::<f32>() else {
+                return Err(bevyhow!("Invalid angle: {angle}"));
+            };
+            commands.run_system_cached_with(arrange::set_rotation, (targets.clone(), angle));
+            Ok(())
+        },
+    )
+    .with_hint("Angle in degrees")
+}
+
+/// Dialog to type the index of `targets` in the stack, starting from `index`. `top` is the
+/// index of the topmost frame.
+pub fn z_dialog(targets: Vec<Entity>, index: usize, top: usize) -> TextDialog {
+    TextDialog::new(
+        "ZDialog",
+        vec![DialogField::new("Z", format!("{index}"), |c| {
+            c.is_ascii_digit() || c == '-'
+        })],
+        move |commands, values| {
+            let [index] = values else {
+                return Err(bevyhow!("Expected a stack index"));
+            };
+            let Ok(index) = index.parse::<i64>() else {
+                return Err(bevyhow!("Invalid stack index: {index}"));
+            };
+            commands.run_system_cached_with(z_order::set_z, (targets.clone(), index));
+            Ok(())
+        },
+    )
+    .with_hint(format!("0 (bottom) to {top} (top)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_placeholder() {
+        let mut field = DialogField::new("Tint", "", |_| true).with_placeholder("(mixed)");
+        assert_eq!(field.text(false), "(mixed)");
+        assert_eq!(field.text(true), "_ (mixed)");
+
+        field.edit(&Key::Character("a".into()));
+        assert_eq!(field.text(true), "a_");
+        field.edit(&Key::Backspace);
+        // Cleared by the user
+        assert_eq!(field.text(true), "_");
+        assert_eq!(field.text(false), "");
+    }
+}
@@ -0,0 +1,282 @@
+//! Panel listing recent edits, to undo or redo up to one of them or back to the state before
+//! the first edit.
+
+use bevy::prelude::*;
+
+use crate::{
+    canvas::{ImageFrame, undo},
+    observe_component::Observe,
+};
+
+use super::widget::{PanelBackground, button};
+
+pub struct HistoryPanelPlugin;
+
+impl Plugin for HistoryPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_history_panel.run_if(resource_changed::<undo::UndoStack>),
+        );
+    }
+}
+
+/// Panel listing recent edits after an "Open" entry for the state before them. Clicking an
+/// entry undoes or redoes up to it.
+#[derive(Component)]
+struct HistoryPanel;
+
+/// Row slot of [`HistoryPanel`], showing the edit that leads to `position` in
+/// [`UndoStack::history`](undo::UndoStack::history), or "Open" for position 0.
+#[derive(Component)]
+struct HistoryEntry {
+    slot: usize,
+    position: usize,
+}
+
+/// Image of [`undo::EditAction::thumbnail_frame`] in the row of [`HistoryEntry`] `slot`.
+#[derive(Component)]
+struct HistoryThumbnail(usize);
+
+/// Maximum number of entries shown in [`HistoryPanel`].
+const HISTORY_LENGTH: usize = 8;
+
+pub fn setup_history_panel(world: &mut World) {
+    let background = world.resource::<PanelBackground>().0.clone();
+    let panel = world
+        .spawn((
+            Name::new("HistoryPanel"),
+            HistoryPanel,
+            Visibility::Hidden,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(5.0),
+                right: Val::Px(5.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            background,
+            Observe::new(|mut trigger: Trigger<Pointer<Click>>| {
+                trigger.propagate(false);
+            }),
+        ))
+        .id();
+
+    for slot in 0..HISTORY_LENGTH {
+        let row = world
+            .spawn((
+                ChildOf(panel),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![(
+                    HistoryThumbnail(slot),
+                    ImageNode::default(),
+                    Node {
+                        width: Val::Px(28.0),
+                        height: Val::Px(28.0),
+                        ..default()
+                    },
+                )],
+            ))
+            .id();
+        let button = button(world, "");
+        world
+            .spawn((
+                ChildOf(row),
+                HistoryEntry { slot, position: 0 },
+                button,
+                Observe::new(on_history_entry_clicked),
+            ))
+            .insert(Node {
+                width: Val::Px(220.0),
+                height: Val::Px(35.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            });
+    }
+}
+
+fn on_history_entry_clicked(
+    mut trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    entries: Query<&HistoryEntry>,
+) {
+    trigger.propagate(false);
+
+    if let Ok(entry) = entries.get(trigger.target()) {
+        let position = entry.position;
+        commands.queue(move |world: &mut World| undo::go_to(world, position));
+    }
+}
+
+/// Fills the rows of [`HistoryPanel`] with the edits around the current state.
+fn update_history_panel(
+    undo_stack: Res<undo::UndoStack>,
+    mut panel: Single<&mut Visibility, With<HistoryPanel>>,
+    mut entries: Query<(&mut HistoryEntry, &Children, &ChildOf)>,
+    mut rows: Query<&mut Node, Without<HistoryEntry>>,
+    mut thumbnails: Query<
+        (&HistoryThumbnail, &mut ImageNode, &mut Visibility),
+        Without<HistoryPanel>,
+    >,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+    frames: Query<&ImageFrame>,
+) {
+    let history = undo_stack.history().collect::<Vec<_>>();
+    let position = undo_stack.position();
+    if history.is_empty() {
+        panel.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    panel.set_if_neq(Visibility::Inherited);
+
+    // Positions from 0 for "Open" to after the last edit. Keep the current one in the middle
+    // of the list where possible.
+    let start = (position + HISTORY_LENGTH / 2)
+        .min(history.len() + 1)
+        .saturating_sub(HISTORY_LENGTH);
+
+    for (mut entry, children, child_of) in &mut entries {
+        let entry_position = start + entry.slot;
+        let Ok(mut row) = rows.get_mut(child_of.parent()) else {
+            continue;
+        };
+        if entry_position > history.len() {
+            row.display = Display::None;
+            continue;
+        }
+        row.display = Display::default();
+        entry.position = entry_position;
+        let action = entry_position.checked_sub(1).map(|index| history[index]);
+
+        // Undone edits are dimmed
+        let color = if entry_position <= position {
+            Color::srgb(0.9, 0.9, 0.9)
+        } else {
+            Color::srgb(0.5, 0.5, 0.5)
+        };
+        let mut texts = texts.iter_many_mut(children);
+        while let Some((mut text, mut text_color)) = texts.fetch_next() {
+            text.0 = action.map_or_else(|| "Open".to_string(), |action| action.describe());
+            text_color.0 = color;
+        }
+
+        let thumbnail = action
+            .and_then(|action| action.thumbnail_frame())
+            .and_then(|frame| frames.get(frame).ok());
+        for (_, mut image_node, mut visibility) in thumbnails
+            .iter_mut()
+            .filter(|(thumbnail, ..)| thumbnail.0 == entry.slot)
+        {
+            match thumbnail {
+                Some(frame) => {
+                    image_node.image = frame.0.clone();
+                    visibility.set_if_neq(Visibility::Inherited);
+                }
+                None => {
+                    visibility.set_if_neq(Visibility::Hidden);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        picking::{
+            backend::HitData,
+            pointer::{Location, PointerId},
+        },
+        render::camera::NormalizedRenderTarget,
+        window::WindowRef,
+    };
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    struct Increment;
+
+    impl undo::EditAction for Increment {
+        fn describe(&self) -> String {
+            "increment".to_string()
+        }
+
+        fn undo(&self, world: &mut World) {
+            world.resource_mut::<Counter>().0 -= 1;
+        }
+
+        fn redo(&self, world: &mut World) {
+            world.resource_mut::<Counter>().0 += 1;
+        }
+    }
+
+    fn click(world: &mut World, target: Entity) {
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Entity(target).normalize(None).unwrap(),
+            ),
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(target, 0.0, None, None),
+            duration: Duration::ZERO,
+        };
+        world.trigger_targets(
+            Pointer::new(PointerId::Mouse, location, target, click),
+            target,
+        );
+        world.flush();
+    }
+
+    #[test]
+    fn test_open_entry() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.init_resource::<undo::UndoStack>();
+        for _ in 0..2 {
+            world.resource_mut::<Counter>().0 += 1;
+            world.resource_mut::<undo::UndoStack>().push(Increment);
+        }
+
+        world.spawn((HistoryPanel, Visibility::Hidden));
+        let entries = (0..HISTORY_LENGTH)
+            .map(|slot| {
+                let row = world.spawn(Node::default()).id();
+                world
+                    .spawn((
+                        ChildOf(row),
+                        HistoryEntry { slot, position: 0 },
+                        children![(Text::default(), TextColor::default())],
+                        Observe::new(on_history_entry_clicked),
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        world.run_system_cached(update_history_panel).unwrap();
+
+        let text = |world: &World, entry: Entity| {
+            let text = world.get::<Children>(entry).unwrap()[0];
+            world.get::<Text>(text).unwrap().0.clone()
+        };
+        assert_eq!(text(&world, entries[0]), "Open");
+        assert_eq!(text(&world, entries[1]), "increment");
+        assert_eq!(world.get::<HistoryEntry>(entries[2]).unwrap().position, 2);
+
+        // Undoes every edit
+        click(&mut world, entries[0]);
+        assert_eq!(world.resource::<Counter>().0, 0);
+        assert_eq!(world.resource::<undo::UndoStack>().position(), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Panel to rebind the keys of [`Action`]s.
+
+use bevy::prelude::*;
+
+use crate::{
+    key_bindings::{Action, KeyBinding, KeyBindings, is_modifier, modifiers},
+    observe_component::Observe,
+};
+
+use super::widget::{PanelBackground, button};
+
+pub struct KeyBindingsPanelPlugin;
+
+impl Plugin for KeyBindingsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindingAction>().add_systems(
+            Update,
+            (
+                listen_rebind_key,
+                update_rebind_labels.run_if(
+                    resource_changed::<KeyBindings>.or(resource_changed::<RebindingAction>),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Panel to rebind [`Action`]s.
+#[derive(Component)]
+pub(super) struct KeyBindingsPanel;
+
+/// Button showing the key bound to the action. Click to rebind.
+#[derive(Component)]
+struct RebindButton(Action);
+
+/// The action waiting for a key press to be rebound.
+#[derive(Resource, Default)]
+struct RebindingAction(Option<Action>);
+
+pub fn setup_key_bindings_panel(world: &mut World) {
+    let background = world.resource::<PanelBackground>().0.clone();
+    let panel = world
+        .spawn((
+            Name::new("KeyBindingsPanel"),
+            KeyBindingsPanel,
+            Visibility::Hidden,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                right: Val::Px(5.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            background,
+            Observe::new(|mut trigger: Trigger<Pointer<Click>>| {
+                trigger.propagate(false);
+            }),
+        ))
+        .id();
+
+    for action in Action::ALL {
+        world.spawn((
+            ChildOf(panel),
+            Node {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            children![
+                (
+                    Text::new(action.label()),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    Node {
+                        width: Val::Px(170.0),
+                        ..default()
+                    },
+                ),
+                (
+                    RebindButton(action),
+                    button(world, ""),
+                    Observe::new(on_rebind_button_clicked),
+                )
+            ],
+        ));
+    }
+
+    world.spawn((
+        ChildOf(panel),
+        button(world, "Close"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>,
+             mut panel: Single<&mut Visibility, With<KeyBindingsPanel>>,
+             mut rebinding: ResMut<RebindingAction>| {
+                trigger.propagate(false);
+                panel.set_if_neq(Visibility::Hidden);
+                rebinding.0 = None;
+            },
+        ),
+    ));
+}
+
+/// One-shot system to show the key bindings panel.
+pub(super) fn show_key_bindings_panel(mut panel: Single<&mut Visibility, With<KeyBindingsPanel>>) {
+    panel.set_if_neq(Visibility::Inherited);
+}
+
+fn on_rebind_button_clicked(
+    mut trigger: Trigger<Pointer<Click>>,
+    rebind_button: Query<&RebindButton>,
+    mut rebinding: ResMut<RebindingAction>,
+) {
+    trigger.propagate(false);
+
+    if let Ok(rebind_button) = rebind_button.get(trigger.target()) {
+        rebinding.0 = Some(rebind_button.0);
+    }
+}
+
+/// Binds the next key press (with modifiers) to [`RebindingAction`].
+/// Releasing modifiers without another key binds the modifiers alone. Escape cancels.
+fn listen_rebind_key(
+    mut rebinding: ResMut<RebindingAction>,
+    mut key_bindings: ResMut<KeyBindings>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    if input.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+
+    let (ctrl, shift, alt) = modifiers(&input);
+    let mut binding = KeyBinding {
+        key: None,
+        ctrl,
+        shift,
+        alt,
+    };
+
+    if let Some(&key) = input.get_just_pressed().find(|key| !is_modifier(**key)) {
+        binding.key = Some(key);
+    } else if let Some(&released) = input.get_just_released().find(|key| is_modifier(**key))
+        && input.get_pressed().all(|key| is_modifier(*key))
+    {
+        match released {
+            KeyCode::ControlLeft | KeyCode::ControlRight => binding.ctrl = true,
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => binding.shift = true,
+            KeyCode::AltLeft | KeyCode::AltRight => binding.alt = true,
+            _ => return,
+        }
+    } else {
+        return;
+    }
+
+    rebinding.0 = None;
+    key_bindings.set(action, binding);
+    key_bindings.warn_conflicts();
+    if let Err(error) = key_bindings.save() {
+        warn!("Failed to save key bindings: {error}");
+    }
+}
+
+fn update_rebind_labels(
+    key_bindings: Res<KeyBindings>,
+    rebinding: Res<RebindingAction>,
+    rebind_buttons: Query<(&RebindButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (rebind_button, children) in &rebind_buttons {
+        let label = if rebinding.0 == Some(rebind_button.0) {
+            "Press a key...".to_string()
+        } else {
+            key_bindings.get(rebind_button.0).to_string()
+        };
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0.clone_from(&label);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Context menu, dialogs and panels built with `bevy_ui`.
+
+mod context_menu;
+mod dialog;
+mod history;
+mod key_bindings_panel;
+mod palette;
+mod prompt;
+mod widget;
+
+use bevy::prelude::*;
+
+use crate::despawn::SafeDespawn;
+
+use widget::PanelBackground;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            context_menu::ContextMenuPlugin,
+            dialog::DialogPlugin,
+            history::HistoryPanelPlugin,
+            key_bindings_panel::KeyBindingsPanelPlugin,
+            palette::CommandPalettePlugin,
+            prompt::PromptPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, despawn_dummy.run_if(run_once_at(1)));
+    }
+}
+
+fn setup(world: &mut World) {
+    let menu_background = world.resource::<AssetServer>().load("images/tile_0028.png");
+
+    let menu_background_node = ImageNode {
+        image: menu_background,
+        image_mode: NodeImageMode::Sliced(TextureSlicer {
+            border: BorderRect::all(8.),
+            sides_scale_mode: SliceScaleMode::Tile { stretch_value: 1.0 },
+            ..default()
+        }),
+        ..default()
+    };
+
+    // spawn a dummy entity to fix 1-frame delay
+    world.spawn((DummyForShaderInit, menu_background_node.clone()));
+    world.insert_resource(PanelBackground(menu_background_node));
+
+    widget::setup_dialog_layer(world);
+    key_bindings_panel::setup_key_bindings_panel(world);
+    history::setup_history_panel(world);
+    prompt::setup_recovery_prompt(world);
+    prompt::setup_exit_prompt(world);
+    palette::setup_command_palette(world);
+    prompt::setup_import_progress_panel(world);
+    prompt::setup_export_summary_panel(world);
+    context_menu::setup_context_menu(world);
+}
+
+#[derive(Component)]
+struct DummyForShaderInit;
+
+/// Run condition to run the system only once at the `n`-th frame.
+/// `run_once_nth(0)` is equivalent to `run_once()`.
+fn run_once_at(n: u32) -> impl Condition<()> {
+    IntoSystem::into_system(move |mut count: Local<u32>| {
+        if *count > n {
+            return false;
+        }
+        let prev_count = *count;
+        *count += 1;
+        prev_count == n
+    })
+}
+
+fn despawn_dummy(mut commands: Commands, dummy: Query<Entity, With<DummyForShaderInit>>) {
+    for entity in dummy.iter() {
+        commands.safe_despawn(entity);
+    }
+}
@@ -0,0 +1,432 @@
+//! Command palette to search [`Action`]s and context menu commands by name and run one.
+
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+
+use crate::{
+    canvas::{ImageFrame, Selected, SelectionOrder},
+    key_bindings::{Action, InvokeAction, KeyBindings, action_just_pressed, modifiers},
+    modal::{ModalSource, close_modal, open_modal},
+    observe_component::Observe,
+};
+
+use super::{
+    context_menu::{MenuCommand, MenuScope, run_menu_command},
+    widget::{edit_text, field_text, show_panel, spawn_dialog_panel, text_field},
+};
+
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_command_palette.run_if(action_just_pressed(Action::CommandPalette)),
+                listen_command_palette_input,
+                update_command_palette_panel.run_if(resource_changed_or_removed::<CommandPalette>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Number of commands listed in the command palette at once.
+const PALETTE_ROWS: usize = 8;
+
+/// Command listed in the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaletteCommand {
+    Action(Action),
+    Menu(MenuCommand),
+}
+
+impl PaletteCommand {
+    fn label(&self) -> &'static str {
+        match self {
+            PaletteCommand::Action(action) => action.label(),
+            PaletteCommand::Menu(command) => command.label(),
+        }
+    }
+}
+
+/// Command palette to search commands by name and run one. Present while it is open.
+#[derive(Resource, Default)]
+struct CommandPalette {
+    query: String,
+    /// Index of the highlighted command in [`CommandPalette::matches`].
+    selected: usize,
+    /// Index of the first listed command in [`CommandPalette::matches`].
+    scroll: usize,
+}
+
+impl CommandPalette {
+    /// Commands matching the query, best first.
+    fn matches(&self) -> Vec<PaletteCommand> {
+        let commands = Action::ALL
+            .into_iter()
+            .filter(|&action| is_palette_action(action))
+            .map(PaletteCommand::Action)
+            .chain(
+                MenuCommand::ALL
+                    .into_iter()
+                    .filter(|command| command.action().is_none())
+                    .map(PaletteCommand::Menu),
+            );
+        let mut matches = commands
+            .filter_map(|command| Some((fuzzy_score(&self.query, command.label())?, command)))
+            .collect::<Vec<_>>();
+        // Equal scores keep the order of actions, then menu commands
+        matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(_, command)| command).collect()
+    }
+
+    /// Highlights the command at `index` of `count` matches, scrolling it into view.
+    fn select(&mut self, index: usize, count: usize) {
+        self.selected = index.min(count.saturating_sub(1));
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + PALETTE_ROWS {
+            self.scroll = self.selected + 1 - PALETTE_ROWS;
+        }
+    }
+
+    /// Moves the highlight by `rows`, scrolling it into view.
+    fn move_selection(&mut self, rows: isize) {
+        let count = self.matches().len();
+        self.select(self.selected.saturating_add_signed(rows), count);
+    }
+
+    /// Scrolls the list of `count` matches by `rows`, keeping the highlight in view.
+    fn scroll_by(&mut self, rows: isize, count: usize) {
+        let max = count.saturating_sub(PALETTE_ROWS);
+        self.scroll = self.scroll.saturating_add_signed(rows).min(max);
+        let last = (self.scroll + PALETTE_ROWS).min(count).saturating_sub(1);
+        self.selected = self.selected.clamp(self.scroll, last.max(self.scroll));
+    }
+}
+
+/// Whether `action` is listed in the command palette.
+fn is_palette_action(action: Action) -> bool {
+    match action {
+        Action::CommandPalette => false,
+        Action::Inspector | Action::TogglePickingAreas => cfg!(feature = "dev"),
+        Action::ToggleFpsOverlay => cfg!(feature = "fps_overlay"),
+        action => !action.is_held(),
+    }
+}
+
+/// Scores how well `query` matches `label` ignoring case, or `None` unless the characters of
+/// `query` appear in `label` in order. Consecutive characters and word starts score higher.
+fn fuzzy_score(query: &str, label: &str) -> Option<i32> {
+    let label = label.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = next + label[next..].iter().position(|&l| l == c)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 2;
+        }
+        if index == 0 || label[index - 1] == ' ' {
+            score += 3;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+    Some(score)
+}
+
+/// Panel showing [`CommandPalette`].
+#[derive(Component)]
+struct CommandPalettePanel;
+
+/// Field of [`CommandPalette::query`].
+#[derive(Component)]
+struct CommandPaletteField;
+
+/// Row listing the command at this index of the listed part of [`CommandPalette::matches`].
+#[derive(Component)]
+struct CommandPaletteRow(usize);
+
+pub fn setup_command_palette(world: &mut World) {
+    let panel = spawn_dialog_panel(
+        world,
+        "CommandPalettePanel",
+        (
+            CommandPalettePanel,
+            Observe::new(
+                |mut trigger: Trigger<Pointer<Scroll>>, palette: Option<ResMut<CommandPalette>>| {
+                    trigger.propagate(false);
+                    let Some(mut palette) = palette else {
+                        return;
+                    };
+                    let count = palette.matches().len();
+                    palette.scroll_by(if trigger.y > 0.0 { -1 } else { 1 }, count);
+                },
+            ),
+        ),
+        false,
+    );
+
+    world.spawn((ChildOf(panel), CommandPaletteField, text_field(320.0)));
+
+    for index in 0..PALETTE_ROWS {
+        world.spawn((
+            ChildOf(panel),
+            CommandPaletteRow(index),
+            Node {
+                width: Val::Px(320.0),
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            Text::default(),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            // Key binding
+            children![(
+                TextSpan::default(),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.6)),
+            )],
+            Observe::new(
+                |mut trigger: Trigger<Pointer<Click>>,
+                 mut commands: Commands,
+                 rows: Query<&CommandPaletteRow>,
+                 palette: Option<ResMut<CommandPalette>>| {
+                    trigger.propagate(false);
+                    let (Ok(row), Some(mut palette)) = (rows.get(trigger.target()), palette) else {
+                        return;
+                    };
+                    palette.selected = palette.scroll + row.0;
+                    run_command_palette(&mut commands, &palette);
+                },
+            ),
+        ));
+    }
+}
+
+fn open_command_palette(mut commands: Commands) {
+    commands.insert_resource(CommandPalette::default());
+    // Typed text must not trigger key bindings or edit the canvas
+    commands.queue(open_modal(ModalSource::CommandPalette));
+}
+
+fn close_command_palette(commands: &mut Commands) {
+    commands.remove_resource::<CommandPalette>();
+    commands.queue(close_modal(ModalSource::CommandPalette));
+}
+
+/// Closes the palette and runs the highlighted command. An action sees the [`InvokeAction`]
+/// once the palette no longer blocks input.
+fn run_command_palette(commands: &mut Commands, palette: &CommandPalette) {
+    let Some(&command) = palette.matches().get(palette.selected) else {
+        return;
+    };
+    close_command_palette(commands);
+    match command {
+        PaletteCommand::Action(action) => {
+            commands.send_event(InvokeAction(action));
+        }
+        PaletteCommand::Menu(command) => {
+            commands.run_system_cached_with(run_palette_menu_command, command);
+        }
+    }
+}
+
+/// One-shot system to run `command` on the selected frames in the order of selection. Like the
+/// context menu, commands shown on the canvas run on all frames if none is selected.
+fn run_palette_menu_command(
+    In(command): In<MenuCommand>,
+    mut commands: Commands,
+    selected: Query<(Entity, Option<&SelectionOrder>), With<Selected>>,
+    frames: Query<Entity, With<ImageFrame>>,
+) {
+    let targets = if !selected.is_empty() {
+        let mut selected = selected.iter().collect::<Vec<_>>();
+        selected.sort_by_key(|&(_, order)| order.copied());
+        selected.into_iter().map(|(entity, _)| entity).collect()
+    } else if command.scope() == MenuScope::Frame {
+        info!("{} needs a selection", command.label());
+        return;
+    } else {
+        frames.iter().collect()
+    };
+    commands.run_system_cached_with(run_menu_command, (command, targets));
+}
+
+/// Types into [`CommandPalette`]. Up and Down move the highlight, Page Up and Page Down move
+/// it by a page, Enter runs the highlighted command and Escape closes the palette.
+fn listen_command_palette_input(
+    mut commands: Commands,
+    mut events: EventReader<KeyboardInput>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    palette: Option<ResMut<CommandPalette>>,
+) {
+    let Some(mut palette) = palette else {
+        events.clear();
+        return;
+    };
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                run_command_palette(&mut commands, &palette);
+                return;
+            }
+            Key::Escape => {
+                close_command_palette(&mut commands);
+                return;
+            }
+            Key::ArrowUp => palette.move_selection(-1),
+            Key::ArrowDown => palette.move_selection(1),
+            Key::PageUp => palette.move_selection(-(PALETTE_ROWS as isize)),
+            Key::PageDown => palette.move_selection(PALETTE_ROWS as isize),
+            key => {
+                // Such as the shortcut opening the palette
+                let (ctrl, _, alt) = modifiers(&keyboard_input);
+                if matches!(key, Key::Character(_)) && (ctrl || alt) {
+                    continue;
+                }
+                if edit_text(&mut palette.query, key, |_| true) {
+                    palette.selected = 0;
+                    palette.scroll = 0;
+                }
+            }
+        }
+    }
+}
+
+fn update_command_palette_panel(
+    palette: Option<Res<CommandPalette>>,
+    key_bindings: Res<KeyBindings>,
+    mut panel: Single<&mut Node, (With<CommandPalettePanel>, Without<CommandPaletteRow>)>,
+    field: Single<&Children, With<CommandPaletteField>>,
+    mut texts: Query<&mut Text, Without<CommandPaletteRow>>,
+    mut rows: Query<(
+        &CommandPaletteRow,
+        &mut Text,
+        &mut Node,
+        &mut BackgroundColor,
+        &Children,
+    )>,
+    mut spans: Query<&mut TextSpan>,
+) {
+    let Some(palette) = palette else {
+        show_panel(&mut panel, false);
+        return;
+    };
+    show_panel(&mut panel, true);
+    let mut field_texts = texts.iter_many_mut(*field);
+    while let Some(mut text) = field_texts.fetch_next() {
+        text.0 = field_text(&palette.query, true, "");
+    }
+
+    let matches = palette.matches();
+    for (row, mut text, mut node, mut background, children) in &mut rows {
+        let index = palette.scroll + row.0;
+        let Some(&command) = matches.get(index) else {
+            node.display = Display::None;
+            continue;
+        };
+        node.display = Display::Flex;
+        text.0 = command.label().to_string();
+        background.0 = if index == palette.selected {
+            Color::srgba(1.0, 1.0, 1.0, 0.2)
+        } else {
+            Color::NONE
+        };
+
+        let binding = match command {
+            PaletteCommand::Action(action) => Some(key_bindings.get(action)),
+            PaletteCommand::Menu(_) => None,
+        };
+        if let Some(mut span) = children.first().and_then(|&span| spans.get_mut(span).ok()) {
+            span.0 = match binding {
+                Some(binding) if !binding.is_unbound() => format!("  {binding}"),
+                _ => String::new(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_palette_matches() {
+        assert!(fuzzy_score("rdo", "Redo").is_some());
+        assert!(fuzzy_score("odr", "Redo").is_none());
+        // Word starts score higher
+        assert!(fuzzy_score("sl", "Save Layout") > fuzzy_score("sl", "Scale Down"));
+
+        let palette = CommandPalette {
+            query: "tog grid".to_string(),
+            ..default()
+        };
+        assert_eq!(
+            palette.matches().first(),
+            Some(&PaletteCommand::Action(Action::ToggleGridSnap))
+        );
+        let palette = CommandPalette {
+            query: "export".to_string(),
+            ..default()
+        };
+        assert_eq!(
+            palette.matches().first(),
+            Some(&PaletteCommand::Menu(MenuCommand::ExportFrames))
+        );
+
+        // Everything but held modifiers and the palette itself when empty
+        let all = CommandPalette::default().matches();
+        assert!(all.contains(&PaletteCommand::Action(Action::Undo)));
+        assert!(all.contains(&PaletteCommand::Menu(MenuCommand::Organize)));
+        assert!(!all.contains(&PaletteCommand::Action(Action::AddToSelection)));
+        assert!(!all.contains(&PaletteCommand::Action(Action::CommandPalette)));
+        // Every context menu command, once
+        for command in MenuCommand::ALL {
+            let listed = match command.action() {
+                Some(action) => PaletteCommand::Action(action),
+                None => PaletteCommand::Menu(command),
+            };
+            assert!(all.contains(&listed), "{command:?}");
+        }
+    }
+
+    #[test]
+    fn test_command_palette_scroll() {
+        let mut palette = CommandPalette::default();
+        let count = 20;
+
+        // Scrolls to keep the highlight in view
+        palette.select(PALETTE_ROWS, count);
+        assert_eq!((palette.selected, palette.scroll), (PALETTE_ROWS, 1));
+        palette.select(100, count);
+        assert_eq!((palette.selected, palette.scroll), (19, 12));
+        palette.select(5, count);
+        assert_eq!((palette.selected, palette.scroll), (5, 5));
+
+        // Moves the highlight along when it would scroll out of view
+        palette.scroll_by(-1, count);
+        assert_eq!((palette.selected, palette.scroll), (5, 4));
+        palette.scroll_by(3, count);
+        assert_eq!((palette.selected, palette.scroll), (7, 7));
+        palette.scroll_by(100, count);
+        assert_eq!((palette.selected, palette.scroll), (12, 12));
+        palette.scroll_by(-100, count);
+        assert_eq!((palette.selected, palette.scroll), (7, 0));
+    }
+}
@@ -0,0 +1,318 @@
+//! Prompts for unsaved changes and crash recovery, the progress of imports and the result of
+//! exports.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowCloseRequested},
+};
+
+use crate::{
+    canvas::{export, import, layout},
+    despawn::SafeDespawn,
+    modal::{ModalSource, close_modal, open_modal},
+    observe_component::Observe,
+};
+
+use super::widget::{button, show_panel, spawn_dialog_panel, spawn_prompt};
+
+pub struct PromptPlugin;
+
+impl Plugin for PromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_import_progress_panel.run_if(resource_changed_or_removed::<import::ImportQueue>),
+        )
+        .add_systems(
+            Update,
+            update_export_summary_panel
+                .run_if(resource_changed_or_removed::<export::ExportSummary>),
+        )
+        .add_systems(Update, on_window_close_requested);
+    }
+}
+
+/// Prompt shown when closing the primary window with unsaved changes.
+#[derive(Component)]
+struct ExitPrompt;
+
+pub fn setup_exit_prompt(world: &mut World) {
+    let row = spawn_prompt(
+        world,
+        "ExitPrompt",
+        ExitPrompt,
+        "Save changes to the layout before closing?",
+        false,
+    );
+
+    world.spawn((
+        ChildOf(row),
+        button(world, "Save"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>,
+             mut commands: Commands,
+             mut prompt: Single<&mut Node, With<ExitPrompt>>| {
+                trigger.propagate(false);
+                close_exit_prompt(&mut commands, &mut prompt);
+                commands.queue(|world: &mut World| {
+                    if layout::save_layout_file(world) {
+                        close_primary_window(world);
+                    }
+                });
+            },
+        ),
+    ));
+    world.spawn((
+        ChildOf(row),
+        button(world, "Don't Save"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                trigger.propagate(false);
+                commands.queue(close_primary_window);
+            },
+        ),
+    ));
+    world.spawn((
+        ChildOf(row),
+        button(world, "Cancel"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>,
+             mut commands: Commands,
+             mut prompt: Single<&mut Node, With<ExitPrompt>>| {
+                trigger.propagate(false);
+                close_exit_prompt(&mut commands, &mut prompt);
+            },
+        ),
+    ));
+}
+
+fn close_exit_prompt(commands: &mut Commands, prompt: &mut Mut<Node>) {
+    show_panel(prompt, false);
+    commands.queue(close_modal(ModalSource::ExitPrompt));
+}
+
+/// Despawning the primary window exits the app.
+fn close_primary_window(world: &mut World) {
+    let windows = world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for window in windows {
+        world.safe_despawn(window);
+    }
+}
+
+/// Closes windows on request, unless closing the primary window would lose unsaved changes.
+fn on_window_close_requested(
+    mut commands: Commands,
+    mut events: EventReader<WindowCloseRequested>,
+    dirty: Res<layout::Dirty>,
+    primary_window: Query<(), With<PrimaryWindow>>,
+    mut prompt: Single<&mut Node, With<ExitPrompt>>,
+) {
+    for event in events.read() {
+        if dirty.0 && primary_window.contains(event.window) {
+            show_panel(&mut prompt, true);
+            commands.queue(open_modal(ModalSource::ExitPrompt));
+        } else {
+            commands.safe_despawn(event.window);
+        }
+    }
+}
+
+/// Prompt to restore the layout auto-saved by a session that didn't exit cleanly.
+#[derive(Component)]
+struct RecoveryPrompt;
+
+pub fn setup_recovery_prompt(world: &mut World) {
+    if !world.resource::<layout::AutoSave>().has_recovery() {
+        return;
+    }
+
+    let row = spawn_prompt(
+        world,
+        "RecoveryPrompt",
+        RecoveryPrompt,
+        "Restore the layout auto-saved before the last exit?",
+        true,
+    );
+
+    world.spawn((
+        ChildOf(row),
+        button(world, "Restore"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>,
+             mut commands: Commands,
+             auto_save: Res<layout::AutoSave>,
+             prompt: Single<Entity, With<RecoveryPrompt>>| {
+                trigger.propagate(false);
+                commands.run_system_cached_with(layout::load_layout, auto_save.path.clone());
+                commands.safe_despawn(*prompt);
+            },
+        ),
+    ));
+    world.spawn((
+        ChildOf(row),
+        button(world, "Discard"),
+        Observe::new(
+            |mut trigger: Trigger<Pointer<Click>>,
+             mut commands: Commands,
+             auto_save: Res<layout::AutoSave>,
+             prompt: Single<Entity, With<RecoveryPrompt>>| {
+                trigger.propagate(false);
+                if let Err(error) = std::fs::remove_file(&auto_save.path) {
+                    warn!("Failed to remove {}: {error}", auto_save.path.display());
+                }
+                commands.safe_despawn(*prompt);
+            },
+        ),
+    ));
+}
+
+/// Progress of the [`import::ImportQueue`] in progress, with a button to cancel it.
+#[derive(Component)]
+struct ImportProgressPanel;
+
+/// Text of [`ImportProgressPanel`].
+#[derive(Component)]
+struct ImportProgressText;
+
+pub fn setup_import_progress_panel(world: &mut World) {
+    let panel = spawn_dialog_panel(world, "ImportProgressPanel", ImportProgressPanel, false);
+
+    world.spawn((
+        ChildOf(panel),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            (
+                ImportProgressText,
+                Text::default(),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+            ),
+            (
+                button(world, "Cancel"),
+                Observe::new(
+                    |mut trigger: Trigger<Pointer<Click>>,
+                     queue: Option<ResMut<import::ImportQueue>>| {
+                        trigger.propagate(false);
+                        if let Some(mut queue) = queue {
+                            queue.cancel();
+                        }
+                    },
+                ),
+            )
+        ],
+    ));
+}
+
+fn update_import_progress_panel(
+    queue: Option<Res<import::ImportQueue>>,
+    mut panel: Single<&mut Node, With<ImportProgressPanel>>,
+    mut text: Single<&mut Text, With<ImportProgressText>>,
+) {
+    let Some(queue) = queue else {
+        show_panel(&mut panel, false);
+        return;
+    };
+    show_panel(&mut panel, true);
+    let (done, total) = queue.progress();
+    text.0 = format!("Importing {done} / {total}");
+}
+
+/// Result of the last export, shown while [`export::ExportSummary`] is present.
+#[derive(Component)]
+struct ExportSummaryPanel;
+
+/// Text of [`ExportSummaryPanel`].
+#[derive(Component)]
+struct ExportSummaryText;
+
+pub fn setup_export_summary_panel(world: &mut World) {
+    let panel = spawn_dialog_panel(world, "ExportSummaryPanel", ExportSummaryPanel, false);
+
+    world.spawn((
+        ChildOf(panel),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            (
+                ExportSummaryText,
+                Text::default(),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+            ),
+            (
+                button(world, "OK"),
+                Observe::new(
+                    |mut trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        trigger.propagate(false);
+                        commands.remove_resource::<export::ExportSummary>();
+                    },
+                ),
+            )
+        ],
+    ));
+}
+
+fn update_export_summary_panel(
+    summary: Option<Res<export::ExportSummary>>,
+    mut panel: Single<&mut Node, With<ExportSummaryPanel>>,
+    mut text: Single<&mut Text, With<ExportSummaryText>>,
+) {
+    let Some(summary) = summary else {
+        show_panel(&mut panel, false);
+        return;
+    };
+    show_panel(&mut panel, true);
+    text.0 = summary.message();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modal::ModalActive;
+
+    use super::*;
+
+    #[test]
+    fn test_close_request_prompt() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowCloseRequested>>();
+        world.insert_resource(layout::Dirty(true));
+        let prompt = world
+            .spawn((
+                ExitPrompt,
+                Node {
+                    display: Display::None,
+                    ..default()
+                },
+            ))
+            .id();
+        let window = world.spawn(PrimaryWindow).id();
+        let close = |world: &mut World| {
+            world.send_event(WindowCloseRequested { window });
+            world.run_system_cached(on_window_close_requested).unwrap();
+        };
+
+        // Asks first while there are unsaved changes
+        close(&mut world);
+        assert!(world.get_entity(window).is_ok());
+        assert_eq!(world.get::<Node>(prompt).unwrap().display, Display::Flex);
+        assert!(world.contains_resource::<ModalActive>());
+
+        world.resource_mut::<layout::Dirty>().0 = false;
+        close(&mut world);
+        assert!(world.get_entity(window).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Building blocks shared by menus, dialogs and prompts.
+//!
+//! Dialogs and prompts are spawned into the [`DialogLayer`], which centers them in the window
+//! and stacks them when several are open at once. Hidden panels there use
+//! [`Display::None`] so that they don't take up room in the stack.
+
+use bevy::{input::keyboard::Key, prelude::*};
+
+use crate::observe_component::Observe;
+
+/// Sliced image behind menus and panels.
+#[derive(Resource, Clone)]
+pub struct PanelBackground(pub ImageNode);
+
+/// Full-window node centering dialogs and prompts, stacked from the top.
+#[derive(Component)]
+pub struct DialogLayer;
+
+pub fn setup_dialog_layer(world: &mut World) {
+    world.spawn((
+        Name::new("DialogLayer"),
+        DialogLayer,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        // Only the panels take clicks
+        Pickable::IGNORE,
+    ));
+}
+
+/// Spawns a column panel in the [`DialogLayer`], shown if `shown`. Clicks on the panel don't
+/// reach the canvas.
+pub fn spawn_dialog_panel(
+    world: &mut World,
+    name: &'static str,
+    bundle: impl Bundle,
+    shown: bool,
+) -> Entity {
+    let layer = world
+        .query_filtered::<Entity, With<DialogLayer>>()
+        .single(world)
+        .expect("dialog layer should be set up before panels");
+    let background = world.resource::<PanelBackground>().0.clone();
+
+    world
+        .spawn((
+            Name::new(name),
+            bundle,
+            ChildOf(layer),
+            Node {
+                display: display(shown),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            background,
+            Observe::new(|mut trigger: Trigger<Pointer<Click>>| {
+                trigger.propagate(false);
+            }),
+        ))
+        .id()
+}
+
+/// Spawns a prompt with `message` and returns the row to spawn its buttons in.
+pub fn spawn_prompt(
+    world: &mut World,
+    name: &'static str,
+    bundle: impl Bundle,
+    message: &str,
+    shown: bool,
+) -> Entity {
+    let panel = spawn_dialog_panel(world, name, bundle, shown);
+    world.spawn((
+        ChildOf(panel),
+        Text::new(message),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+    ));
+    world
+        .spawn((
+            ChildOf(panel),
+            Node {
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+fn display(shown: bool) -> Display {
+    if shown { Display::Flex } else { Display::None }
+}
+
+/// Shows or hides a panel in the [`DialogLayer`].
+pub fn show_panel(node: &mut Mut<Node>, shown: bool) {
+    let display = display(shown);
+    if node.display != display {
+        node.display = display;
+    }
+}
+
+/// Box of a single-line text field. Its [`Text`] is the only child.
+pub fn text_field(width: f32) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(width),
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        children![(
+            Text::default(),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+        )],
+    )
+}
+
+/// Text shown in a field: `value` with a cursor at the end when focused, or `placeholder`
+/// when empty, after the cursor when focused.
+pub fn field_text(value: &str, focused: bool, placeholder: &str) -> String {
+    match (value.is_empty() && !placeholder.is_empty(), focused) {
+        (true, true) => format!("_ {placeholder}"),
+        (true, false) => placeholder.to_string(),
+        (false, true) => format!("{value}_"),
+        (false, false) => value.to_string(),
+    }
+}
+
+/// Applies a key press to the text of a field, keeping the characters `accepts`. Returns
+/// whether the key edits text.
+pub fn edit_text(text: &mut String, key: &Key, accepts: impl Fn(char) -> bool) -> bool {
+    match key {
+        Key::Backspace => {
+            text.pop();
+        }
+        Key::Space if accepts(' ') => text.push(' '),
+        Key::Character(typed) => text.extend(typed.chars().filter(|&c| accepts(c))),
+        _ => return false,
+    }
+    true
+}
+
+/// Create a button with the given label.
+pub fn button(world: &World, label: &str) -> impl Bundle {
+    let assets = world.resource::<AssetServer>();
+    let button_normal = assets.load("images/tile_0015.png");
+    let button_pressed = assets.load("images/tile_0016.png");
+
+    (
+        Button,
+        Node {
+            width: Val::Px(150.0),
+            height: Val::Px(35.0),
+            // horizontally center child text
+            justify_content: JustifyContent::Center,
+            // vertically center child text
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ImageNode {
+            image: button_normal.clone(),
+            image_mode: NodeImageMode::Sliced(TextureSlicer {
+                border: BorderRect::all(8.),
+                sides_scale_mode: SliceScaleMode::Tile { stretch_value: 1.0 },
+                ..default()
+            }),
+            ..default()
+        },
+        children![(
+            Text::new(label),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            TextShadow::default(),
+        )],
+        button_observers(button_normal, button_pressed),
+    )
+}
+
+/// A set of observers for a button.
+/// The button image will be changed when pressed or released.
+fn button_observers(button_normal: Handle<Image>, button_pressed: Handle<Image>) -> impl Bundle {
+    (
+        Observe::new(
+            move |trigger: Trigger<Pointer<Pressed>>, mut image_node: Query<&mut ImageNode>| {
+                image_node.get_mut(trigger.target()).unwrap().image = button_pressed.clone();
+            },
+        ),
+        Observe::new({
+            let button_normal = button_normal.clone();
+            move |trigger: Trigger<Pointer<Released>>, mut image_node: Query<&mut ImageNode>| {
+                image_node.get_mut(trigger.target()).unwrap().image = button_normal.clone();
+            }
+        }),
+        Observe::new(
+            move |trigger: Trigger<Pointer<DragEnd>>, mut image_node: Query<&mut ImageNode>| {
+                image_node.get_mut(trigger.target()).unwrap().image = button_normal.clone();
+            },
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_text() {
+        let digits = |c: char| c.is_ascii_digit();
+        let mut text = "1".to_string();
+        assert!(edit_text(&mut text, &Key::Character("2a3".into()), digits));
+        assert_eq!(text, "123");
+        // Spaces only where accepted
+        assert!(!edit_text(&mut text, &Key::Space, digits));
+        assert!(edit_text(&mut text, &Key::Backspace, digits));
+        assert_eq!(text, "12");
+        assert!(!edit_text(&mut text, &Key::Enter, digits));
+
+        assert_eq!(field_text("12", true, ""), "12_");
+        assert_eq!(field_text("", false, "(mixed)"), "(mixed)");
+        assert_eq!(field_text("", true, "(mixed)"), "_ (mixed)");
+        assert_eq!(field_text("", true, ""), "_");
+    }
+}
@@ -5,6 +5,9 @@ use bevy::{
     window::PrimaryWindow,
 };
 
+/// Converts pointer movement in the viewport to the world space of the camera under the
+/// pointer. The camera is resolved from the location of each pointer event, so touches
+/// dragging in different viewports don't affect each other.
 #[derive(SystemParam)]
 pub struct PointerDelta<'w, 's, F: QueryFilter + 'static = ()> {
     camera: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform), F>,
@@ -42,3 +45,84 @@ fn viewport_delta_to_world_2d(
 
     Some((right * ndc_delta.x + up * ndc_delta.y).truncate())
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::AssetEvent,
+        ecs::system::RunSystemOnce,
+        render::camera::{ManualTextureViews, NormalizedRenderTarget, Viewport, camera_system},
+        window::{
+            WindowCreated, WindowRef, WindowResized, WindowResolution, WindowScaleFactorChanged,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_two_pointers_in_different_viewports() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<ManualTextureViews>();
+
+        let window = world
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(200.0, 100.0).with_scale_factor_override(1.0),
+                    ..default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+
+        // Side by side viewports, the right one zoomed out by 2
+        let camera = |x: u32, scale: f32| {
+            (
+                Camera {
+                    viewport: Some(Viewport {
+                        physical_position: UVec2::new(x, 0),
+                        physical_size: UVec2::new(100, 100),
+                        ..default()
+                    }),
+                    ..default()
+                },
+                Projection::from(OrthographicProjection::default_2d()),
+                GlobalTransform::from(Transform::from_scale(Vec3::splat(scale))),
+            )
+        };
+        let left = world.spawn(camera(0, 1.0)).id();
+        let right = world.spawn(camera(100, 2.0)).id();
+        world.run_system_cached(camera_system).unwrap();
+
+        // Touches dragging in each viewport
+        let target =
+            NormalizedRenderTarget::Window(WindowRef::Primary.normalize(Some(window)).unwrap());
+        let locations = [50.0, 150.0].map(|x| Location {
+            target: target.clone(),
+            position: Vec2::new(x, 50.0),
+        });
+
+        let delta = Vec2::new(10.0, 10.0);
+        let deltas = world
+            .run_system_once(move |pointer_delta: PointerDelta| {
+                locations
+                    .each_ref()
+                    .map(|location| pointer_delta.get_world(location, delta))
+            })
+            .unwrap();
+        // The viewport y axis points down
+        let expected = [
+            (Vec2::new(10.0, -10.0), left),
+            (Vec2::new(20.0, -20.0), right),
+        ];
+        for (delta, (expected_delta, expected_camera)) in deltas.into_iter().zip(expected) {
+            let (delta, camera) = delta.unwrap();
+            assert!(delta.abs_diff_eq(expected_delta, 1e-4), "{delta}");
+            assert_eq!(camera, expected_camera);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::math::Vec2;
+use neta::packing::{EdgeVectors, ShapePosition, calculate_centroid, fill, minkowski_sum};
+
+const GAP: f32 = 2.0;
+
+fn rect(translation: Vec2, size: Vec2, rotation: f32) -> ShapePosition {
+    ShapePosition {
+        translation,
+        edges: EdgeVectors::with_rect_size_rotation(size, rotation),
+    }
+}
+
+/// Places `shapes` one by one around the first, like organizing frames.
+fn pack(shapes: &[ShapePosition]) -> Vec<ShapePosition> {
+    let mut placed = vec![shapes[0].clone()];
+    for shape in &shapes[1..] {
+        let shape = fill(&placed, shape, GAP, Some(4)).unwrap_or_else(|| shape.clone());
+        placed.push(shape);
+    }
+    placed
+}
+
+fn shapes() -> Vec<ShapePosition> {
+    vec![
+        rect(Vec2::ZERO, Vec2::new(40.0, 30.0), 0.0),
+        rect(Vec2::new(5.0, 0.0), Vec2::new(20.0, 20.0), 0.0),
+        rect(Vec2::new(-5.0, 5.0), Vec2::new(10.0, 50.0), 0.3),
+        rect(Vec2::new(0.0, -5.0), Vec2::new(60.0, 10.0), 0.0),
+        rect(Vec2::new(10.0, 10.0), Vec2::new(15.0, 15.0), -0.8),
+    ]
+}
+
+#[test]
+fn test_pack_without_overlap() {
+    let packed = pack(&shapes());
+
+    assert_eq!(packed.len(), 5);
+    for (i, a) in packed.iter().enumerate() {
+        for b in &packed[i + 1..] {
+            assert!(!a.is_overlapping(b), "{a:?} overlaps {b:?}");
+        }
+    }
+    // The first shape is where it was
+    assert_eq!(packed[0].translation, Vec2::ZERO);
+}
+
+#[test]
+fn test_pack_is_deterministic() {
+    let first = pack(&shapes());
+    let second = pack(&shapes());
+
+    for (a, b) in first.iter().zip(&second) {
+        assert_eq!(a.translation, b.translation);
+        assert_eq!(a.vertices(), b.vertices());
+    }
+}
+
+#[test]
+fn test_minkowski_sum_of_rects() {
+    let a = EdgeVectors::with_rect_size_rotation(Vec2::new(4.0, 3.0), 0.0);
+    let b = EdgeVectors::with_rect_size_rotation(Vec2::new(2.0, 1.0), 0.0);
+
+    let sum = minkowski_sum(&a, &b);
+    let vertices = ShapePosition {
+        translation: Vec2::ZERO,
+        edges: sum,
+    }
+    .vertices();
+
+    // A 6x4 rectangle centered on the translation
+    assert_eq!(calculate_centroid(&vertices), Vec2::ZERO);
+    let (min, max) = vertices
+        .iter()
+        .fold((Vec2::INFINITY, Vec2::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    assert_eq!(max - min, Vec2::new(6.0, 4.0));
+}